@@ -2,10 +2,11 @@ use serde::{Deserialize, Serialize};
 use std::fs::{self, OpenOptions};
 use std::io::Write;
 use std::path::PathBuf;
-use std::process::Command;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use crate::cache_monitor::format_size;
+use crate::command_runner::{CommandRunner, SystemCommandRunner, PRIVILEGED_TIMEOUT, QUICK_TIMEOUT};
+use crate::i18n::{tr, tr_count, Msg};
 
 // ============================================================================
 // SAFETY: Hardcoded cache path - NEVER accept user input for paths
@@ -76,10 +77,21 @@ pub struct DeletionItem {
     pub is_directory: bool,
 }
 
+/// How a clean may interact with the user
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CleanMode {
+    /// User-initiated - may show an admin password prompt
+    Interactive,
+    /// Background auto-clean - never prompts; privileged steps are skipped
+    /// and reported via `CleanResult::requires_password`
+    Unattended,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum CleanError {
     SafetyViolation(String),
     PermissionDenied(String),
+    PrivilegesRequired(String),
     DaemonKillFailed(String),
     CacheNotFound(String),
     RemovalFailed(String),
@@ -91,6 +103,7 @@ impl std::fmt::Display for CleanError {
         match self {
             CleanError::SafetyViolation(msg) => write!(f, "SAFETY VIOLATION: {}", msg),
             CleanError::PermissionDenied(msg) => write!(f, "Permission denied: {}", msg),
+            CleanError::PrivilegesRequired(msg) => write!(f, "Administrator privileges required: {}", msg),
             CleanError::DaemonKillFailed(msg) => write!(f, "Failed to stop daemon: {}", msg),
             CleanError::CacheNotFound(msg) => write!(f, "Cache not found: {}", msg),
             CleanError::RemovalFailed(msg) => write!(f, "Failed to remove cache: {}", msg),
@@ -132,6 +145,11 @@ fn log_deletion(message: &str) {
     }
 }
 
+/// Record a monitor event (missed schedule windows, clock jumps) alongside the deletions
+pub fn log_event(message: &str) {
+    log_deletion(message);
+}
+
 fn chrono_format_now() -> String {
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -174,19 +192,21 @@ fn current_timestamp() -> u64 {
 // ============================================================================
 
 /// Stop the coresymbolicationd daemon
-fn stop_daemon() -> Result<(), CleanError> {
-    let result = Command::new("killall")
-        .arg("-9")
-        .arg("coresymbolicationd")
-        .output();
+/// Falls back to an admin prompt only in interactive mode
+fn stop_daemon(runner: &dyn CommandRunner, mode: CleanMode) -> Result<(), CleanError> {
+    let result = runner.run("killall", &["-9", "coresymbolicationd"], QUICK_TIMEOUT);
 
     match result {
         Ok(output) => {
-            if output.status.success() || output.status.code() == Some(1) {
+            if output.success() || output.code == Some(1) {
                 log_deletion("Stopped coresymbolicationd daemon");
                 Ok(())
+            } else if mode == CleanMode::Unattended {
+                Err(CleanError::PrivilegesRequired(
+                    "Stopping coresymbolicationd needs administrator approval".to_string(),
+                ))
             } else {
-                stop_daemon_with_privileges()
+                stop_daemon_with_privileges(runner)
             }
         }
         Err(e) => Err(CleanError::DaemonKillFailed(e.to_string())),
@@ -194,26 +214,20 @@ fn stop_daemon() -> Result<(), CleanError> {
 }
 
 /// Stop daemon using osascript with administrator privileges
-fn stop_daemon_with_privileges() -> Result<(), CleanError> {
+fn stop_daemon_with_privileges(runner: &dyn CommandRunner) -> Result<(), CleanError> {
     let script = r#"do shell script "killall -9 coresymbolicationd 2>/dev/null || true" with administrator privileges"#;
 
-    let result = Command::new("osascript")
-        .arg("-e")
-        .arg(script)
-        .output();
+    let result = runner.run("osascript", &["-e", script], PRIVILEGED_TIMEOUT);
 
     match result {
         Ok(output) => {
-            if output.status.success() {
+            if output.success() {
                 log_deletion("Stopped coresymbolicationd daemon (with privileges)");
                 Ok(())
+            } else if output.stderr.contains("User canceled") {
+                Err(CleanError::PermissionDenied("User cancelled authentication".to_string()))
             } else {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                if stderr.contains("User canceled") {
-                    Err(CleanError::PermissionDenied("User cancelled authentication".to_string()))
-                } else {
-                    Err(CleanError::DaemonKillFailed(stderr.to_string()))
-                }
+                Err(CleanError::DaemonKillFailed(output.stderr))
             }
         }
         Err(e) => Err(CleanError::DaemonKillFailed(e.to_string())),
@@ -315,6 +329,16 @@ fn get_dir_size(path: &std::path::Path) -> u64 {
 /// - Logs every deletion with timestamp
 /// - No wildcards or recursive deletes outside the exact folder
 pub fn clean_cache(dry_run: bool) -> Result<CleanResult, CleanError> {
+    clean_cache_with(&SystemCommandRunner, dry_run, CleanMode::Interactive)
+}
+
+/// Clean the cache from a background task without ever prompting for a password
+pub fn clean_cache_unattended() -> Result<CleanResult, CleanError> {
+    clean_cache_with(&SystemCommandRunner, false, CleanMode::Unattended)
+}
+
+/// Clean the cache, running external tools through the given runner
+pub fn clean_cache_with(runner: &dyn CommandRunner, dry_run: bool, mode: CleanMode) -> Result<CleanResult, CleanError> {
     let cache_path = get_safe_cache_path();
 
     // SAFETY CHECK 1: Verify path is exactly what we expect
@@ -332,10 +356,10 @@ pub fn clean_cache(dry_run: bool) -> Result<CleanResult, CleanError> {
         return Ok(CleanResult {
             success: true,
             bytes_freed: 0,
-            bytes_freed_display: "0 B".to_string(),
+            bytes_freed_display: format_size(0),
             files_removed: 0,
             timestamp: current_timestamp(),
-            message: "Cache directory does not exist - nothing to clean".to_string(),
+            message: tr(Msg::CleanNothing),
             requires_password: false,
             was_dry_run: dry_run,
             items_found: Vec::new(),
@@ -364,11 +388,7 @@ pub fn clean_cache(dry_run: bool) -> Result<CleanResult, CleanError> {
             bytes_freed_display: format_size(total_size),
             files_removed: total_count,
             timestamp: current_timestamp(),
-            message: format!(
-                "Dry run: would delete {} ({} items)",
-                format_size(total_size),
-                total_count
-            ),
+            message: tr_count(Msg::CleanDryRun, total_count, &[("size", &format_size(total_size))]),
             requires_password: false,
             was_dry_run: true,
             items_found: items,
@@ -376,8 +396,11 @@ pub fn clean_cache(dry_run: bool) -> Result<CleanResult, CleanError> {
     }
 
     // ACTUAL DELETION - Stop daemon first
-    if let Err(e) = stop_daemon() {
+    let mut requires_password = false;
+    if let Err(e) = stop_daemon(runner, mode) {
         log_deletion(&format!("Warning: Could not stop daemon: {}", e));
+        // Record that escalation was needed but didn't happen
+        requires_password = matches!(e, CleanError::PrivilegesRequired(_) | CleanError::PermissionDenied(_));
         // Continue anyway - daemon might not be running
     }
 
@@ -454,12 +477,12 @@ pub fn clean_cache(dry_run: bool) -> Result<CleanResult, CleanError> {
         bytes_freed_display: format_size(bytes_freed),
         files_removed,
         timestamp: current_timestamp(),
-        message: format!(
-            "Cleaned {} ({} items)",
-            format_size(bytes_freed),
-            files_removed
+        message: tr_count(
+            if requires_password { Msg::CleanDoneNeedsApproval } else { Msg::CleanDone },
+            files_removed,
+            &[("size", &format_size(bytes_freed))],
         ),
-        requires_password: false,
+        requires_password,
         was_dry_run: false,
         items_found: items,
     })
@@ -467,30 +490,32 @@ pub fn clean_cache(dry_run: bool) -> Result<CleanResult, CleanError> {
 
 /// Reindex Spotlight (optional, helps clean orphaned APFS document IDs)
 pub fn reindex_spotlight() -> Result<(), CleanError> {
+    reindex_spotlight_with(&SystemCommandRunner)
+}
+
+/// Reindex Spotlight using the given runner
+pub fn reindex_spotlight_with(runner: &dyn CommandRunner) -> Result<(), CleanError> {
     log_deletion("Requesting Spotlight reindex");
 
     let script = r#"do shell script "mdutil -E /" with administrator privileges"#;
 
-    let result = Command::new("osascript")
-        .arg("-e")
-        .arg(script)
-        .output();
+    let result = runner.run("osascript", &["-e", script], PRIVILEGED_TIMEOUT);
 
     match result {
         Ok(output) => {
-            if output.status.success() {
+            if output.success() {
                 log_deletion("Spotlight reindex initiated");
                 Ok(())
+            } else if output.stderr.contains("User canceled") {
+                Err(CleanError::PermissionDenied("User cancelled authentication".to_string()))
             } else {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                if stderr.contains("User canceled") {
-                    Err(CleanError::PermissionDenied("User cancelled authentication".to_string()))
-                } else {
-                    Ok(()) // Don't fail on this - it's optional
-                }
+                Ok(()) // Don't fail on this - it's optional
             }
         }
-        Err(_) => Ok(()), // Don't fail on this - it's optional
+        Err(e) => {
+            log_deletion(&format!("Spotlight reindex failed: {}", e));
+            Ok(()) // Don't fail on this - it's optional
+        }
     }
 }
 
@@ -498,3 +523,71 @@ pub fn reindex_spotlight() -> Result<(), CleanError> {
 pub fn get_log_file_path() -> String {
     get_log_path().to_string_lossy().to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_runner::{CommandError, CommandOutput, RecordingRunner};
+
+    #[test]
+    fn test_stop_daemon_without_privileges() {
+        let runner = RecordingRunner::new().respond("killall", Ok(CommandOutput::ok("")));
+        assert!(stop_daemon(&runner, CleanMode::Interactive).is_ok());
+        assert_eq!(runner.programs(), vec!["killall"]);
+
+        // Exit code 1 means no matching process - nothing to stop
+        let runner = RecordingRunner::new().respond("killall", Ok(CommandOutput::failed(1, "")));
+        assert!(stop_daemon(&runner, CleanMode::Interactive).is_ok());
+        assert_eq!(runner.programs(), vec!["killall"]);
+    }
+
+    #[test]
+    fn test_stop_daemon_falls_back_to_privileges() {
+        let runner = RecordingRunner::new()
+            .respond("killall", Ok(CommandOutput::failed(2, "Operation not permitted")))
+            .respond("osascript", Ok(CommandOutput::ok("")));
+        assert!(stop_daemon(&runner, CleanMode::Interactive).is_ok());
+        assert_eq!(runner.programs(), vec!["killall", "osascript"]);
+        assert_eq!(runner.calls()[1].timeout, PRIVILEGED_TIMEOUT);
+    }
+
+    #[test]
+    fn test_stop_daemon_privileged_failures() {
+        let runner = RecordingRunner::new()
+            .respond("killall", Ok(CommandOutput::failed(2, "")))
+            .respond("osascript", Ok(CommandOutput::failed(1, "execution error: User canceled. (-128)")));
+        assert!(matches!(stop_daemon(&runner, CleanMode::Interactive), Err(CleanError::PermissionDenied(_))));
+
+        let runner = RecordingRunner::new()
+            .respond("killall", Ok(CommandOutput::failed(2, "")))
+            .respond(
+                "osascript",
+                Err(CommandError::TimedOut { program: "osascript".to_string(), timeout: PRIVILEGED_TIMEOUT }),
+            );
+        assert!(matches!(stop_daemon(&runner, CleanMode::Interactive), Err(CleanError::DaemonKillFailed(_))));
+    }
+
+    #[test]
+    fn test_stop_daemon_unattended_never_prompts() {
+        let runner = RecordingRunner::new()
+            .respond("killall", Ok(CommandOutput::failed(2, "Operation not permitted")))
+            .respond("osascript", Ok(CommandOutput::ok("")));
+        assert!(matches!(
+            stop_daemon(&runner, CleanMode::Unattended),
+            Err(CleanError::PrivilegesRequired(_))
+        ));
+        assert_eq!(runner.programs(), vec!["killall"]);
+
+        let runner = RecordingRunner::new().respond("killall", Ok(CommandOutput::ok("")));
+        assert!(stop_daemon(&runner, CleanMode::Unattended).is_ok());
+    }
+
+    #[test]
+    fn test_reindex_spotlight_is_optional() {
+        let runner = RecordingRunner::new().respond("osascript", Ok(CommandOutput::failed(1, "some error")));
+        assert!(reindex_spotlight_with(&runner).is_ok());
+
+        let runner = RecordingRunner::new().respond("osascript", Ok(CommandOutput::failed(1, "User canceled.")));
+        assert!(matches!(reindex_spotlight_with(&runner), Err(CleanError::PermissionDenied(_))));
+    }
+}
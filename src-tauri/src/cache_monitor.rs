@@ -1,9 +1,19 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
-use std::process::Command;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use crate::command_runner::{CommandRunner, SystemCommandRunner, QUICK_TIMEOUT};
+use crate::process_info::{ProcessInfo, ProcessInfoSource};
+use crate::scheduler::{AutoCleanStatus, MonitorStatus};
+use crate::units::{format_size_in, size_units};
+
+/// Process name of the symbolication daemon
+pub const DAEMON_NAME: &str = "coresymbolicationd";
+
+/// Maximum number of daemon restarts kept in history
+const MAX_RESTART_HISTORY: usize = 50;
+
 /// Cache status thresholds in bytes
 pub const WARNING_THRESHOLD: u64 = 5 * 1024 * 1024 * 1024; // 5GB
 pub const CRITICAL_THRESHOLD: u64 = 10 * 1024 * 1024 * 1024; // 10GB
@@ -44,6 +54,12 @@ pub struct CacheStatus {
     pub exists: bool,
     pub file_count: u64,
     pub last_checked: u64,
+    /// Auto-clean state (filled in by the app, default for raw scans)
+    #[serde(default)]
+    pub auto_clean: AutoCleanStatus,
+    /// Monitor loop state (filled in by the app, default for raw scans)
+    #[serde(default)]
+    pub monitor: MonitorStatus,
 }
 
 impl Default for CacheStatus {
@@ -56,6 +72,8 @@ impl Default for CacheStatus {
             exists: false,
             file_count: 0,
             last_checked: current_timestamp(),
+            auto_clean: AutoCleanStatus::default(),
+            monitor: MonitorStatus::default(),
         }
     }
 }
@@ -82,48 +100,9 @@ fn current_timestamp() -> u64 {
         .as_secs()
 }
 
-/// Format bytes into human-readable string
-/// Shows GB when ≥1000 MB, MB for 1-999, KB for small, B for tiny
+/// Format bytes into human-readable string, in the units chosen in settings
 pub fn format_size(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-    const GB_THRESHOLD: u64 = 1000 * MB; // Show GB at 1000 MB, not 1024
-
-    if bytes >= GB_THRESHOLD {
-        // Show as GB, omit decimal if whole number
-        let value = bytes as f64 / GB as f64;
-        let rounded = (value * 10.0).round() / 10.0; // Round to 1 decimal
-        if (rounded - rounded.floor()).abs() < 0.01 {
-            format!("{:.0} GB", rounded) // Whole number: "1 GB", "2 GB"
-        } else {
-            format!("{:.1} GB", rounded) // Decimal: "1.5 GB", "2.3 GB"
-        }
-    } else if bytes >= MB {
-        // Show as MB (1-999 range)
-        let value = bytes / MB;
-        format!("{} MB", value)
-    } else if bytes >= KB {
-        // Show as KB
-        format!("{} KB", bytes / KB)
-    } else if bytes > 0 {
-        format!("{} B", bytes)
-    } else {
-        "0 B".to_string()
-    }
-}
-
-/// Add commas to numbers (e.g., 1250 -> "1,250")
-fn format_with_commas(n: u64) -> String {
-    let s = n.to_string();
-    let mut result = String::new();
-    for (i, c) in s.chars().rev().enumerate() {
-        if i > 0 && i % 3 == 0 {
-            result.push(',');
-        }
-        result.push(c);
-    }
-    result.chars().rev().collect()
+    format_size_in(bytes, size_units())
 }
 
 /// Calculate directory size recursively
@@ -174,19 +153,98 @@ pub fn get_cache_status() -> CacheStatus {
         exists,
         file_count,
         last_checked: current_timestamp(),
+        auto_clean: AutoCleanStatus::default(),
+        monitor: MonitorStatus::default(),
     }
 }
 
 /// Check if coresymbolicationd daemon is running
 pub fn is_daemon_running() -> bool {
-    let output = Command::new("pgrep")
-        .arg("-x")
-        .arg("coresymbolicationd")
-        .output();
-
-    match output {
-        Ok(result) => result.status.success(),
-        Err(_) => false,
+    is_daemon_running_with(&SystemCommandRunner)
+}
+
+/// Check if coresymbolicationd daemon is running using the given runner
+pub fn is_daemon_running_with(runner: &dyn CommandRunner) -> bool {
+    runner
+        .run("pgrep", &["-x", DAEMON_NAME], QUICK_TIMEOUT)
+        .map(|output| output.success())
+        .unwrap_or(false)
+}
+
+/// A daemon restart observed by the monitor (PID changed or daemon reappeared)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DaemonRestart {
+    /// When the restart was observed
+    pub timestamp: u64,
+    /// PID before the restart (None if the daemon had been seen stopped)
+    pub old_pid: Option<u32>,
+    pub new_pid: u32,
+}
+
+/// Daemon details returned to the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaemonInfo {
+    pub running: bool,
+    /// Process details (None when the daemon isn't running)
+    pub process: Option<ProcessInfo>,
+    /// Restarts observed since the app launched, oldest first
+    pub restarts: Vec<DaemonRestart>,
+}
+
+/// Tracks the daemon PID across monitor ticks to build a restart history
+#[derive(Debug, Default)]
+pub struct DaemonTracker {
+    last_pid: Option<u32>,
+    /// Whether the daemon has been observed at least once
+    seen: bool,
+    restarts: Vec<DaemonRestart>,
+}
+
+impl DaemonTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the latest observation, returning a restart if one happened
+    /// The first sighting of the daemon is not counted as a restart
+    pub fn observe(&mut self, process: Option<&ProcessInfo>, now: u64) -> Option<DaemonRestart> {
+        let new_pid = process.map(|p| p.pid);
+        let restart = match new_pid {
+            Some(pid) if self.seen && self.last_pid != Some(pid) => Some(DaemonRestart {
+                timestamp: now,
+                old_pid: self.last_pid,
+                new_pid: pid,
+            }),
+            _ => None,
+        };
+
+        if let Some(r) = &restart {
+            if self.restarts.len() >= MAX_RESTART_HISTORY {
+                self.restarts.remove(0);
+            }
+            self.restarts.push(r.clone());
+        }
+
+        self.seen |= new_pid.is_some();
+        self.last_pid = new_pid;
+        restart
+    }
+
+    /// Restarts observed so far, oldest first
+    pub fn restarts(&self) -> &[DaemonRestart] {
+        &self.restarts
+    }
+}
+
+/// Look up the daemon, record the observation and return its details
+pub fn read_daemon_info(source: &dyn ProcessInfoSource, tracker: &mut DaemonTracker) -> DaemonInfo {
+    let process = source.find_process(DAEMON_NAME);
+    tracker.observe(process.as_ref(), current_timestamp());
+
+    DaemonInfo {
+        running: process.is_some(),
+        process,
+        restarts: tracker.restarts().to_vec(),
     }
 }
 
@@ -231,6 +289,8 @@ pub fn get_simulated_status(size_bytes: u64) -> CacheStatus {
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default()
             .as_secs(),
+        auto_clean: AutoCleanStatus::default(),
+        monitor: MonitorStatus::default(),
     }
 }
 
@@ -240,16 +300,14 @@ mod tests {
 
     #[test]
     fn test_format_size() {
+        // IEC unless settings choose otherwise
         assert_eq!(format_size(0), "0 B");
         assert_eq!(format_size(500), "500 B");
-        assert_eq!(format_size(1024), "1 KB");
-        assert_eq!(format_size(1048576), "1 MB");
-        assert_eq!(format_size(500 * 1024 * 1024), "500 MB");
-        assert_eq!(format_size(999 * 1024 * 1024), "999 MB");
-        // 1000 MB should show as GB (threshold)
-        assert_eq!(format_size(1000 * 1024 * 1024), "1 GB");
-        assert_eq!(format_size(1073741824), "1 GB"); // 1024 MB = 1 GB
-        assert_eq!(format_size(5368709120), "5 GB");
+        assert_eq!(format_size(1024), "1 KiB");
+        assert_eq!(format_size(1048576), "1 MiB");
+        assert_eq!(format_size(500 * 1024 * 1024), "500 MiB");
+        assert_eq!(format_size(1073741824), "1 GiB");
+        assert_eq!(format_size(5368709120), "5 GiB");
     }
 
     #[test]
@@ -261,4 +319,61 @@ mod tests {
         assert_eq!(CacheState::from_size(10 * 1024 * 1024 * 1024), CacheState::Critical);
         assert_eq!(CacheState::from_size(15 * 1024 * 1024 * 1024), CacheState::Critical);
     }
+
+    #[test]
+    fn test_is_daemon_running_with() {
+        use crate::command_runner::{CommandError, CommandOutput, RecordingRunner};
+
+        let runner = RecordingRunner::new()
+            .respond("pgrep", Ok(CommandOutput::ok("123\n")))
+            .respond("pgrep", Ok(CommandOutput::failed(1, "")))
+            .respond("pgrep", Err(CommandError::TimedOut { program: "pgrep".to_string(), timeout: QUICK_TIMEOUT }));
+
+        assert!(is_daemon_running_with(&runner));
+        assert!(!is_daemon_running_with(&runner));
+        assert!(!is_daemon_running_with(&runner));
+        assert_eq!(runner.calls()[0].args, vec!["-x", "coresymbolicationd"]);
+    }
+
+    fn process(pid: u32) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            start_time: 0,
+            uptime_secs: 0,
+            resident_bytes: 0,
+            cpu_time_secs: 0.0,
+            cpu_percent: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_daemon_tracker_records_restarts() {
+        let mut tracker = DaemonTracker::new();
+
+        // Not running at launch, then first sighting - not a restart
+        assert_eq!(tracker.observe(None, 100), None);
+        assert_eq!(tracker.observe(Some(&process(10)), 160), None);
+        assert_eq!(tracker.observe(Some(&process(10)), 220), None);
+
+        // PID changed between ticks
+        let restart = tracker.observe(Some(&process(11)), 280).unwrap();
+        assert_eq!(restart, DaemonRestart { timestamp: 280, old_pid: Some(10), new_pid: 11 });
+
+        // Stopped, then relaunched by launchd
+        assert_eq!(tracker.observe(None, 340), None);
+        let restart = tracker.observe(Some(&process(12)), 400).unwrap();
+        assert_eq!(restart.old_pid, None);
+
+        assert_eq!(tracker.restarts().len(), 2);
+    }
+
+    #[test]
+    fn test_daemon_tracker_history_is_bounded() {
+        let mut tracker = DaemonTracker::new();
+        for pid in 0..(MAX_RESTART_HISTORY as u32 + 10) {
+            tracker.observe(Some(&process(pid)), pid as u64);
+        }
+        assert_eq!(tracker.restarts().len(), MAX_RESTART_HISTORY);
+        assert_eq!(tracker.restarts().last().unwrap().new_pid, MAX_RESTART_HISTORY as u32 + 9);
+    }
 }
@@ -0,0 +1,279 @@
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Timeout for quick process queries (pgrep, killall, open)
+pub const QUICK_TIMEOUT: Duration = Duration::from_secs(5);
+/// Timeout for notification helpers (terminal-notifier, osascript notifications)
+pub const NOTIFY_TIMEOUT: Duration = Duration::from_secs(10);
+/// Timeout for notifications that wait for a button click
+/// The notification is left to expire on its own once this passes
+pub const ACTION_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+/// Timeout for commands that show an admin password prompt
+/// Long enough for a user to type their password, short enough that an
+/// abandoned dialog doesn't block the caller forever
+pub const PRIVILEGED_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How often a running child is polled for exit
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Captured result of a command that ran to completion
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandOutput {
+    /// Exit code (None if terminated by a signal)
+    pub code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl CommandOutput {
+    /// Create a successful output with the given stdout
+    #[cfg(test)]
+    pub fn ok(stdout: &str) -> Self {
+        Self {
+            code: Some(0),
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+        }
+    }
+
+    /// Create a failed output with the given exit code and stderr
+    #[cfg(test)]
+    pub fn failed(code: i32, stderr: &str) -> Self {
+        Self {
+            code: Some(code),
+            stdout: String::new(),
+            stderr: stderr.to_string(),
+        }
+    }
+
+    /// Whether the command exited with status 0
+    pub fn success(&self) -> bool {
+        self.code == Some(0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandError {
+    /// The program could not be started (missing binary, permissions)
+    SpawnFailed { program: String, message: String },
+    /// The program did not exit within its timeout and was killed
+    TimedOut { program: String, timeout: Duration },
+    /// Waiting on or reading from the child failed
+    Io { program: String, message: String },
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommandError::SpawnFailed { program, message } => {
+                write!(f, "Failed to start {}: {}", program, message)
+            }
+            CommandError::TimedOut { program, timeout } => {
+                write!(f, "{} timed out after {}s", program, timeout.as_secs())
+            }
+            CommandError::Io { program, message } => {
+                write!(f, "I/O error running {}: {}", program, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+// ============================================================================
+// Runner trait
+// ============================================================================
+
+/// Runs external tools on behalf of the app
+///
+/// All shelling out (pgrep, killall, osascript, mdutil, terminal-notifier, open)
+/// goes through this trait so every call has a timeout and so tests can swap in
+/// a fake instead of touching the real system.
+pub trait CommandRunner: Send + Sync {
+    /// Run a program to completion, capturing stdout/stderr
+    /// The child is killed if it is still running after `timeout`
+    fn run(&self, program: &str, args: &[&str], timeout: Duration) -> Result<CommandOutput, CommandError>;
+}
+
+/// Runner backed by `std::process::Command`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemCommandRunner;
+
+impl CommandRunner for SystemCommandRunner {
+    fn run(&self, program: &str, args: &[&str], timeout: Duration) -> Result<CommandOutput, CommandError> {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| CommandError::SpawnFailed {
+                program: program.to_string(),
+                message: e.to_string(),
+            })?;
+
+        // Drain pipes on separate threads so a chatty child can't fill the
+        // pipe buffer and block before we get a chance to wait on it
+        let stdout_reader = child.stdout.take().map(spawn_reader);
+        let stderr_reader = child.stderr.take().map(spawn_reader);
+
+        let deadline = Instant::now() + timeout;
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) if Instant::now() >= deadline => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(CommandError::TimedOut {
+                        program: program.to_string(),
+                        timeout,
+                    });
+                }
+                Ok(None) => thread::sleep(POLL_INTERVAL),
+                Err(e) => {
+                    let _ = child.kill();
+                    return Err(CommandError::Io {
+                        program: program.to_string(),
+                        message: e.to_string(),
+                    });
+                }
+            }
+        };
+
+        Ok(CommandOutput {
+            code: status.code(),
+            stdout: stdout_reader.map(join_reader).unwrap_or_default(),
+            stderr: stderr_reader.map(join_reader).unwrap_or_default(),
+        })
+    }
+}
+
+fn spawn_reader<R: Read + Send + 'static>(mut pipe: R) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = pipe.read_to_end(&mut buf);
+        String::from_utf8_lossy(&buf).to_string()
+    })
+}
+
+fn join_reader(handle: thread::JoinHandle<String>) -> String {
+    handle.join().unwrap_or_default()
+}
+
+// ============================================================================
+// Test fake
+// ============================================================================
+
+/// A single call captured by `RecordingRunner`
+#[cfg(test)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedCall {
+    pub program: String,
+    pub args: Vec<String>,
+    pub timeout: Duration,
+}
+
+/// Fake runner that records every call and replays scripted responses
+/// Responses are matched by program name in FIFO order; unscripted programs
+/// behave as if the binary doesn't exist
+#[cfg(test)]
+#[derive(Default)]
+pub struct RecordingRunner {
+    responses: std::sync::Mutex<Vec<(String, Result<CommandOutput, CommandError>)>>,
+    calls: std::sync::Mutex<Vec<RecordedCall>>,
+}
+
+#[cfg(test)]
+impl RecordingRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a response for the next call to `program`
+    pub fn respond(self, program: &str, response: Result<CommandOutput, CommandError>) -> Self {
+        self.responses
+            .lock()
+            .unwrap()
+            .push((program.to_string(), response));
+        self
+    }
+
+    /// All calls made so far, in order
+    pub fn calls(&self) -> Vec<RecordedCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// Program names of all calls made so far, in order
+    pub fn programs(&self) -> Vec<String> {
+        self.calls().into_iter().map(|c| c.program).collect()
+    }
+}
+
+#[cfg(test)]
+impl CommandRunner for RecordingRunner {
+    fn run(&self, program: &str, args: &[&str], timeout: Duration) -> Result<CommandOutput, CommandError> {
+        self.calls.lock().unwrap().push(RecordedCall {
+            program: program.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            timeout,
+        });
+
+        let mut responses = self.responses.lock().unwrap();
+        match responses.iter().position(|(p, _)| p == program) {
+            Some(index) => responses.remove(index).1,
+            None => Err(CommandError::SpawnFailed {
+                program: program.to_string(),
+                message: "not scripted".to_string(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_system_runner_captures_output() {
+        let output = SystemCommandRunner
+            .run("sh", &["-c", "echo out; echo err >&2; exit 3"], QUICK_TIMEOUT)
+            .unwrap();
+        assert_eq!(output.code, Some(3));
+        assert_eq!(output.stdout.trim(), "out");
+        assert_eq!(output.stderr.trim(), "err");
+        assert!(!output.success());
+    }
+
+    #[test]
+    fn test_system_runner_times_out() {
+        let started = Instant::now();
+        let result = SystemCommandRunner.run("sleep", &["5"], Duration::from_millis(100));
+        assert!(matches!(result, Err(CommandError::TimedOut { .. })));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_system_runner_missing_binary() {
+        let result = SystemCommandRunner.run("symbolsweep-no-such-binary", &[], QUICK_TIMEOUT);
+        assert!(matches!(result, Err(CommandError::SpawnFailed { .. })));
+    }
+
+    #[test]
+    fn test_recording_runner_replays_in_order() {
+        let runner = RecordingRunner::new()
+            .respond("pgrep", Ok(CommandOutput::failed(1, "")))
+            .respond("pgrep", Ok(CommandOutput::ok("42\n")));
+
+        assert!(!runner.run("pgrep", &["-x", "foo"], QUICK_TIMEOUT).unwrap().success());
+        assert!(runner.run("pgrep", &["-x", "foo"], QUICK_TIMEOUT).unwrap().success());
+        assert!(runner.run("killall", &[], QUICK_TIMEOUT).is_err());
+        assert_eq!(runner.programs(), vec!["pgrep", "pgrep", "killall"]);
+        assert_eq!(runner.calls()[0].args, vec!["-x", "foo"]);
+    }
+}
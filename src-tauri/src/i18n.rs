@@ -0,0 +1,653 @@
+use chrono::{TimeZone, Utc};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::command_runner::{CommandRunner, SystemCommandRunner, QUICK_TIMEOUT};
+use crate::units::DurationStyle;
+
+/// Environment variables checked for a locale, most specific first
+const LOCALE_ENV: [&str; 3] = ["LC_ALL", "LC_MESSAGES", "LANG"];
+
+/// Locale user-facing text is produced in (a `Locale` as u8), set at startup
+static LOCALE: AtomicU8 = AtomicU8::new(Locale::En as u8);
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Languages with a message catalog
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+}
+
+/// Every piece of text the Rust side shows to the user
+///
+/// Templates fill `{name}` placeholders; plural messages hold "one|other" forms
+/// and fill `{n}` with the count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Msg {
+    // Durations
+    Seconds,
+    Minutes,
+    Hours,
+    Days,
+    SecondsShort,
+    MinutesShort,
+    HoursShort,
+    DaysShort,
+    Ago,
+    InFuture,
+    Never,
+    Now,
+    /// chrono format for an absolute local time
+    DateTime,
+    // Tray
+    TrayShow,
+    TrayCleanNow,
+    TrayApprove,
+    TraySnoozeMenu,
+    TraySnoozeFor,
+    TrayResumeAlerts,
+    TraySwitchProfile,
+    TrayQuit,
+    TrayTooltipIdle,
+    TrayTooltip,
+    TrayNextClean,
+    TrayLastClean,
+    Files,
+    StateNormal,
+    StateWarning,
+    StateCritical,
+    // Notification actions
+    ActionCleanNow,
+    ActionOpenWindow,
+    ActionSnooze,
+    // Notifications
+    TitleCleaned,
+    CleanedBody,
+    TitleApproval,
+    ApprovalBody,
+    TitlePaused,
+    PausedBody,
+    TitleRunaway,
+    RunawayCpu,
+    RunawayGrowth,
+    TitleWarning,
+    WarningAlert,
+    WarningReminder,
+    TitleCritical,
+    CriticalAlert,
+    CriticalReminder,
+    TitleQuietSummary,
+    QuietSummaryMore,
+    TitleRecovered,
+    RecoveryRestored,
+    RecoveryReset,
+    TitleTest,
+    TestBody,
+    // Clean results
+    CleanNothing,
+    CleanDryRun,
+    CleanDone,
+    CleanDoneNeedsApproval,
+    CleanFreedNothing,
+    // Auto-clean deferrals
+    DeferredBusy,
+    DeferredOnBattery,
+    DeferredNotIdle,
+    DeferredQuietHours,
+    DeferredPresenting,
+    DeferredWakeGrace,
+}
+
+// ============================================================================
+// Catalogs
+// ============================================================================
+
+fn english(msg: Msg) -> &'static str {
+    match msg {
+        Msg::Seconds => "{n} second|{n} seconds",
+        Msg::Minutes => "{n} minute|{n} minutes",
+        Msg::Hours => "{n} hour|{n} hours",
+        Msg::Days => "{n} day|{n} days",
+        Msg::SecondsShort => "{n}s",
+        Msg::MinutesShort => "{n}m",
+        Msg::HoursShort => "{n}h",
+        Msg::DaysShort => "{n}d",
+        Msg::Ago => "{duration} ago",
+        Msg::InFuture => "in {duration}",
+        Msg::Never => "Never",
+        Msg::Now => "now",
+        Msg::DateTime => "%a %b %-d, %H:%M",
+        Msg::TrayShow => "Show SymbolSweep",
+        Msg::TrayCleanNow => "Clean Cache Now",
+        Msg::TrayApprove => "Needs Your Approval: Restart Daemon…",
+        Msg::TraySnoozeMenu => "Snooze Cache Alerts",
+        Msg::TraySnoozeFor => "For {duration}",
+        Msg::TrayResumeAlerts => "Resume Alerts",
+        Msg::TraySwitchProfile => "Switch Profile",
+        Msg::TrayQuit => "Quit",
+        Msg::TrayTooltipIdle => "SymbolSweep - Cache Monitor",
+        Msg::TrayTooltip => "SymbolSweep\n{size} - {files}\nStatus: {state}",
+        Msg::TrayNextClean => "Next clean: {relative} ({time})",
+        Msg::TrayLastClean => "Last clean: {relative}",
+        Msg::Files => "{n} file|{n} files",
+        Msg::StateNormal => "Normal",
+        Msg::StateWarning => "Warning ({size}+)",
+        Msg::StateCritical => "Critical ({size}+)",
+        Msg::ActionCleanNow => "Clean Now",
+        Msg::ActionOpenWindow => "Open SymbolSweep",
+        Msg::ActionSnooze => "Snooze {duration}",
+        Msg::TitleCleaned => "SymbolSweep",
+        Msg::CleanedBody => "Cleaned {size} of cache",
+        Msg::TitleApproval => "SymbolSweep - Approval Needed",
+        Msg::ApprovalBody => {
+            "Auto-clean couldn't stop coresymbolicationd without your password. Approve it from the menu bar."
+        }
+        Msg::TitlePaused => "SymbolSweep - Auto-clean Paused",
+        Msg::PausedBody => "Auto-clean paused after {n} failure: {error}|Auto-clean paused after {n} failures: {error}",
+        Msg::TitleRunaway => "SymbolSweep - Runaway Daemon",
+        Msg::RunawayCpu => "{daemon} has used {percent}% CPU for {duration}",
+        Msg::RunawayGrowth => "Cache grew by {size} in {duration}",
+        Msg::TitleWarning => "SymbolSweep - Warning",
+        Msg::WarningAlert => "Cache at {size} - consider cleaning soon",
+        Msg::WarningReminder => "Cache still at {size} - consider cleaning soon",
+        Msg::TitleCritical => "SymbolSweep - Critical",
+        Msg::CriticalAlert => "Cache at {size} - cleaning recommended!",
+        Msg::CriticalReminder => "Cache still at {size} - cleaning recommended!",
+        Msg::TitleQuietSummary => "SymbolSweep - {n} Update While Quiet|SymbolSweep - {n} Updates While Quiet",
+        Msg::QuietSummaryMore => "…and {n} more",
+        Msg::TitleRecovered => "SymbolSweep - Settings Recovered",
+        Msg::RecoveryRestored => "Settings were unreadable ({reason}); restored the last good copy",
+        Msg::RecoveryReset => "Settings were unreadable ({reason}); reset to defaults",
+        Msg::TitleTest => "SymbolSweep Test",
+        Msg::TestBody => "Notifications are working!",
+        Msg::CleanNothing => "Cache directory does not exist - nothing to clean",
+        Msg::CleanDryRun => "Dry run: would delete {size} ({n} item)|Dry run: would delete {size} ({n} items)",
+        Msg::CleanDone => "Cleaned {size} ({n} item)|Cleaned {size} ({n} items)",
+        Msg::CleanDoneNeedsApproval => {
+            "Cleaned {size} ({n} item) - stopping the daemon needs your approval|\
+             Cleaned {size} ({n} items) - stopping the daemon needs your approval"
+        }
+        Msg::CleanFreedNothing => "Clean freed nothing ({message})",
+        Msg::DeferredBusy => "waiting for {names} to exit",
+        Msg::DeferredOnBattery => "waiting for AC power",
+        Msg::DeferredNotIdle => "waiting for {duration} without input",
+        Msg::DeferredQuietHours => "waiting for quiet hours to end",
+        Msg::DeferredPresenting => "waiting for the presentation to end",
+        Msg::DeferredWakeGrace => "waiting a few minutes after wake",
+    }
+}
+
+fn german(msg: Msg) -> &'static str {
+    match msg {
+        Msg::Seconds => "{n} Sekunde|{n} Sekunden",
+        Msg::Minutes => "{n} Minute|{n} Minuten",
+        Msg::Hours => "{n} Stunde|{n} Stunden",
+        Msg::Days => "{n} Tag|{n} Tage",
+        Msg::SecondsShort => "{n} Sek.",
+        Msg::MinutesShort => "{n} Min.",
+        Msg::HoursShort => "{n} Std.",
+        Msg::DaysShort => "{n} T.",
+        Msg::Ago => "{duration} her",
+        // "in" would need the dative ("in 3 Tagen"); "noch" keeps the forms above
+        Msg::InFuture => "noch {duration}",
+        Msg::Never => "Nie",
+        Msg::Now => "jetzt",
+        Msg::DateTime => "%d.%m. %H:%M",
+        Msg::TrayShow => "SymbolSweep anzeigen",
+        Msg::TrayCleanNow => "Cache jetzt bereinigen",
+        Msg::TrayApprove => "Deine Zustimmung nötig: Daemon neu starten…",
+        Msg::TraySnoozeMenu => "Cache-Warnungen pausieren",
+        Msg::TraySnoozeFor => "Für {duration}",
+        Msg::TrayResumeAlerts => "Warnungen fortsetzen",
+        Msg::TraySwitchProfile => "Profil wechseln",
+        Msg::TrayQuit => "Beenden",
+        Msg::TrayTooltipIdle => "SymbolSweep - Cache-Überwachung",
+        Msg::TrayTooltip => "SymbolSweep\n{size} - {files}\nStatus: {state}",
+        Msg::TrayNextClean => "Nächste Bereinigung: {relative} ({time})",
+        Msg::TrayLastClean => "Letzte Bereinigung: {relative}",
+        Msg::Files => "{n} Datei|{n} Dateien",
+        Msg::StateNormal => "Normal",
+        Msg::StateWarning => "Warnung (ab {size})",
+        Msg::StateCritical => "Kritisch (ab {size})",
+        Msg::ActionCleanNow => "Jetzt bereinigen",
+        Msg::ActionOpenWindow => "SymbolSweep öffnen",
+        Msg::ActionSnooze => "{duration} pausieren",
+        Msg::TitleCleaned => "SymbolSweep",
+        Msg::CleanedBody => "{size} Cache bereinigt",
+        Msg::TitleApproval => "SymbolSweep - Zustimmung nötig",
+        Msg::ApprovalBody => {
+            "Die automatische Bereinigung konnte coresymbolicationd ohne dein Passwort nicht stoppen. \
+             Stimme über die Menüleiste zu."
+        }
+        Msg::TitlePaused => "SymbolSweep - Automatische Bereinigung pausiert",
+        Msg::PausedBody => {
+            "Automatische Bereinigung nach {n} Fehler pausiert: {error}|\
+             Automatische Bereinigung nach {n} Fehlern pausiert: {error}"
+        }
+        Msg::TitleRunaway => "SymbolSweep - Daemon außer Kontrolle",
+        Msg::RunawayCpu => "{daemon} nutzt seit {duration} {percent} % CPU",
+        Msg::RunawayGrowth => "Cache ist in {duration} um {size} gewachsen",
+        Msg::TitleWarning => "SymbolSweep - Warnung",
+        Msg::WarningAlert => "Cache bei {size} - bald bereinigen",
+        Msg::WarningReminder => "Cache noch bei {size} - bald bereinigen",
+        Msg::TitleCritical => "SymbolSweep - Kritisch",
+        Msg::CriticalAlert => "Cache bei {size} - Bereinigung empfohlen!",
+        Msg::CriticalReminder => "Cache noch bei {size} - Bereinigung empfohlen!",
+        Msg::TitleQuietSummary => {
+            "SymbolSweep - {n} Meldung während der Ruhezeit|SymbolSweep - {n} Meldungen während der Ruhezeit"
+        }
+        Msg::QuietSummaryMore => "…und {n} weitere",
+        Msg::TitleRecovered => "SymbolSweep - Einstellungen wiederhergestellt",
+        Msg::RecoveryRestored => "Einstellungen waren unlesbar ({reason}); letzte gute Kopie wiederhergestellt",
+        Msg::RecoveryReset => "Einstellungen waren unlesbar ({reason}); auf Standardwerte zurückgesetzt",
+        Msg::TitleTest => "SymbolSweep-Test",
+        Msg::TestBody => "Mitteilungen funktionieren!",
+        Msg::CleanNothing => "Cache-Ordner existiert nicht - nichts zu bereinigen",
+        Msg::CleanDryRun => {
+            "Probelauf: würde {size} löschen ({n} Objekt)|Probelauf: würde {size} löschen ({n} Objekte)"
+        }
+        Msg::CleanDone => "{size} bereinigt ({n} Objekt)|{size} bereinigt ({n} Objekte)",
+        Msg::CleanDoneNeedsApproval => {
+            "{size} bereinigt ({n} Objekt) - zum Stoppen des Daemons ist deine Zustimmung nötig|\
+             {size} bereinigt ({n} Objekte) - zum Stoppen des Daemons ist deine Zustimmung nötig"
+        }
+        Msg::CleanFreedNothing => "Bereinigung hat nichts freigegeben ({message})",
+        Msg::DeferredBusy => "wartet, bis {names} beendet ist",
+        Msg::DeferredOnBattery => "wartet auf Netzstrom",
+        Msg::DeferredNotIdle => "wartet auf {duration} ohne Eingabe",
+        Msg::DeferredQuietHours => "wartet auf das Ende der Ruhezeit",
+        Msg::DeferredPresenting => "wartet auf das Ende der Präsentation",
+        Msg::DeferredWakeGrace => "wartet nach dem Aufwachen einige Minuten",
+    }
+}
+
+/// Template for `msg` in `locale`
+fn template(locale: Locale, msg: Msg) -> &'static str {
+    match locale {
+        Locale::En => english(msg),
+        Locale::De => german(msg),
+    }
+}
+
+// ============================================================================
+// Locale
+// ============================================================================
+
+impl Locale {
+    /// Locale for a POSIX or BCP 47 tag ("de_DE.UTF-8", "de-AT", "en"), if there's a catalog for it
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag
+            .trim()
+            .trim_matches('"')
+            .split(['_', '-', '.', '@'])
+            .next()?
+            .to_lowercase();
+        match language.as_str() {
+            "en" => Some(Locale::En),
+            "de" => Some(Locale::De),
+            _ => None,
+        }
+    }
+
+    fn decimal_separator(self) -> char {
+        match self {
+            Locale::En => '.',
+            Locale::De => ',',
+        }
+    }
+
+    fn thousands_separator(self) -> char {
+        match self {
+            Locale::En => ',',
+            Locale::De => '.',
+        }
+    }
+
+    /// Index of the plural form to use for `n` (English and German: one, other)
+    fn plural_form(self, n: u64) -> usize {
+        usize::from(n != 1)
+    }
+}
+
+/// Produce user-facing text in `locale` from now on
+pub fn set_locale(locale: Locale) {
+    LOCALE.store(locale as u8, Ordering::Relaxed);
+}
+
+/// Locale user-facing text is currently produced in
+pub fn locale() -> Locale {
+    match LOCALE.load(Ordering::Relaxed) {
+        x if x == Locale::De as u8 => Locale::De,
+        _ => Locale::En,
+    }
+}
+
+/// First supported locale among `tags`, English if there isn't one
+/// "C" and "POSIX" are skipped, so they don't mask a preferred language further down.
+pub fn detect_locale<'a>(tags: impl IntoIterator<Item = &'a str>) -> Locale {
+    tags.into_iter().find_map(Locale::from_tag).unwrap_or_default()
+}
+
+/// Locale from the environment, then the macOS preferred languages
+/// Apps launched from Finder have no LANG, so the system list is usually what decides.
+pub fn system_locale() -> Locale {
+    let env: Vec<String> = LOCALE_ENV
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .filter(|value| !value.is_empty())
+        .collect();
+    let preferred = preferred_languages(&SystemCommandRunner);
+    detect_locale(env.iter().chain(preferred.iter()).map(String::as_str))
+}
+
+/// Languages from `defaults read -g AppleLanguages`, most preferred first
+fn preferred_languages(runner: &dyn CommandRunner) -> Vec<String> {
+    // (\n    "de-DE",\n    "en-US"\n)
+    match runner.run("defaults", &["read", "-g", "AppleLanguages"], QUICK_TIMEOUT) {
+        Ok(output) if output.success() => output
+            .stdout
+            .lines()
+            .map(|line| line.trim().trim_end_matches(',').trim_matches('"').to_string())
+            .filter(|line| !line.is_empty() && line != "(" && line != ")")
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+// ============================================================================
+// Formatting
+// ============================================================================
+
+/// Text for `msg` in the current locale
+pub fn tr(msg: Msg) -> String {
+    template(locale(), msg).to_string()
+}
+
+/// Text for `msg` in the current locale with `{name}` placeholders filled in
+pub fn tr_with(msg: Msg, args: &[(&str, &str)]) -> String {
+    fill(template(locale(), msg), args)
+}
+
+/// Plural-aware text for `count` of something in the current locale
+pub fn tr_count(msg: Msg, count: u64, args: &[(&str, &str)]) -> String {
+    tr_count_in(locale(), msg, count, args)
+}
+
+fn tr_count_in(locale: Locale, msg: Msg, count: u64, args: &[(&str, &str)]) -> String {
+    let forms: Vec<&str> = template(locale, msg).split('|').collect();
+    let form = forms[locale.plural_form(count).min(forms.len() - 1)];
+    let n = format_number_in(count, locale);
+    let mut args = args.to_vec();
+    args.push(("n", &n));
+    fill(form, &args)
+}
+
+/// Replace `{name}` placeholders in one pass, so a value containing braces is left as is
+fn fill(template: &str, args: &[(&str, &str)]) -> String {
+    let mut text = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        text.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let value = after
+            .find('}')
+            .and_then(|close| args.iter().find(|(name, _)| *name == &after[..close]).map(|arg| (arg.1, close)));
+        match value {
+            Some((value, close)) => {
+                text.push_str(value);
+                rest = &after[close + 1..];
+            }
+            None => {
+                text.push('{');
+                rest = after;
+            }
+        }
+    }
+    text.push_str(rest);
+    text
+}
+
+/// Whole number with the locale's digit grouping, e.g. "1,250" or "1.250"
+pub fn format_number(n: u64) -> String {
+    format_number_in(n, locale())
+}
+
+fn format_number_in(n: u64, locale: Locale) -> String {
+    let digits = n.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(locale.thousands_separator());
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// `number` (as formatted by Rust, e.g. "1.5") with the locale's decimal separator
+pub fn localize_decimal(number: &str) -> String {
+    localize_decimal_in(number, locale())
+}
+
+fn localize_decimal_in(number: &str, locale: Locale) -> String {
+    number.replace('.', &locale.decimal_separator().to_string())
+}
+
+/// Duration in `style`, e.g. "1 day", "1 day 23 hours", "1d 23h" or "1 Tag 23 Stunden"
+/// Whole units only; the rest is dropped.
+pub fn format_duration_in(secs: u64, style: DurationStyle, locale: Locale) -> String {
+    // (seconds per unit, full message, abbreviated message)
+    const UNITS: [(u64, Msg, Msg); 4] = [
+        (86400, Msg::Days, Msg::DaysShort),
+        (3600, Msg::Hours, Msg::HoursShort),
+        (60, Msg::Minutes, Msg::MinutesShort),
+        (1, Msg::Seconds, Msg::SecondsShort),
+    ];
+    let largest = UNITS.iter().position(|(unit, _, _)| secs >= *unit).unwrap_or(UNITS.len() - 1);
+    let shown = match style {
+        DurationStyle::Single => &UNITS[largest..=largest],
+        DurationStyle::Compound | DurationStyle::Abbreviated => &UNITS[largest..(largest + 2).min(UNITS.len())],
+    };
+
+    let mut parts = Vec::new();
+    let mut rest = secs;
+    for (i, (unit, full, short)) in shown.iter().enumerate() {
+        let count = rest / unit;
+        rest %= unit;
+        // "2 hours", not "2 hours 0 minutes"
+        if count == 0 && i > 0 {
+            continue;
+        }
+        let msg = if style == DurationStyle::Abbreviated { *short } else { *full };
+        parts.push(tr_count_in(locale, msg, count, &[]));
+    }
+    parts.join(" ")
+}
+
+/// `timestamp` relative to `now`: "in 3 hours", "1 day 23 hours ago" or "now"
+pub fn format_relative_in(timestamp: u64, now: u64, style: DurationStyle, locale: Locale) -> String {
+    if timestamp == now {
+        return template(locale, Msg::Now).to_string();
+    }
+    let (msg, secs) = if timestamp > now {
+        (Msg::InFuture, timestamp - now)
+    } else {
+        (Msg::Ago, now - timestamp)
+    };
+    fill(
+        template(locale, msg),
+        &[("duration", &format_duration_in(secs, style, locale))],
+    )
+}
+
+/// `timestamp` relative to now, in the current locale and duration style
+pub fn format_relative(timestamp: u64) -> String {
+    format_relative_in(timestamp, Utc::now().timestamp() as u64, crate::units::duration_style(), locale())
+}
+
+/// Absolute time of `timestamp` in `tz`, e.g. "Wed Oct 18, 12:30" or "18.10. 12:30"
+pub fn format_timestamp_in<Tz: TimeZone>(timestamp: u64, tz: &Tz, locale: Locale) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    tz.timestamp_opt(timestamp as i64, 0)
+        .single()
+        .map(|time| time.format(template(locale, Msg::DateTime)).to_string())
+        .unwrap_or_default()
+}
+
+/// Absolute time of `timestamp` in the local time zone and current locale
+pub fn format_local_time(timestamp: u64) -> String {
+    format_timestamp_in(timestamp, &chrono::Local, locale())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_runner::{CommandOutput, RecordingRunner};
+
+    #[test]
+    fn test_locale_from_tag() {
+        assert_eq!(Locale::from_tag("de_DE.UTF-8"), Some(Locale::De));
+        assert_eq!(Locale::from_tag("de-AT"), Some(Locale::De));
+        assert_eq!(Locale::from_tag("\"en-GB\""), Some(Locale::En));
+        assert_eq!(Locale::from_tag("fr_FR"), None);
+        assert_eq!(Locale::from_tag("C"), None);
+        assert_eq!(Locale::from_tag(""), None);
+    }
+
+    #[test]
+    fn test_detect_locale_takes_first_supported_tag() {
+        assert_eq!(detect_locale(["C", "de-DE", "en-US"]), Locale::De);
+        assert_eq!(detect_locale(["fr_FR.UTF-8", "en-US", "de-DE"]), Locale::En);
+        // Nothing supported falls back to English
+        assert_eq!(detect_locale(["fr-FR", "ja-JP"]), Locale::En);
+        assert_eq!(detect_locale(Vec::<&str>::new()), Locale::En);
+    }
+
+    #[test]
+    fn test_preferred_languages() {
+        let runner = RecordingRunner::new().respond(
+            "defaults",
+            Ok(CommandOutput::ok("(\n    \"de-DE\",\n    \"en-US\"\n)\n")),
+        );
+        assert_eq!(preferred_languages(&runner), vec!["de-DE", "en-US"]);
+        let runner = RecordingRunner::new().respond("defaults", Ok(CommandOutput::failed(1, "does not exist")));
+        assert!(preferred_languages(&runner).is_empty());
+    }
+
+    #[test]
+    fn test_templates_come_from_the_locales_catalog() {
+        assert_eq!(template(Locale::De, Msg::TrayQuit), "Beenden");
+        assert_eq!(template(Locale::En, Msg::TrayQuit), "Quit");
+    }
+
+    #[test]
+    fn test_fill_substitutes_in_one_pass() {
+        assert_eq!(fill("{a} and {b}", &[("a", "1"), ("b", "2")]), "1 and 2");
+        // Values aren't searched for placeholders
+        assert_eq!(fill("{error}: {n}", &[("error", "bad {n}"), ("n", "3")]), "bad {n}: 3");
+        // Unknown and unclosed placeholders are kept
+        assert_eq!(fill("{other} {", &[("n", "3")]), "{other} {");
+    }
+
+    #[test]
+    fn test_plurals() {
+        assert_eq!(tr_count_in(Locale::En, Msg::Files, 1, &[]), "1 file");
+        assert_eq!(tr_count_in(Locale::En, Msg::Files, 0, &[]), "0 files");
+        assert_eq!(tr_count_in(Locale::De, Msg::Files, 1, &[]), "1 Datei");
+        assert_eq!(tr_count_in(Locale::De, Msg::Files, 1250, &[]), "1.250 Dateien");
+        assert_eq!(
+            tr_count_in(Locale::En, Msg::CleanDone, 2, &[("size", "1.5 GiB")]),
+            "Cleaned 1.5 GiB (2 items)"
+        );
+        assert_eq!(
+            tr_count_in(Locale::De, Msg::CleanDone, 1, &[("size", "1,5 GiB")]),
+            "1,5 GiB bereinigt (1 Objekt)"
+        );
+    }
+
+    #[test]
+    fn test_durations() {
+        use DurationStyle::*;
+        assert_eq!(format_duration_in(1, Single, Locale::En), "1 second");
+        assert_eq!(format_duration_in(90, Single, Locale::En), "1 minute");
+        assert_eq!(format_duration_in(3 * 3600, Single, Locale::En), "3 hours");
+        assert_eq!(format_duration_in(86400, Single, Locale::De), "1 Tag");
+        assert_eq!(format_duration_in(3 * 3600, Single, Locale::De), "3 Stunden");
+    }
+
+    #[test]
+    fn test_compound_and_abbreviated_durations() {
+        use DurationStyle::*;
+        let secs = 47 * 3600 + 59 * 60;
+        assert_eq!(format_duration_in(secs, Single, Locale::En), "1 day");
+        assert_eq!(format_duration_in(secs, Compound, Locale::En), "1 day 23 hours");
+        assert_eq!(format_duration_in(secs, Abbreviated, Locale::En), "1d 23h");
+        assert_eq!(format_duration_in(secs, Compound, Locale::De), "1 Tag 23 Stunden");
+        assert_eq!(format_duration_in(secs, Abbreviated, Locale::De), "1 T. 23 Std.");
+
+        // Zero second parts are left out
+        assert_eq!(format_duration_in(2 * 3600, Compound, Locale::En), "2 hours");
+        assert_eq!(format_duration_in(90, Compound, Locale::En), "1 minute 30 seconds");
+        assert_eq!(format_duration_in(0, Compound, Locale::En), "0 seconds");
+        assert_eq!(format_duration_in(45, Abbreviated, Locale::En), "45s");
+    }
+
+    #[test]
+    fn test_relative_times() {
+        use DurationStyle::*;
+        let now = 1_700_000_000;
+        assert_eq!(format_relative_in(now + 3 * 3600, now, Compound, Locale::En), "in 3 hours");
+        assert_eq!(format_relative_in(now - 47 * 3600, now, Compound, Locale::En), "1 day 23 hours ago");
+        assert_eq!(format_relative_in(now - 47 * 3600, now, Abbreviated, Locale::En), "1d 23h ago");
+        assert_eq!(format_relative_in(now, now, Compound, Locale::En), "now");
+        assert_eq!(format_relative_in(now + 3 * 86400, now, Single, Locale::De), "noch 3 Tage");
+        assert_eq!(format_relative_in(now - 90, now, Compound, Locale::De), "1 Minute 30 Sekunden her");
+    }
+
+    #[test]
+    fn test_absolute_times() {
+        // 2023-11-14 22:13:20 UTC, a Tuesday
+        let timestamp = 1_700_000_000;
+        assert_eq!(format_timestamp_in(timestamp, &Utc, Locale::En), "Tue Nov 14, 22:13");
+        assert_eq!(format_timestamp_in(timestamp, &Utc, Locale::De), "14.11. 22:13");
+        let berlin = chrono::FixedOffset::east_opt(3600).unwrap();
+        assert_eq!(format_timestamp_in(timestamp, &berlin, Locale::De), "14.11. 23:13");
+    }
+
+    #[test]
+    fn test_number_separators() {
+        assert_eq!(format_number_in(0, Locale::En), "0");
+        assert_eq!(format_number_in(999, Locale::En), "999");
+        assert_eq!(format_number_in(1250, Locale::En), "1,250");
+        assert_eq!(format_number_in(1_234_567, Locale::En), "1,234,567");
+        assert_eq!(format_number_in(1_234_567, Locale::De), "1.234.567");
+        assert_eq!(localize_decimal_in("1.5", Locale::De), "1,5");
+        assert_eq!(localize_decimal_in("1.5", Locale::En), "1.5");
+    }
+
+    #[test]
+    fn test_every_message_has_one_form_per_plural() {
+        for msg in [
+            Msg::Seconds,
+            Msg::Minutes,
+            Msg::Hours,
+            Msg::Days,
+            Msg::Files,
+            Msg::PausedBody,
+            Msg::TitleQuietSummary,
+            Msg::CleanDryRun,
+            Msg::CleanDone,
+            Msg::CleanDoneNeedsApproval,
+        ] {
+            for locale in [Locale::En, Locale::De] {
+                assert_eq!(template(locale, msg).split('|').count(), 2, "{:?} in {:?}", msg, locale);
+            }
+        }
+    }
+}
@@ -2,27 +2,94 @@
 
 mod cache_cleaner;
 mod cache_monitor;
+mod command_runner;
+mod i18n;
+mod monitor_engine;
+mod notification_policy;
+mod notifier;
+mod power;
+mod process_info;
+mod runaway;
+mod schedule;
 mod scheduler;
+mod settings_cli;
+mod settings_policy;
+mod settings_profiles;
+mod settings_schema;
+mod settings_store;
+mod settings_validation;
+mod settings_watcher;
 mod tray;
+mod units;
 
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use tauri::{Emitter, Manager};
+use std::thread::JoinHandle;
+use tauri::{Emitter, Manager, Runtime};
 use tauri_plugin_autostart::MacosLauncher;
 
 use cache_cleaner::{clean_cache, get_log_file_path, reindex_spotlight, CleanResult};
-use cache_monitor::{get_cache_status, get_combined_cache_status, get_simulated_status, is_daemon_running, CacheStatus};
+use cache_monitor::{get_combined_cache_status, is_daemon_running, read_daemon_info, CacheStatus, DaemonInfo, DaemonTracker};
+use i18n::{tr, Msg};
+use monitor_engine::{
+    read_status, stop_monitor, Clock, EventSink, LiveStatus, MonitorEngine, MonitorEvent, MonitorSignal,
+    SystemClock, SystemStatusProvider, UnattendedCleaner, WakeReason, SHUTDOWN_TIMEOUT,
+};
+use notification_policy::Notification;
+use notifier::NotificationAction;
 use scheduler::{time_since_last_clean, Settings};
+use settings_policy::{load_policy, Policy, PolicyStatus};
+use settings_profiles::{Profile, ProfileInfo};
+use settings_store::SettingsRecovery;
+use settings_validation::{validate, SettingsError};
+use settings_watcher::{ReloadOutcome, SettingsWatcher};
 use tray::{create_tray, send_notification, update_tray_icon};
+use units::SizeUnits;
 
 /// App state for sharing across commands
 pub struct AppState {
     pub settings: Arc<Mutex<Settings>>,
+    /// Set when settings.json was corrupt at launch and had to be recovered
+    pub settings_recovery: Mutex<Option<SettingsRecovery>>,
+    /// Fields locked by the system-wide policy file (read once at launch)
+    pub policy: Policy,
+    pub policy_status: PolicyStatus,
+    /// Daemon PID history, updated by the monitor loop
+    pub daemon_tracker: Arc<Mutex<DaemonTracker>>,
+    /// A background clean skipped a step that needs an admin password
+    pub pending_approval: Arc<AtomicBool>,
+    /// Auto-clean deferral and scan interval, updated by the monitor loop
+    pub live_status: Arc<Mutex<LiveStatus>>,
+    /// Wakes or stops the background monitor loop
+    pub monitor_signal: Arc<MonitorSignal>,
+    /// Background monitor thread, joined on exit
+    pub monitor_thread: Mutex<Option<JoinHandle<()>>>,
 }
 
 impl Default for AppState {
     fn default() -> Self {
+        let loaded = Settings::load();
+        let (policy, mut policy_status) = load_policy();
+        let mut settings = loaded.settings;
+        if let Some(reset) = policy.apply(&mut settings) {
+            eprintln!("Settings: applied managed policy ({})", policy_status.locked.join(", "));
+            for field in &reset {
+                eprintln!("Settings: {} clashed with the managed policy, using the default", field);
+            }
+            policy_status.reset = reset;
+        }
+        units::set_size_units(settings.size_units);
+        units::set_duration_style(settings.duration_style);
         Self {
-            settings: Arc::new(Mutex::new(Settings::load())),
+            settings: Arc::new(Mutex::new(settings)),
+            settings_recovery: Mutex::new(loaded.recovery),
+            policy,
+            policy_status,
+            daemon_tracker: Arc::new(Mutex::new(DaemonTracker::new())),
+            pending_approval: Arc::new(AtomicBool::new(false)),
+            live_status: Arc::new(Mutex::new(LiveStatus::default())),
+            monitor_signal: Arc::new(MonitorSignal::new()),
+            monitor_thread: Mutex::new(None),
         }
     }
 }
@@ -34,12 +101,9 @@ impl Default for AppState {
 /// Get current cache status (respects debug mode)
 #[tauri::command]
 fn get_status(state: tauri::State<AppState>) -> CacheStatus {
-    let settings = state.settings.lock().unwrap();
-    if settings.debug_mode {
-        get_simulated_status(settings.debug_simulated_size)
-    } else {
-        get_cache_status()
-    }
+    let mut status = read_status(&state.settings.lock().unwrap());
+    state.live_status.lock().unwrap().apply(&mut status);
+    status
 }
 
 /// Get combined cache status (user + system caches)
@@ -54,6 +118,14 @@ fn get_daemon_status() -> bool {
     is_daemon_running()
 }
 
+/// Get coresymbolicationd process details and observed restarts
+#[tauri::command]
+fn get_daemon_info(state: tauri::State<AppState>) -> DaemonInfo {
+    let source = process_info::default_source();
+    let mut tracker = state.daemon_tracker.lock().unwrap();
+    read_daemon_info(source.as_ref(), &mut tracker)
+}
+
 // ============================================================================
 // Tauri Commands - Cache Cleaning
 // ============================================================================
@@ -65,21 +137,7 @@ fn clean(app: tauri::AppHandle, state: tauri::State<AppState>, dry_run: bool) ->
         Ok(result) => {
             // Update last clean timestamp only if not a dry run
             if !dry_run && result.success {
-                if let Ok(mut settings) = state.settings.lock() {
-                    settings.record_clean();
-                    // Reset debug simulated size to 0 after clean (makes debug mode more realistic)
-                    if settings.debug_mode {
-                        settings.debug_simulated_size = 0;
-                        let _ = settings.save();
-                        // Notify frontend to refresh settings
-                        let _ = app.emit("settings-updated", settings.clone());
-                    }
-                }
-                // Update tray icon immediately after clean (use real status since debug size is now 0)
-                let status = get_cache_status();
-                let _ = update_tray_icon(&app, &status);
-                // Emit status update so frontend refreshes
-                let _ = app.emit("cache-status-update", &status);
+                finish_manual_clean(&app, &state, &result);
             }
             Ok(result)
         }
@@ -87,6 +145,57 @@ fn clean(app: tauri::AppHandle, state: tauri::State<AppState>, dry_run: bool) ->
     }
 }
 
+/// Record a successful user-initiated clean and refresh the tray and frontend
+fn finish_manual_clean<R: Runtime>(app: &tauri::AppHandle<R>, state: &AppState, result: &CleanResult) {
+    if let Ok(mut settings) = state.settings.lock() {
+        // A successful manual clean lifts any auto-clean backoff
+        settings.auto_clean_backoff.reset();
+        settings.record_clean();
+        // Reset debug simulated size to 0 after clean (makes debug mode more realistic)
+        if settings.debug_mode {
+            settings.debug_simulated_size = 0;
+        }
+        let _ = settings.save();
+        // Notify frontend to refresh settings
+        let _ = app.emit("settings-updated", settings.clone());
+    }
+
+    // A clean that went through clears any queued approval; queueing one is
+    // left to the unattended auto-clean path
+    if !result.requires_password {
+        set_approval_pending(app, false);
+    }
+
+    // Update tray icon immediately after clean (debug size is now 0)
+    let status = read_status(&state.settings.lock().unwrap());
+    let _ = update_tray_icon(app, &status);
+    // Emit status update so frontend refreshes
+    let _ = app.emit("cache-status-update", &status);
+
+    // Let the monitor re-evaluate against the new clean timestamp
+    state.monitor_signal.wake(WakeReason::ManualClean);
+}
+
+/// Record whether a background clean is waiting on the user's approval
+/// Returns true if the approval was newly queued
+fn set_approval_pending<R: Runtime>(app: &tauri::AppHandle<R>, pending: bool) -> bool {
+    let was_pending = app.state::<AppState>().pending_approval.swap(pending, Ordering::SeqCst);
+    if was_pending != pending {
+        let _ = tray::set_approval_pending(app, pending);
+        let _ = app.emit("approval-pending", pending);
+    }
+    pending && !was_pending
+}
+
+/// Run an interactive clean after the user approved it from the tray menu
+/// May show an admin password prompt, so call from a background thread
+pub(crate) fn run_approved_clean<R: Runtime>(app: &tauri::AppHandle<R>) {
+    match clean_cache(false) {
+        Ok(result) => finish_manual_clean(app, &app.state::<AppState>(), &result),
+        Err(e) => eprintln!("Approved clean failed: {}", e),
+    }
+}
+
 /// Get the deletion log file path
 #[tauri::command]
 fn get_log_path() -> String {
@@ -109,40 +218,264 @@ fn get_settings(state: tauri::State<AppState>) -> Settings {
     state.settings.lock().unwrap().clone()
 }
 
+/// How settings were recovered at launch, if settings.json was corrupt
+#[tauri::command]
+fn get_settings_recovery(state: tauri::State<AppState>) -> Option<SettingsRecovery> {
+    state.settings_recovery.lock().unwrap().clone()
+}
+
+/// Fields locked by the managed policy, so the frontend can disable their controls
+#[tauri::command]
+fn get_managed_policy(state: tauri::State<AppState>) -> PolicyStatus {
+    state.policy_status.clone()
+}
+
 /// Update settings
+/// Rejects invalid values and changes to policy-locked fields with field-level errors
 #[tauri::command]
-fn update_settings(app: tauri::AppHandle, state: tauri::State<AppState>, settings: Settings) -> Result<(), String> {
+fn update_settings(
+    app: tauri::AppHandle,
+    state: tauri::State<AppState>,
+    settings: Settings,
+) -> Result<(), SettingsError> {
+    state.policy.check_update(&settings)?;
+    let errors = validate(&settings);
+    if !errors.is_empty() {
+        return Err(SettingsError::Invalid { errors });
+    }
+
     let mut current = state.settings.lock().unwrap();
 
+    // Backoff, alert state and the last clean are owned by the monitor - don't let a stale frontend copy overwrite them
+    let mut settings = settings;
+    settings.keep_runtime_state(&current);
+
     // Check if launch_at_login changed
     let launch_changed = current.launch_at_login != settings.launch_at_login;
     let new_launch_value = settings.launch_at_login;
 
     *current = settings.clone();
-    current.save()?;
+    current
+        .save()
+        .map_err(|message| SettingsError::saving(&current, message))?;
+
+    settings_changed(&app, &state, &current);
+
+    // Handle launch at login change
+    if launch_changed {
+        sync_autostart(&app, new_launch_value);
+    }
+
+    Ok(())
+}
 
-    // Update tray immediately when settings change (especially debug mode)
-    let status = if settings.debug_mode {
-        get_simulated_status(settings.debug_simulated_size)
+/// Make changed settings take effect everywhere without waiting for the next check
+fn settings_changed<R: Runtime>(app: &tauri::AppHandle<R>, state: &AppState, settings: &Settings) {
+    units::set_size_units(settings.size_units);
+    units::set_duration_style(settings.duration_style);
+    let status = read_status(settings);
+    let _ = app.emit("settings-updated", settings.clone());
+    let _ = app.emit("cache-status-update", &status);
+    // Apply the new interval, thresholds and debug mode now rather than after the current wait
+    state.monitor_signal.wake(WakeReason::SettingsChanged);
+    let _ = update_tray_icon(app, &status);
+}
+
+/// Register or remove the login item to match `launch_at_login`
+fn sync_autostart<R: Runtime>(app: &tauri::AppHandle<R>, launch_at_login: bool) {
+    use tauri_plugin_autostart::ManagerExt;
+    let autostart_manager = app.autolaunch();
+    if launch_at_login {
+        let _ = autostart_manager.enable();
     } else {
-        get_cache_status()
+        let _ = autostart_manager.disable();
+    }
+}
+
+/// Apply `--set field=value` arguments, from this launch or a second launch of the app
+fn apply_cli_overrides<R: Runtime>(app: &tauri::AppHandle<R>, args: &[String]) {
+    let overrides = match settings_cli::parse_overrides(args) {
+        Ok(overrides) if overrides.is_empty() => return,
+        Ok(overrides) => overrides,
+        Err(e) => {
+            eprintln!("Settings: ignoring --set arguments: {}", e);
+            return;
+        }
     };
-    let _ = update_tray_icon(&app, &status);
 
-    // Handle launch at login change
+    let state = app.state::<AppState>();
+    let mut current = state.settings.lock().unwrap();
+    let settings = match settings_cli::apply_overrides(&current, overrides, &state.policy) {
+        Ok(settings) => settings,
+        Err(e) => {
+            eprintln!("Settings: ignoring --set arguments: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = settings.save() {
+        eprintln!("Settings: couldn't save --set arguments: {}", e);
+        return;
+    }
+    let launch_changed = current.launch_at_login != settings.launch_at_login;
+    *current = settings;
+
+    settings_changed(app, &state, &current);
     if launch_changed {
-        use tauri_plugin_autostart::ManagerExt;
-        let autostart_manager = app.autolaunch();
-        if new_launch_value {
-            let _ = autostart_manager.enable();
-        } else {
-            let _ = autostart_manager.disable();
+        sync_autostart(app, current.launch_at_login);
+    }
+}
+
+/// Poll settings.json for edits made outside the app and apply them like an in-app change
+/// Stops when the app shuts the monitor down
+fn watch_settings_file<R: Runtime>(app: tauri::AppHandle<R>) {
+    let state = app.state::<AppState>();
+    let mut watcher = SettingsWatcher::new(Settings::dir(), state.policy.clone());
+    loop {
+        std::thread::sleep(settings_watcher::POLL_INTERVAL);
+        if state.monitor_signal.is_shutting_down() {
+            return;
+        }
+        // Read outside the lock; only the merge needs it
+        let Some(content) = watcher.poll() else { continue };
+        let (outcome, launch_at_login, merged) = {
+            let mut settings = state.settings.lock().unwrap();
+            let launch_at_login = settings.launch_at_login;
+            (watcher.merge(&content, &mut settings), launch_at_login, settings.clone())
+        };
+
+        match outcome {
+            ReloadOutcome::Unchanged => {}
+            ReloadOutcome::Applied { fields } => {
+                eprintln!("Settings: reloaded {} from settings.json", fields.join(", "));
+                watcher.write_back(&content, &merged);
+                settings_changed(&app, &state, &merged);
+                if merged.launch_at_login != launch_at_login {
+                    sync_autostart(&app, merged.launch_at_login);
+                }
+            }
+            ReloadOutcome::Rejected(error) => {
+                eprintln!("Settings: ignored an edit to settings.json: {}", error);
+                let _ = app.emit("settings-reload-failed", &error);
+            }
         }
     }
+}
+
+// ============================================================================
+// Tauri Commands - Import, Export and Profiles
+// ============================================================================
+
+/// Write the portable settings to `path` (default: ~/Downloads), returning where they went
+#[tauri::command]
+fn export_settings(state: tauri::State<AppState>, path: Option<String>) -> Result<String, SettingsError> {
+    let path = path.map(std::path::PathBuf::from).unwrap_or_else(|| {
+        let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+        std::path::PathBuf::from(home).join("Downloads/SymbolSweep Settings.json")
+    });
+    let content = settings_profiles::export(&state.settings.lock().unwrap());
+    std::fs::write(&path, content).map_err(|e| SettingsError::SaveFailed {
+        message: format!("Failed to export settings: {}", e),
+    })?;
+    Ok(path.display().to_string())
+}
+
+/// Replace the portable settings with those exported to `path`
+#[tauri::command]
+fn import_settings(app: tauri::AppHandle, path: String) -> Result<(), SettingsError> {
+    let content = std::fs::read_to_string(&path).map_err(|e| SettingsError::Unreadable {
+        message: format!("Failed to read {}: {}", path, e),
+    })?;
+    let profile = settings_profiles::parse_export(&content)?;
+    apply_profile(&app, &profile)
+}
+
+/// Built-in and saved profiles
+#[tauri::command]
+fn list_profiles() -> Vec<ProfileInfo> {
+    settings_profiles::list_profiles(&Settings::dir())
+        .iter()
+        .map(Profile::info)
+        .collect()
+}
+
+/// Save the current settings as a named profile
+#[tauri::command]
+fn save_profile(app: tauri::AppHandle, state: tauri::State<AppState>, name: String) -> Result<(), SettingsError> {
+    settings_profiles::save_profile(&Settings::dir(), &name, &state.settings.lock().unwrap())?;
+    let _ = tray::refresh_menu(&app);
+    Ok(())
+}
+
+/// Delete a saved profile
+#[tauri::command]
+fn delete_profile(app: tauri::AppHandle, name: String) -> Result<(), SettingsError> {
+    settings_profiles::delete_profile(&Settings::dir(), &name)?;
+    let _ = tray::refresh_menu(&app);
+    Ok(())
+}
+
+/// Switch to a profile by name
+#[tauri::command]
+fn switch_profile(app: tauri::AppHandle, name: String) -> Result<(), SettingsError> {
+    switch_to_profile(&app, &name)
+}
+
+/// Switch profiles (shared by the command and the tray menu)
+pub(crate) fn switch_to_profile<R: Runtime>(app: &tauri::AppHandle<R>, name: &str) -> Result<(), SettingsError> {
+    let profile = settings_profiles::find_profile(&Settings::dir(), name)?;
+    apply_profile(app, &profile)
+}
+
+/// Replace the portable settings with `profile`'s in one step
+/// The merged settings are validated and saved before they take effect, so a
+/// failure leaves both the running settings and settings.json untouched.
+fn apply_profile<R: Runtime>(app: &tauri::AppHandle<R>, profile: &Profile) -> Result<(), SettingsError> {
+    let state = app.state::<AppState>();
+    let mut current = state.settings.lock().unwrap();
+    let settings = profile.apply_to(&current, &state.policy)?;
+    settings
+        .save()
+        .map_err(|message| SettingsError::saving(&settings, message))?;
+    *current = settings;
+
+    settings_changed(app, &state, &current);
+    Ok(())
+}
+
+/// Resume auto-clean after the circuit breaker paused it
+#[tauri::command]
+fn resume_auto_clean(app: tauri::AppHandle, state: tauri::State<AppState>) -> Result<(), String> {
+    let mut settings = state.settings.lock().unwrap();
+    settings.auto_clean_backoff.reset();
+    settings.save()?;
+    let _ = app.emit("settings-updated", settings.clone());
+    let _ = app.emit("cache-status-update", read_status(&settings));
+    state.monitor_signal.wake(WakeReason::SettingsChanged);
+    Ok(())
+}
+
+/// Snooze cache warning/critical notifications for `duration_secs` (0 resumes them)
+#[tauri::command]
+fn snooze_notifications(app: tauri::AppHandle, duration_secs: u64) -> Result<(), String> {
+    snooze_alerts(&app, duration_secs)
+}
 
+/// Snooze threshold notifications (shared by the command and the tray menu)
+pub(crate) fn snooze_alerts<R: Runtime>(app: &tauri::AppHandle<R>, duration_secs: u64) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let mut settings = state.settings.lock().unwrap();
+    settings.threshold_alerts.snooze(SystemClock.now(), duration_secs);
+    settings.save()?;
+    let _ = app.emit("settings-updated", settings.clone());
     Ok(())
 }
 
+/// Format sizes in `units` the way the tray and notifications show them
+#[tauri::command]
+fn format_sizes(sizes: Vec<u64>, units: SizeUnits) -> Vec<String> {
+    sizes.into_iter().map(|bytes| units::format_size_in(bytes, units)).collect()
+}
+
 /// Get time since last clean
 #[tauri::command]
 fn get_last_clean_time(state: tauri::State<AppState>) -> String {
@@ -159,7 +492,11 @@ fn quit_app(app: tauri::AppHandle) {
 /// Test notification (debug only) - uses the same notification path as real notifications
 #[tauri::command]
 fn test_notification(app: tauri::AppHandle) {
-    tray::send_notification(&app, "SymbolSweep Test", "Notifications are working!");
+    tray::send_notification(
+        &app,
+        Notification::new(&tr(Msg::TitleTest), tr(Msg::TestBody))
+            .with_actions(vec![NotificationAction::OpenWindow]),
+    );
 }
 
 /// Open macOS System Settings to the Notifications pane
@@ -167,9 +504,64 @@ fn test_notification(app: tauri::AppHandle) {
 fn open_notification_settings() {
     #[cfg(target_os = "macos")]
     {
-        let _ = std::process::Command::new("open")
-            .arg("x-apple.systempreferences:com.apple.Notifications-Settings.extension")
-            .spawn();
+        use command_runner::{CommandRunner, SystemCommandRunner, QUICK_TIMEOUT};
+        let _ = SystemCommandRunner.run(
+            "open",
+            &["x-apple.systempreferences:com.apple.Notifications-Settings.extension"],
+            QUICK_TIMEOUT,
+        );
+    }
+}
+
+// ============================================================================
+// Background Monitoring
+// ============================================================================
+
+/// Forwards monitor engine events to the tray, frontend and notifications
+struct AppEventSink {
+    app: tauri::AppHandle,
+    signal: Arc<MonitorSignal>,
+}
+
+impl EventSink for AppEventSink {
+    fn emit(&mut self, event: MonitorEvent) {
+        // Tray and window calls wait for the main thread, which is busy exiting
+        if self.signal.is_shutting_down()
+            && !matches!(event, MonitorEvent::SettingsChanged(_) | MonitorEvent::Logged(_))
+        {
+            return;
+        }
+        match event {
+            MonitorEvent::StatusUpdated(status) => {
+                let _ = update_tray_icon(&self.app, &status);
+                let _ = self.app.emit("cache-status-update", &status);
+            }
+            MonitorEvent::SettingsChanged(_) => {
+                // The engine already wrote its fields into the shared settings; saving its
+                // snapshot instead could undo an in-app change made since it was taken
+                let state = self.app.state::<AppState>();
+                let settings = state.settings.lock().unwrap();
+                let _ = settings.save();
+                let _ = self.app.emit("settings-updated", settings.clone());
+            }
+            MonitorEvent::AutoCleanCompleted(result) => {
+                let _ = self.app.emit("auto-clean-completed", &result);
+            }
+            MonitorEvent::AutoCleanFailed(error) => {
+                let _ = self.app.emit("auto-clean-failed", &error);
+            }
+            MonitorEvent::RunawayDetected(event) => {
+                let _ = self.app.emit("runaway-detected", &event);
+            }
+            MonitorEvent::Logged(message) => cache_cleaner::log_event(&message),
+            MonitorEvent::ApprovalPending => {
+                let _ = tray::set_approval_pending(&self.app, true);
+                let _ = self.app.emit("approval-pending", true);
+            }
+            MonitorEvent::Notify { title, body, actions } => {
+                send_notification(&self.app, Notification::new(&title, body).with_actions(actions));
+            }
+        }
     }
 }
 
@@ -179,10 +571,15 @@ fn open_notification_settings() {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    i18n::set_locale(i18n::system_locale());
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_shell::init())
-        .plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
+        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+            // A second launch can reconfigure this one: `open -a SymbolSweep --args --set monitor_interval_secs=2m`
+            apply_cli_overrides(app, &argv);
+
             // When a second instance tries to launch, show the existing window
             if let Some(window) = app.get_webview_window("main") {
                 let _ = window.show();
@@ -218,157 +615,53 @@ pub fn run() {
             let state = app.state::<AppState>();
             let settings_init = Arc::clone(&state.settings);
             std::thread::spawn(move || {
-                let initial_status = {
-                    let settings = settings_init.lock().unwrap();
-                    if settings.debug_mode {
-                        get_simulated_status(settings.debug_simulated_size)
-                    } else {
-                        get_cache_status()
-                    }
-                };
+                let initial_status = read_status(&settings_init.lock().unwrap());
                 let _ = update_tray_icon(&app_handle_init, &initial_status);
                 let _ = app_handle_init.emit("cache-status-update", &initial_status);
             });
 
-            // Sync autostart state with saved setting
-            {
-                use tauri_plugin_autostart::ManagerExt;
-                let autostart_manager = app.autolaunch();
-                let settings = state.settings.lock().unwrap();
-                if settings.launch_at_login {
-                    let _ = autostart_manager.enable();
-                } else {
-                    let _ = autostart_manager.disable();
-                }
+            // Tell the user if their settings file was corrupt and had to be recovered
+            if let Some(recovery) = state.settings_recovery.lock().unwrap().clone() {
+                let _ = app.emit("settings-recovered", &recovery);
+                send_notification(
+                    app.handle(),
+                    Notification::new(&tr(Msg::TitleRecovered), recovery.message()),
+                );
             }
 
-            // Set up background monitoring
-            let app_handle = app.handle().clone();
-            let state = app.state::<AppState>();
-            let settings = Arc::clone(&state.settings);
+            // `--set field=value` arguments this instance was launched with
+            let args: Vec<String> = std::env::args().collect();
+            apply_cli_overrides(app.handle(), &args);
 
-            std::thread::spawn(move || {
-                // Track if we've already notified for warning/critical this session
-                // Reset when state drops back to normal
-                let mut warning_notified = false;
-                let mut critical_notified = false;
-
-                loop {
-                    // Get monitoring interval (read before sleep)
-                    let interval = {
-                        let s = settings.lock().unwrap();
-                        s.monitor_interval_secs
-                    };
-
-                    // Sleep first (so we don't immediately check on startup)
-                    std::thread::sleep(std::time::Duration::from_secs(interval));
-
-                    // Get current status (read debug settings FRESH after sleep)
-                    let status = {
-                        let s = settings.lock().unwrap();
-                        if s.debug_mode {
-                            get_simulated_status(s.debug_simulated_size)
-                        } else {
-                            get_cache_status()
-                        }
-                    };
-
-                    // Update tray icon
-                    let _ = update_tray_icon(&app_handle, &status);
-
-                    // Emit status update to frontend
-                    let _ = app_handle.emit("cache-status-update", &status);
-
-                    // Check for auto-clean conditions
-                    let should_auto_clean = {
-                        let s = settings.lock().unwrap();
-                        let threshold_clean = s.auto_clean_on_threshold
-                            && status.size_bytes >= s.auto_clean_threshold;
-
-                        let now = std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap_or_default()
-                            .as_secs();
-
-                        let scheduled_clean = s.auto_clean_scheduled
-                            && (now - s.last_clean_timestamp) >= s.auto_clean_interval_secs;
-
-                        threshold_clean || scheduled_clean
-                    };
-
-                    if should_auto_clean {
-                        let show_notifications = settings.lock().unwrap().show_notifications;
-
-                        // Perform clean
-                        if let Ok(result) = clean_cache(false) {
-                            // Update last clean timestamp and reset debug size
-                            if let Ok(mut s) = settings.lock() {
-                                s.record_clean();
-                                // Reset debug simulated size to 0 after clean
-                                if s.debug_mode {
-                                    s.debug_simulated_size = 0;
-                                    let _ = s.save();
-                                    // Notify frontend to refresh settings
-                                    let _ = app_handle.emit("settings-updated", s.clone());
-                                }
-                            }
-
-                            // Update tray to show clean state
-                            let clean_status = get_cache_status();
-                            let _ = update_tray_icon(&app_handle, &clean_status);
-                            // Emit status update so frontend refreshes
-                            let _ = app_handle.emit("cache-status-update", &clean_status);
-
-                            // Emit clean result
-                            let _ = app_handle.emit("auto-clean-completed", &result);
-
-                            // Notify about completion (only if something was actually freed)
-                            if show_notifications && result.bytes_freed > 0 {
-                                send_notification(
-                                    &app_handle,
-                                    "SymbolSweep",
-                                    &format!("Cleaned {} of cache", result.bytes_freed_display),
-                                );
-                            }
-                        }
-                    }
+            // Sync autostart state with saved setting
+            sync_autostart(app.handle(), state.settings.lock().unwrap().launch_at_login);
 
-                    // Check for warning/critical thresholds and notify (once per escalation)
-                    // Skip if auto-clean just ran - no point warning about something we just cleaned
-                    let show_notifications = settings.lock().unwrap().show_notifications;
-                    if show_notifications && !should_auto_clean {
-                        match status.state {
-                            cache_monitor::CacheState::Warning => {
-                                if !warning_notified {
-                                    send_notification(
-                                        &app_handle,
-                                        "SymbolSweep - Warning",
-                                        &format!("Cache at {} - consider cleaning soon", status.size_display),
-                                    );
-                                    warning_notified = true;
-                                }
-                            }
-                            cache_monitor::CacheState::Critical => {
-                                if !critical_notified {
-                                    send_notification(
-                                        &app_handle,
-                                        "SymbolSweep - Critical",
-                                        &format!("Cache at {} - cleaning recommended!", status.size_display),
-                                    );
-                                    critical_notified = true;
-                                }
-                            }
-                            _ => {}
-                        }
-                    }
+            // Pick up edits to settings.json from outside the app (fleet tooling, hand edits)
+            let app_handle_watch = app.handle().clone();
+            std::thread::spawn(move || watch_settings_file(app_handle_watch));
 
-                    // Reset notification flags when back to normal
-                    if matches!(status.state, cache_monitor::CacheState::Normal) {
-                        warning_notified = false;
-                        critical_notified = false;
-                    }
-                }
-            });
+            // Set up background monitoring
+            let state = app.state::<AppState>();
+            let mut engine = MonitorEngine::new(
+                Arc::clone(&state.settings),
+                Arc::clone(&state.pending_approval),
+                Arc::clone(&state.live_status),
+                SystemClock,
+                SystemStatusProvider::new(
+                    process_info::default_source(),
+                    power::default_power_source(),
+                    Arc::clone(&state.daemon_tracker),
+                ),
+                UnattendedCleaner,
+                AppEventSink {
+                    app: app.handle().clone(),
+                    signal: Arc::clone(&state.monitor_signal),
+                },
+            );
+
+            let signal = Arc::clone(&state.monitor_signal);
+            let monitor_thread = std::thread::spawn(move || engine.run(&signal));
+            *state.monitor_thread.lock().unwrap() = Some(monitor_thread);
 
             // Configure window for menu bar app behavior
             if let Some(window) = app.get_webview_window("main") {
@@ -400,12 +693,24 @@ pub fn run() {
             get_status,
             get_combined_status,
             get_daemon_status,
+            get_daemon_info,
             clean,
             get_log_path,
             reindex,
             get_settings,
+            get_settings_recovery,
+            get_managed_policy,
+            export_settings,
+            import_settings,
+            list_profiles,
+            save_profile,
+            delete_profile,
+            switch_profile,
             update_settings,
             get_last_clean_time,
+            format_sizes,
+            resume_auto_clean,
+            snooze_notifications,
             quit_app,
             test_notification,
             open_notification_settings,
@@ -413,6 +718,16 @@ pub fn run() {
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
         .run(|app, event| {
+            // Give the monitor a moment to finish its current tick instead of abandoning it mid-scan
+            if let tauri::RunEvent::Exit = event {
+                let state = app.state::<AppState>();
+                if let Some(monitor_thread) = state.monitor_thread.lock().unwrap().take() {
+                    if !stop_monitor(&state.monitor_signal, monitor_thread, SHUTDOWN_TIMEOUT) {
+                        eprintln!("Monitor: tick still running at exit, not waiting for it");
+                    }
+                }
+            }
+
             // Handle dock icon click on macOS
             #[cfg(target_os = "macos")]
             if let tauri::RunEvent::Reopen { .. } = event {
@@ -0,0 +1,1839 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Condvar, Mutex, OnceLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::cache_cleaner::{clean_cache_unattended, CleanError, CleanResult};
+use crate::cache_monitor::{
+    get_cache_status, get_simulated_status, read_daemon_info, CacheState, CacheStatus, DaemonTracker,
+    CRITICAL_THRESHOLD, WARNING_THRESHOLD,
+};
+use crate::i18n::{tr, tr_count, tr_with, Msg};
+use crate::notification_policy::{Notification, NotificationPolicy, Suppression, ThresholdAlert};
+use crate::notifier::NotificationAction;
+use crate::power::PowerSource;
+use crate::process_info::{ProcessInfo, ProcessInfoSource};
+use crate::runaway::{RunawayConfig, RunawayDetector, RunawayEvent};
+use crate::schedule::{next_clean_after, schedule_anchor, ScheduleError};
+use crate::scheduler::{
+    format_duration, AutoCleanDeferral, AutoCleanStatus, DeferralReason, IntervalReason, MissedCleanPolicy,
+    MonitorStatus, Settings,
+};
+
+// ============================================================================
+// Injectable dependencies
+// ============================================================================
+
+/// Source of the current time
+pub trait Clock: Send {
+    /// Current Unix timestamp in seconds
+    fn now(&self) -> u64;
+    /// Time since an arbitrary fixed point
+    /// Never goes backwards and doesn't advance while the machine is asleep
+    fn monotonic(&self) -> Duration;
+}
+
+/// Wall clock backed by `SystemTime`, monotonic clock backed by `Instant`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or(Duration::ZERO)
+            .as_secs()
+    }
+
+    fn monotonic(&self) -> Duration {
+        static START: OnceLock<Instant> = OnceLock::new();
+        START.get_or_init(Instant::now).elapsed()
+    }
+}
+
+/// Source of cache and daemon status
+pub trait StatusProvider: Send {
+    /// Current cache status (settings are passed so debug mode can be honoured)
+    fn cache_status(&mut self, settings: &Settings) -> CacheStatus;
+    /// Current daemon process details (None if not running)
+    fn daemon_process(&mut self) -> Option<ProcessInfo>;
+    /// Executable names of all running processes
+    fn running_processes(&mut self) -> Vec<String>;
+    /// Whether the machine is on AC power (None if unknown)
+    fn on_ac_power(&mut self) -> Option<bool>;
+    /// Seconds since the last user input (None if unknown)
+    fn idle_secs(&mut self) -> Option<u64>;
+}
+
+/// Performs background cleans
+pub trait Cleaner: Send {
+    /// Clean the cache without user interaction
+    fn clean(&mut self) -> Result<CleanResult, CleanError>;
+}
+
+/// Everything the engine wants the outside world to know about
+#[derive(Debug, Clone)]
+pub enum MonitorEvent {
+    /// Fresh cache status (tray + frontend)
+    StatusUpdated(CacheStatus),
+    /// Settings changed and should be persisted
+    SettingsChanged(Settings),
+    AutoCleanCompleted(CleanResult),
+    AutoCleanFailed(String),
+    RunawayDetected(RunawayEvent),
+    /// A background clean skipped a step that needs an admin password
+    ApprovalPending,
+    /// Schedule or clock event worth keeping in the app's log file
+    Logged(String),
+    /// Show a user notification, with buttons that route back into the app
+    Notify {
+        title: String,
+        body: String,
+        actions: Vec<NotificationAction>,
+    },
+}
+
+/// Receives engine events
+pub trait EventSink: Send {
+    fn emit(&mut self, event: MonitorEvent);
+}
+
+// ============================================================================
+// Production implementations
+// ============================================================================
+
+/// Read cache status (simulated in debug mode) with auto-clean state attached
+pub fn read_status(settings: &Settings) -> CacheStatus {
+    let mut status = if settings.debug_mode {
+        get_simulated_status(settings.debug_simulated_size)
+    } else {
+        get_cache_status()
+    };
+    status.auto_clean = AutoCleanStatus::from_settings(settings);
+    status
+}
+
+/// Scans the real cache, daemon and power state, recording daemon restarts in the shared tracker
+pub struct SystemStatusProvider {
+    process_source: Box<dyn ProcessInfoSource>,
+    power_source: Box<dyn PowerSource>,
+    daemon_tracker: Arc<Mutex<DaemonTracker>>,
+}
+
+impl SystemStatusProvider {
+    pub fn new(
+        process_source: Box<dyn ProcessInfoSource>,
+        power_source: Box<dyn PowerSource>,
+        daemon_tracker: Arc<Mutex<DaemonTracker>>,
+    ) -> Self {
+        Self {
+            process_source,
+            power_source,
+            daemon_tracker,
+        }
+    }
+}
+
+impl StatusProvider for SystemStatusProvider {
+    fn cache_status(&mut self, settings: &Settings) -> CacheStatus {
+        read_status(settings)
+    }
+
+    fn daemon_process(&mut self) -> Option<ProcessInfo> {
+        let mut tracker = self.daemon_tracker.lock().unwrap();
+        read_daemon_info(self.process_source.as_ref(), &mut tracker).process
+    }
+
+    fn running_processes(&mut self) -> Vec<String> {
+        self.process_source.running_names()
+    }
+
+    fn on_ac_power(&mut self) -> Option<bool> {
+        self.power_source.on_ac_power()
+    }
+
+    fn idle_secs(&mut self) -> Option<u64> {
+        self.power_source.idle_secs()
+    }
+}
+
+/// Cleans the real cache without ever prompting for a password
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UnattendedCleaner;
+
+impl Cleaner for UnattendedCleaner {
+    fn clean(&mut self) -> Result<CleanResult, CleanError> {
+        clean_cache_unattended()
+    }
+}
+
+// ============================================================================
+// Loop control
+// ============================================================================
+
+/// Longest the loop waits without checking whether the machine slept
+const WAKE_CHECK_SLICE: Duration = Duration::from_secs(30);
+
+/// Longest the app waits on exit for a running tick to finish
+pub const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Wall-clock time passing faster than monotonic time by more than this means
+/// the machine was asleep (monotonic time stops during sleep)
+const SLEEP_GAP_THRESHOLD: Duration = Duration::from_secs(60);
+
+/// Why the monitor loop was woken before its interval elapsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WakeReason {
+    /// Settings were changed by the user
+    SettingsChanged,
+    /// The user ran a clean, so the status is stale
+    ManualClean,
+    /// The machine woke from sleep
+    SystemWake,
+}
+
+/// Result of waiting for the next tick
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitOutcome {
+    /// The full interval passed
+    Elapsed,
+    Woken(WakeReason),
+    /// The app is exiting
+    Shutdown,
+}
+
+#[derive(Debug, Default)]
+struct SignalState {
+    wake: Option<WakeReason>,
+    shutdown: bool,
+}
+
+/// Lets the app interrupt the monitor loop's wait and stop it cleanly
+///
+/// Wakes are coalesced: several wakes while a tick is running produce a single
+/// immediate tick afterwards.
+#[derive(Debug, Default)]
+pub struct MonitorSignal {
+    state: Mutex<SignalState>,
+    condvar: Condvar,
+}
+
+impl MonitorSignal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run the next tick now instead of waiting out the interval
+    pub fn wake(&self, reason: WakeReason) {
+        self.state.lock().unwrap().wake = Some(reason);
+        self.condvar.notify_all();
+    }
+
+    /// Stop the loop once the current tick (if any) finishes
+    pub fn shutdown(&self) {
+        self.state.lock().unwrap().shutdown = true;
+        self.condvar.notify_all();
+    }
+
+    /// Whether shutdown was requested; a tick still running skips tray and window updates
+    pub fn is_shutting_down(&self) -> bool {
+        self.state.lock().unwrap().shutdown
+    }
+
+    /// Block until `timeout` passes, a wake is requested, the machine wakes
+    /// from sleep, or shutdown is requested
+    pub fn wait(&self, timeout: Duration) -> WaitOutcome {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.state.lock().unwrap();
+
+        loop {
+            if state.shutdown {
+                return WaitOutcome::Shutdown;
+            }
+            if let Some(reason) = state.wake.take() {
+                return WaitOutcome::Woken(reason);
+            }
+
+            let started = Instant::now();
+            if started >= deadline {
+                return WaitOutcome::Elapsed;
+            }
+            let wall_started = SystemTime::now();
+
+            let slice = (deadline - started).min(WAKE_CHECK_SLICE);
+            state = self.condvar.wait_timeout(state, slice).unwrap().0;
+
+            let wall_elapsed = wall_started.elapsed().unwrap_or(Duration::ZERO);
+            if slept_through(started.elapsed(), wall_elapsed) {
+                return WaitOutcome::Woken(WakeReason::SystemWake);
+            }
+        }
+    }
+}
+
+/// When a deferral for `reason` started: carried over while the hold keeps the
+/// same kind of reason, and restarted when it changes (battery to busy tools, say)
+fn deferred_since(previous: Option<&AutoCleanDeferral>, reason: &DeferralReason, now: u64) -> u64 {
+    previous
+        .filter(|d| std::mem::discriminant(&d.reason) == std::mem::discriminant(reason))
+        .map_or(now, |d| d.since)
+}
+
+/// Stop the loop and wait up to `timeout` for its current tick, returning whether it finished
+///
+/// Called from the main thread on exit. Tray updates from a running tick wait for the
+/// main thread, so the join happens on a helper thread rather than blocking it.
+pub fn stop_monitor(signal: &MonitorSignal, thread: JoinHandle<()>, timeout: Duration) -> bool {
+    signal.shutdown();
+    let (done_tx, done_rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = thread.join();
+        let _ = done_tx.send(());
+    });
+    done_rx.recv_timeout(timeout).is_ok()
+}
+
+/// Whether the wall clock advanced far enough past monotonic time to indicate sleep
+fn slept_through(monotonic_elapsed: Duration, wall_elapsed: Duration) -> bool {
+    wall_elapsed.saturating_sub(monotonic_elapsed) > SLEEP_GAP_THRESHOLD
+}
+
+// ============================================================================
+// Engine
+// ============================================================================
+
+/// Disagreement between wall-clock and monotonic time (in seconds) tolerated
+/// between ticks before it's treated as sleep or a clock change
+const TIME_JUMP_SLACK_SECS: i64 = 60;
+
+/// Most missed schedule windows counted individually after a long sleep
+const MAX_MISSED_WINDOWS: u64 = 1000;
+
+/// Engine state that commands report alongside a fresh cache scan
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LiveStatus {
+    /// Why a due auto-clean is waiting
+    pub deferral: Option<AutoCleanDeferral>,
+    /// How often the cache is being scanned
+    pub monitor: MonitorStatus,
+}
+
+impl LiveStatus {
+    /// Copy the live state into a freshly read status
+    pub fn apply(&self, status: &mut CacheStatus) {
+        status.auto_clean.deferral = self.deferral.clone();
+        status.monitor = self.monitor.clone();
+    }
+}
+
+/// Monitoring and auto-clean decisions, driven one tick at a time
+///
+/// The engine owns no threads and never sleeps; the app calls `tick` on its
+/// own schedule, and tests call it in a loop while advancing a fake clock.
+pub struct MonitorEngine<C: Clock, P: StatusProvider, K: Cleaner, E: EventSink> {
+    settings: Arc<Mutex<Settings>>,
+    approval_pending: Arc<AtomicBool>,
+    /// Deferral and scan interval (shared so commands can report them)
+    live: Arc<Mutex<LiveStatus>>,
+    clock: C,
+    provider: P,
+    cleaner: K,
+    sink: E,
+    runaway_detector: RunawayDetector,
+    notification_policy: NotificationPolicy,
+    /// Wall and monotonic time of the previous tick, for spotting sleep and clock changes
+    last_tick: Option<(u64, Duration)>,
+    /// Monotonic time before which auto-clean waits after a wake
+    grace_until: Option<Duration>,
+    /// Wall time before which scheduled cleans wait after skipping missed windows
+    skip_schedule_until: Option<u64>,
+    /// Due time of the missed schedule window already logged
+    reported_missed: Option<u64>,
+    /// Wall time of the first schedule check; a schedule that never cleaned counts windows from here
+    started_at: Option<u64>,
+    /// Schedule error already logged
+    reported_schedule_error: Option<ScheduleError>,
+    /// Wall time and cache size at the previous scan, for the growth rate
+    last_sample: Option<(u64, u64)>,
+}
+
+impl<C: Clock, P: StatusProvider, K: Cleaner, E: EventSink> MonitorEngine<C, P, K, E> {
+    pub fn new(
+        settings: Arc<Mutex<Settings>>,
+        approval_pending: Arc<AtomicBool>,
+        live: Arc<Mutex<LiveStatus>>,
+        clock: C,
+        provider: P,
+        cleaner: K,
+        sink: E,
+    ) -> Self {
+        Self {
+            settings,
+            approval_pending,
+            live,
+            clock,
+            provider,
+            cleaner,
+            sink,
+            runaway_detector: RunawayDetector::new(),
+            notification_policy: NotificationPolicy::new(),
+            last_tick: None,
+            grace_until: None,
+            skip_schedule_until: None,
+            reported_missed: None,
+            started_at: None,
+            reported_schedule_error: None,
+            last_sample: None,
+        }
+    }
+
+    /// Seconds to wait before the next tick
+    /// The adaptive interval once a scan has set one, the configured interval before that
+    pub fn interval_secs(&self) -> u64 {
+        match self.live.lock().unwrap().monitor.interval_secs {
+            0 => self.settings.lock().unwrap().monitor_interval_secs.max(1),
+            secs => secs,
+        }
+    }
+
+    /// Tick every interval until shutdown, ticking early whenever woken
+    /// Waits before the first tick so startup isn't slowed by a scan
+    pub fn run(&mut self, signal: &MonitorSignal) {
+        loop {
+            let interval = Duration::from_secs(self.interval_secs());
+            match signal.wait(interval) {
+                WaitOutcome::Shutdown => break,
+                WaitOutcome::Elapsed | WaitOutcome::Woken(_) => self.tick(),
+            }
+        }
+    }
+
+    /// Event sink (for tests to inspect what was emitted)
+    #[cfg(test)]
+    pub fn sink(&mut self) -> &mut E {
+        &mut self.sink
+    }
+
+    /// Run one monitoring pass: scan, detect runaways, auto-clean and notify
+    pub fn tick(&mut self) {
+        let now = self.clock.now();
+        let monotonic = self.clock.monotonic();
+        self.check_time_jump(now, monotonic);
+
+        // Work from a snapshot so the lock isn't held while scanning or cleaning
+        let settings = self.settings.lock().unwrap().clone();
+
+        let status = self.read_status(&settings);
+        let process = self.provider.daemon_process();
+        self.sink.emit(MonitorEvent::StatusUpdated(status.clone()));
+
+        // Quiet hours or a presentation just ended - deliver what was held back
+        if let Some(summary) = self.notification_policy.take_summary(&settings, now) {
+            self.sink.emit(MonitorEvent::Notify {
+                title: summary.title,
+                body: summary.body,
+                actions: summary.actions,
+            });
+        }
+
+        let remediate = self.check_runaway(&settings, now, &status, process.as_ref());
+
+        let wants_clean = self.should_auto_clean(&settings, now, &status) || remediate;
+        let may_clean = wants_clean && settings.auto_clean_backoff.allows_attempt(now);
+        let deferral = if may_clean { self.check_deferral(&settings, now, monotonic) } else { None };
+        let should_auto_clean = may_clean && deferral.is_none();
+        self.set_deferral(deferral, &status);
+        if should_auto_clean {
+            self.auto_clean(&settings, now, &status);
+        }
+
+        // Skip if auto-clean just ran - no point warning about something we just cleaned
+        if !should_auto_clean {
+            self.notify_threshold(&settings, now, &status);
+        }
+
+        self.update_interval(&settings, now, &status);
+    }
+
+    fn read_status(&mut self, settings: &Settings) -> CacheStatus {
+        let mut status = self.provider.cache_status(settings);
+        status.auto_clean = AutoCleanStatus::from_settings_at(settings, self.clock.now());
+        self.live.lock().unwrap().apply(&mut status);
+        status
+    }
+
+    /// Hold a due auto-clean until the machine is on AC power and idle (when
+    /// required) and no debugging tools are running
+    fn check_deferral(&mut self, settings: &Settings, now: u64, monotonic: Duration) -> Option<AutoCleanDeferral> {
+        let previous = self.live.lock().unwrap().deferral.clone();
+        let held = |reason| {
+            Some(AutoCleanDeferral {
+                since: deferred_since(previous.as_ref(), &reason, now),
+                reason,
+                until: None,
+            })
+        };
+
+        if let Some(until) = self.wake_grace_until(now, monotonic) {
+            let reason = DeferralReason::WakeGrace { until };
+            return Some(AutoCleanDeferral {
+                since: deferred_since(previous.as_ref(), &reason, now),
+                reason,
+                until: Some(until),
+            });
+        }
+        if let Some(suppression) = NotificationPolicy::auto_clean_deferral(settings, now) {
+            return held(match suppression {
+                Suppression::QuietHours => DeferralReason::QuietHours,
+                Suppression::Presenting => DeferralReason::Presenting,
+            });
+        }
+
+        // Unknown power or idle state doesn't block
+        if settings.require_ac_power && self.provider.on_ac_power() == Some(false) {
+            return held(DeferralReason::OnBattery);
+        }
+        if settings.require_idle && self.provider.idle_secs().is_some_and(|idle| idle < settings.require_idle_secs) {
+            return held(DeferralReason::NotIdle {
+                required_secs: settings.require_idle_secs,
+            });
+        }
+
+        self.busy_deferral(settings, now, previous.as_ref())
+    }
+
+    /// Hold a due auto-clean while debugging tools are running, up to `busy_max_defer_secs`
+    fn busy_deferral(
+        &mut self,
+        settings: &Settings,
+        now: u64,
+        previous: Option<&AutoCleanDeferral>,
+    ) -> Option<AutoCleanDeferral> {
+        if !settings.defer_while_busy || settings.busy_process_names.is_empty() {
+            return None;
+        }
+
+        let running = self.provider.running_processes();
+        let busy: Vec<String> = settings
+            .busy_process_names
+            .iter()
+            .filter(|name| running.contains(name))
+            .cloned()
+            .collect();
+        if busy.is_empty() {
+            return None;
+        }
+
+        let reason = DeferralReason::BusyProcesses { names: busy.clone() };
+        let since = deferred_since(previous, &reason, now);
+        let until = since.saturating_add(settings.busy_max_defer_secs);
+        if now >= until {
+            self.log(format!(
+                "{} still running after {}; cleaning anyway",
+                busy.join(", "),
+                format_duration(settings.busy_max_defer_secs)
+            ));
+            return None;
+        }
+
+        Some(AutoCleanDeferral {
+            reason,
+            since,
+            until: Some(until),
+        })
+    }
+
+    /// Record the current deferral, logging and reporting when it changes
+    fn set_deferral(&mut self, deferral: Option<AutoCleanDeferral>, status: &CacheStatus) {
+        let previous = std::mem::replace(&mut self.live.lock().unwrap().deferral, deferral.clone());
+        if previous == deferral {
+            return;
+        }
+
+        match (&previous, &deferral) {
+            (_, Some(d)) => self.log(format!("auto-clean deferred, {}", d.reason.message())),
+            (Some(_), None) => self.log("auto-clean no longer deferred".to_string()),
+            (None, None) => {}
+        }
+
+        let mut status = status.clone();
+        status.auto_clean.deferral = deferral;
+        self.sink.emit(MonitorEvent::StatusUpdated(status));
+    }
+
+    /// Pick the next scan interval from how fast the cache is growing
+    /// Scans often when a threshold is near, backs off while the cache is flat,
+    /// and halves the scan rate on battery
+    fn update_interval(&mut self, settings: &Settings, now: u64, status: &CacheStatus) {
+        let previous_sample = self.last_sample.replace((now, status.size_bytes));
+        let monitor = if settings.adaptive_interval {
+            let previous_interval = self.interval_secs();
+            self.adaptive_interval(settings, now, status.size_bytes, previous_sample, previous_interval)
+        } else {
+            MonitorStatus {
+                interval_secs: settings.monitor_interval_secs.max(1),
+                reason: IntervalReason::Fixed,
+            }
+        };
+
+        let mut live = self.live.lock().unwrap();
+        if live.monitor != monitor {
+            eprintln!(
+                "Monitor: scanning every {} ({:?})",
+                format_duration(monitor.interval_secs),
+                monitor.reason
+            );
+            live.monitor = monitor;
+        }
+    }
+
+    fn adaptive_interval(
+        &mut self,
+        settings: &Settings,
+        now: u64,
+        size: u64,
+        previous_sample: Option<(u64, u64)>,
+        previous_interval: u64,
+    ) -> MonitorStatus {
+        let min = settings.min_monitor_interval_secs.max(1);
+        let max = settings.max_monitor_interval_secs.max(min);
+
+        // Bytes per second since the last scan, if the cache grew
+        let growth = previous_sample
+            .filter(|&(at, before)| now > at && size > before)
+            .map(|(at, before)| (size - before) as f64 / (now - at) as f64);
+        let next_threshold = [
+            settings.auto_clean_on_threshold.then_some(settings.auto_clean_threshold),
+            Some(WARNING_THRESHOLD),
+            Some(CRITICAL_THRESHOLD),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|&threshold| threshold > size)
+        .min();
+
+        let (interval, mut reason) = match (growth, next_threshold) {
+            // Check a few times before the threshold is reached
+            (Some(rate), Some(threshold)) => {
+                let eta = (threshold - size) as f64 / rate;
+                ((eta / 4.0) as u64, IntervalReason::Growing)
+            }
+            _ => (previous_interval.saturating_mul(2), IntervalReason::Steady),
+        };
+        let mut interval = interval.clamp(min, max);
+        if interval == max {
+            reason = IntervalReason::Steady;
+        }
+
+        if self.provider.on_ac_power() == Some(false) && interval < max {
+            interval = interval.saturating_mul(2).min(max);
+            reason = IntervalReason::OnBattery;
+        }
+
+        MonitorStatus {
+            interval_secs: interval,
+            reason,
+        }
+    }
+
+    /// Show a notification, subject to the notification policy
+    fn notify(&mut self, settings: &Settings, title: &str, body: String) {
+        self.notify_with_actions(settings, title, body, Vec::new());
+    }
+
+    /// Show a notification with buttons, subject to the notification policy
+    fn notify_with_actions(&mut self, settings: &Settings, title: &str, body: String, actions: Vec<NotificationAction>) {
+        let now = self.clock.now();
+        let notification = Notification::new(title, body).with_actions(actions);
+        if let Some(notification) = self.notification_policy.submit(settings, now, notification) {
+            self.sink.emit(MonitorEvent::Notify {
+                title: notification.title,
+                body: notification.body,
+                actions: notification.actions,
+            });
+        }
+    }
+
+    /// Feed the runaway detector; returns true if a remediation clean should run
+    fn check_runaway(
+        &mut self,
+        settings: &Settings,
+        now: u64,
+        status: &CacheStatus,
+        process: Option<&ProcessInfo>,
+    ) -> bool {
+        if !settings.runaway_detection_enabled {
+            return false;
+        }
+
+        let events = self
+            .runaway_detector
+            .observe(&RunawayConfig::from(settings), now, status.size_bytes, process);
+        for event in &events {
+            self.sink.emit(MonitorEvent::RunawayDetected(event.clone()));
+            self.notify_with_actions(
+                settings,
+                &tr(Msg::TitleRunaway),
+                event.message(),
+                vec![NotificationAction::CleanNow, NotificationAction::OpenWindow],
+            );
+        }
+
+        settings.runaway_auto_remediate && !events.is_empty()
+    }
+
+    /// Compare wall-clock and monotonic time since the last tick
+    ///
+    /// Wall time running ahead means the machine slept (or the clock was set
+    /// forward), so auto-clean is held off for the wake grace period. Wall time
+    /// falling behind means the clock was set back, so stored timestamps are
+    /// shifted back to keep the time elapsed since them.
+    fn check_time_jump(&mut self, now: u64, monotonic: Duration) {
+        let Some((last_wall, last_monotonic)) = self.last_tick.replace((now, monotonic)) else {
+            // First tick: a last clean in the future means the clock went back while we weren't running
+            let mut s = self.settings.lock().unwrap();
+            if s.last_clean_timestamp > now {
+                s.last_clean_timestamp = now;
+                let updated = s.clone();
+                drop(s);
+                self.log("last clean is in the future, clock was set back; treating it as now".to_string());
+                self.sink.emit(MonitorEvent::SettingsChanged(updated));
+            }
+            return;
+        };
+
+        let wall_elapsed = now as i64 - last_wall as i64;
+        let monotonic_elapsed = monotonic.saturating_sub(last_monotonic).as_secs() as i64;
+        let drift = wall_elapsed - monotonic_elapsed;
+
+        if drift > TIME_JUMP_SLACK_SECS {
+            let grace_secs = self.settings.lock().unwrap().wake_grace_secs;
+            self.log(format!(
+                "wall clock jumped ahead {} (sleep or clock change), holding auto-clean for {}",
+                format_duration(drift as u64),
+                format_duration(grace_secs)
+            ));
+            self.grace_until = Some(monotonic + Duration::from_secs(grace_secs));
+        } else if drift < -TIME_JUMP_SLACK_SECS {
+            let jump = drift.unsigned_abs();
+            self.log(format!("wall clock set back {}, shifting stored timestamps", format_duration(jump)));
+            let updated = {
+                let mut s = self.settings.lock().unwrap();
+                s.shift_timestamps_back(jump);
+                s.clone()
+            };
+            self.skip_schedule_until = self.skip_schedule_until.map(|t| t.saturating_sub(jump));
+            self.started_at = self.started_at.map(|t| t.saturating_sub(jump));
+            self.sink.emit(MonitorEvent::SettingsChanged(updated));
+        }
+    }
+
+    /// Print `message` and pass it on for the app's log file
+    fn log(&mut self, message: String) {
+        eprintln!("Monitor: {}", message);
+        self.sink.emit(MonitorEvent::Logged(message));
+    }
+
+    /// Unix timestamp the post-wake grace period ends, if it is still running
+    fn wake_grace_until(&self, now: u64, monotonic: Duration) -> Option<u64> {
+        self.grace_until
+            .filter(|&until| monotonic < until)
+            .map(|until| now + (until - monotonic).as_secs_f64().ceil() as u64)
+    }
+
+    fn should_auto_clean(&mut self, settings: &Settings, now: u64, status: &CacheStatus) -> bool {
+        let threshold_clean = settings.auto_clean_on_threshold && status.size_bytes >= settings.auto_clean_threshold;
+        let scheduled_clean = settings.auto_clean_scheduled && self.scheduled_clean_due(settings, now);
+
+        threshold_clean || scheduled_clean
+    }
+
+    /// Whether a scheduled clean is due, applying the missed-window policy when
+    /// the due time passed while the machine was asleep or the app wasn't running
+    fn scheduled_clean_due(&mut self, settings: &Settings, now: u64) -> bool {
+        let since = *self.started_at.get_or_insert(now);
+        let due_at = match self.next_clean_after(settings, schedule_anchor(settings, since)) {
+            Some(due_at) => due_at.max(self.skip_schedule_until.unwrap_or(0)),
+            None => return false,
+        };
+        if now < due_at {
+            return false;
+        }
+
+        // Overdue by more than a couple of monitor ticks means nobody was watching when it came due
+        let overdue = now - due_at;
+        if overdue <= self.interval_secs().saturating_mul(2) {
+            return true;
+        }
+
+        // Count the windows that passed, and find the first one still ahead
+        let mut missed = 0;
+        let mut next = Some(due_at);
+        while let Some(window) = next.filter(|&w| w <= now) {
+            missed += 1;
+            next = if missed < MAX_MISSED_WINDOWS {
+                self.next_clean_after(settings, window)
+            } else {
+                self.next_clean_after(settings, now)
+            };
+        }
+
+        match settings.missed_clean_policy {
+            MissedCleanPolicy::CatchUp => {
+                if self.reported_missed != Some(due_at) {
+                    self.reported_missed = Some(due_at);
+                    self.log(format!(
+                        "missed {} scheduled clean(s), first due {} ago; catching up",
+                        missed,
+                        format_duration(overdue)
+                    ));
+                }
+                true
+            }
+            MissedCleanPolicy::Skip => {
+                let Some(next) = next else {
+                    return false;
+                };
+                self.log(format!(
+                    "missed {} scheduled clean(s), first due {} ago; skipping to next window in {}",
+                    missed,
+                    format_duration(overdue),
+                    format_duration(next - now)
+                ));
+                self.skip_schedule_until = Some(next);
+                false
+            }
+        }
+    }
+
+    /// Next scheduled window after `after`, logging (once) if the schedule is invalid
+    fn next_clean_after(&mut self, settings: &Settings, after: u64) -> Option<u64> {
+        match next_clean_after(settings, after) {
+            Ok(next) => {
+                self.reported_schedule_error = None;
+                next
+            }
+            Err(e) => {
+                if self.reported_schedule_error.as_ref() != Some(&e) {
+                    eprintln!("Monitor: scheduled clean disabled: {}", e);
+                    self.reported_schedule_error = Some(e);
+                }
+                None
+            }
+        }
+    }
+
+    fn auto_clean(&mut self, settings: &Settings, now: u64, status: &CacheStatus) {
+        // Freeing nothing from a non-empty cache counts as a failure too
+        // (except in debug mode, where the size is simulated)
+        let outcome = match self.cleaner.clean() {
+            Ok(result) if !settings.debug_mode && result.bytes_freed == 0 && status.size_bytes > 0 => {
+                Err(tr_with(Msg::CleanFreedNothing, &[("message", &result.message)]))
+            }
+            Ok(result) => Ok(result),
+            Err(e) => Err(e.to_string()),
+        };
+
+        match outcome {
+            Ok(result) => self.auto_clean_succeeded(settings, now, result),
+            Err(error) => self.auto_clean_failed(settings, now, error),
+        }
+    }
+
+    fn auto_clean_succeeded(&mut self, settings: &Settings, now: u64, result: CleanResult) {
+        let updated = {
+            let mut s = self.settings.lock().unwrap();
+            s.auto_clean_backoff.reset();
+            s.record_clean_at(now);
+            // Reset debug simulated size to 0 after clean
+            if s.debug_mode {
+                s.debug_simulated_size = 0;
+            }
+            s.clone()
+        };
+        self.sink.emit(MonitorEvent::SettingsChanged(updated.clone()));
+
+        // Update tray to show clean state
+        let clean_status = self.read_status(&updated);
+        self.sink.emit(MonitorEvent::StatusUpdated(clean_status));
+        self.sink.emit(MonitorEvent::AutoCleanCompleted(result.clone()));
+
+        // Queue the skipped privileged step for the user instead of prompting
+        if result.requires_password {
+            let newly_pending = !self.approval_pending.swap(true, Ordering::SeqCst);
+            self.sink.emit(MonitorEvent::ApprovalPending);
+            if newly_pending {
+                self.notify(
+                    settings,
+                    &tr(Msg::TitleApproval),
+                    tr(Msg::ApprovalBody),
+                );
+            }
+        }
+
+        // Notify about completion (only if something was actually freed)
+        if result.bytes_freed > 0 {
+            self.notify(
+                settings,
+                &tr(Msg::TitleCleaned),
+                tr_with(Msg::CleanedBody, &[("size", &result.bytes_freed_display)]),
+            );
+        }
+    }
+
+    fn auto_clean_failed(&mut self, settings: &Settings, now: u64, error: String) {
+        let (updated, tripped) = {
+            let mut s = self.settings.lock().unwrap();
+            let max_failures = s.auto_clean_max_failures;
+            let tripped = s.auto_clean_backoff.record_failure(now, error.clone(), max_failures);
+            (s.clone(), tripped)
+        };
+        self.sink.emit(MonitorEvent::SettingsChanged(updated.clone()));
+        let status = self.read_status(&updated);
+        self.sink.emit(MonitorEvent::StatusUpdated(status));
+        self.sink.emit(MonitorEvent::AutoCleanFailed(error.clone()));
+
+        if tripped {
+            self.notify_with_actions(
+                settings,
+                &tr(Msg::TitlePaused),
+                tr_count(
+                    Msg::PausedBody,
+                    updated.auto_clean_backoff.consecutive_failures as u64,
+                    &[("error", &error)],
+                ),
+                vec![NotificationAction::OpenWindow],
+            );
+        }
+    }
+
+    /// Notify on escalation to warning/critical, and remind while it stays there
+    fn notify_threshold(&mut self, settings: &Settings, now: u64, status: &CacheStatus) {
+        let mut alerts = settings.threshold_alerts.clone();
+        let alert = alerts.check(settings, status.state, now);
+        if alerts != settings.threshold_alerts {
+            // Persist so a restart doesn't notify again
+            let updated = {
+                let mut s = self.settings.lock().unwrap();
+                s.threshold_alerts = alerts;
+                s.clone()
+            };
+            self.sink.emit(MonitorEvent::SettingsChanged(updated));
+        }
+
+        let Some(alert) = alert else { return };
+        let actions = vec![
+            NotificationAction::CleanNow,
+            NotificationAction::Snooze {
+                secs: settings.notification_snooze_secs,
+            },
+        ];
+        match status.state {
+            CacheState::Warning | CacheState::Critical => {
+                let (title, body) = match (status.state, alert) {
+                    (CacheState::Warning, ThresholdAlert::Escalated) => (Msg::TitleWarning, Msg::WarningAlert),
+                    (CacheState::Warning, ThresholdAlert::Reminder) => (Msg::TitleWarning, Msg::WarningReminder),
+                    (_, ThresholdAlert::Escalated) => (Msg::TitleCritical, Msg::CriticalAlert),
+                    (_, ThresholdAlert::Reminder) => (Msg::TitleCritical, Msg::CriticalReminder),
+                };
+                let body = tr_with(body, &[("size", &status.size_display)]);
+                self.notify_with_actions(settings, &tr(title), body, actions);
+            }
+            CacheState::Normal => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache_monitor::format_size;
+    use std::sync::atomic::AtomicU64;
+
+    pub const MB: u64 = 1024 * 1024;
+    pub const GB: u64 = 1024 * MB;
+    pub const MINUTE: u64 = 60;
+    pub const HOUR: u64 = 60 * MINUTE;
+    pub const DAY: u64 = 24 * HOUR;
+    pub const START: u64 = 1_700_000_000;
+
+    /// Clock the test advances by hand
+    #[derive(Clone, Default)]
+    pub struct FakeClock {
+        pub wall: Arc<AtomicU64>,
+        pub monotonic: Arc<AtomicU64>,
+    }
+
+    impl FakeClock {
+        /// Let time pass while awake
+        pub fn advance(&self, secs: u64) {
+            self.wall.fetch_add(secs, Ordering::SeqCst);
+            self.monotonic.fetch_add(secs, Ordering::SeqCst);
+        }
+
+        /// Let time pass while asleep (monotonic time stands still)
+        pub fn sleep(&self, secs: u64) {
+            self.wall.fetch_add(secs, Ordering::SeqCst);
+        }
+
+        /// Set the wall clock back
+        pub fn set_back(&self, secs: u64) {
+            self.wall.fetch_sub(secs, Ordering::SeqCst);
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> u64 {
+            self.wall.load(Ordering::SeqCst)
+        }
+
+        fn monotonic(&self) -> Duration {
+            Duration::from_secs(self.monotonic.load(Ordering::SeqCst))
+        }
+    }
+
+    /// Simulated cache whose size the test (and the fake cleaner) controls,
+    /// plus the simulated process list
+    #[derive(Clone, Default)]
+    pub struct FakeCache {
+        pub bytes: Arc<Mutex<u64>>,
+        pub processes: Arc<Mutex<Vec<String>>>,
+        pub on_ac_power: Arc<Mutex<Option<bool>>>,
+        pub idle_secs: Arc<Mutex<Option<u64>>>,
+    }
+
+    impl FakeCache {
+        pub fn grow(&self, bytes: u64) {
+            *self.bytes.lock().unwrap() += bytes;
+        }
+
+        pub fn size(&self) -> u64 {
+            *self.bytes.lock().unwrap()
+        }
+
+        pub fn set_size(&self, bytes: u64) {
+            *self.bytes.lock().unwrap() = bytes;
+        }
+
+        pub fn set_processes(&self, names: &[&str]) {
+            *self.processes.lock().unwrap() = names.iter().map(|n| n.to_string()).collect();
+        }
+    }
+
+    impl StatusProvider for FakeCache {
+        fn cache_status(&mut self, _settings: &Settings) -> CacheStatus {
+            get_simulated_status(self.size())
+        }
+
+        fn daemon_process(&mut self) -> Option<ProcessInfo> {
+            None
+        }
+
+        fn running_processes(&mut self) -> Vec<String> {
+            self.processes.lock().unwrap().clone()
+        }
+
+        fn on_ac_power(&mut self) -> Option<bool> {
+            *self.on_ac_power.lock().unwrap()
+        }
+
+        fn idle_secs(&mut self) -> Option<u64> {
+            *self.idle_secs.lock().unwrap()
+        }
+    }
+
+    /// Cleaner that empties the fake cache, or fails on demand
+    #[derive(Clone, Default)]
+    pub struct FakeCleaner {
+        pub cache: FakeCache,
+        pub fail: Arc<AtomicBool>,
+        pub requires_password: Arc<AtomicBool>,
+    }
+
+    impl Cleaner for FakeCleaner {
+        fn clean(&mut self) -> Result<CleanResult, CleanError> {
+            if self.fail.load(Ordering::SeqCst) {
+                return Err(CleanError::RemovalFailed("disk on fire".to_string()));
+            }
+            let freed = std::mem::take(&mut *self.cache.bytes.lock().unwrap());
+            Ok(CleanResult {
+                success: true,
+                bytes_freed: freed,
+                bytes_freed_display: format_size(freed),
+                files_removed: freed / MB,
+                timestamp: 0,
+                message: String::new(),
+                requires_password: self.requires_password.load(Ordering::SeqCst),
+                was_dry_run: false,
+                items_found: Vec::new(),
+            })
+        }
+    }
+
+    #[derive(Default)]
+    pub struct RecordingSink(pub Vec<MonitorEvent>);
+
+    impl EventSink for RecordingSink {
+        fn emit(&mut self, event: MonitorEvent) {
+            self.0.push(event);
+        }
+    }
+
+    impl RecordingSink {
+        pub fn notifications(&self) -> Vec<String> {
+            self.0
+                .iter()
+                .filter_map(|e| match e {
+                    MonitorEvent::Notify { title, .. } => Some(title.clone()),
+                    _ => None,
+                })
+                .collect()
+        }
+
+        pub fn clean_count(&self) -> usize {
+            self.0
+                .iter()
+                .filter(|e| matches!(e, MonitorEvent::AutoCleanCompleted(_)))
+                .count()
+        }
+
+        pub fn logged(&self) -> Vec<String> {
+            self.0
+                .iter()
+                .filter_map(|e| match e {
+                    MonitorEvent::Logged(message) => Some(message.clone()),
+                    _ => None,
+                })
+                .collect()
+        }
+
+        pub fn failure_count(&self) -> usize {
+            self.0
+                .iter()
+                .filter(|e| matches!(e, MonitorEvent::AutoCleanFailed(_)))
+                .count()
+        }
+    }
+
+    pub struct Harness {
+        pub clock: FakeClock,
+        pub cache: FakeCache,
+        pub cleaner: FakeCleaner,
+        pub settings: Arc<Mutex<Settings>>,
+        pub engine: MonitorEngine<FakeClock, FakeCache, FakeCleaner, RecordingSink>,
+    }
+
+    impl Harness {
+        pub fn new(settings: Settings) -> Self {
+            let clock = FakeClock::default();
+            // Start at a realistic wall-clock time
+            clock.wall.store(START, Ordering::SeqCst);
+            let cache = FakeCache::default();
+            let cleaner = FakeCleaner {
+                cache: cache.clone(),
+                ..Default::default()
+            };
+            let settings = Arc::new(Mutex::new(settings));
+            let engine = MonitorEngine::new(
+                Arc::clone(&settings),
+                Arc::new(AtomicBool::new(false)),
+                Arc::new(Mutex::new(LiveStatus::default())),
+                clock.clone(),
+                cache.clone(),
+                cleaner.clone(),
+                RecordingSink::default(),
+            );
+            Self {
+                clock,
+                cache,
+                cleaner,
+                settings,
+                engine,
+            }
+        }
+
+        /// Run ticks at the engine's interval for `duration` seconds, growing the cache each tick
+        pub fn run(&mut self, duration: u64, growth_per_tick: u64) {
+            let end = self.clock.now() + duration;
+            while self.clock.now() < end {
+                self.clock.advance(self.engine.interval_secs());
+                self.cache.grow(growth_per_tick);
+                self.engine.tick();
+            }
+        }
+
+        pub fn sink(&mut self) -> &mut RecordingSink {
+            self.engine.sink()
+        }
+    }
+
+    #[test]
+    fn test_week_of_threshold_cleans() {
+        let mut h = Harness::new(Settings {
+            auto_clean_on_threshold: true,
+            auto_clean_threshold: 5 * GB,
+            ..Settings::default()
+        });
+
+        // 50 MB/minute hits 5 GB every 103 minutes
+        h.run(7 * DAY, 50 * MB);
+
+        assert_eq!(h.sink().clean_count(), (7 * DAY / (103 * MINUTE)) as usize);
+        assert!(h.cache.size() < 5 * GB);
+        // Auto-clean runs on the tick the cache reaches warning, so no warning is sent
+        let notifications = h.sink().notifications();
+        assert!(notifications.iter().all(|t| t == "SymbolSweep"));
+        assert_eq!(notifications.len(), h.sink().clean_count());
+        assert_ne!(h.settings.lock().unwrap().last_clean_timestamp, 0);
+    }
+
+    #[test]
+    fn test_week_of_scheduled_cleans() {
+        let mut h = Harness::new(Settings {
+            auto_clean_scheduled: true,
+            auto_clean_interval_secs: 6 * HOUR,
+            last_clean_timestamp: 1_700_000_000,
+            ..Settings::default()
+        });
+
+        h.run(7 * DAY, MB);
+        assert_eq!(h.sink().clean_count(), 28);
+    }
+
+    #[test]
+    fn test_threshold_notifications_once_per_escalation() {
+        let mut h = Harness::new(Settings::default());
+
+        // Grow to critical without auto-clean
+        h.run(12 * HOUR, 20 * MB);
+        assert_eq!(h.sink().notifications(), vec!["SymbolSweep - Warning", "SymbolSweep - Critical"]);
+
+        // User cleans manually, then it grows back
+        h.cache.set_size(0);
+        h.run(12 * HOUR, 20 * MB);
+        assert_eq!(h.sink().notifications().len(), 4);
+
+        // Notifications off - nothing more
+        h.settings.lock().unwrap().show_notifications = false;
+        h.cache.set_size(0);
+        h.run(12 * HOUR, 20 * MB);
+        assert_eq!(h.sink().notifications().len(), 4);
+    }
+
+    #[test]
+    fn test_failures_back_off_then_pause() {
+        let mut h = Harness::new(Settings {
+            auto_clean_on_threshold: true,
+            auto_clean_threshold: GB,
+            ..Settings::default()
+        });
+        h.cleaner.fail.store(true, Ordering::SeqCst);
+        h.cache.grow(2 * GB);
+
+        // Attempts at +1m, then +1m backoff, then +2m backoff, then paused
+        h.run(DAY, 0);
+        assert_eq!(h.sink().failure_count(), 3);
+        assert!(h.settings.lock().unwrap().auto_clean_backoff.paused);
+        assert!(h.sink().notifications().contains(&"SymbolSweep - Auto-clean Paused".to_string()));
+
+        // Resuming lets the next tick clean
+        h.cleaner.fail.store(false, Ordering::SeqCst);
+        h.settings.lock().unwrap().auto_clean_backoff.reset();
+        h.run(MINUTE, 0);
+        assert_eq!(h.sink().clean_count(), 1);
+        assert_eq!(h.cache.size(), 0);
+    }
+
+    #[test]
+    fn test_freeing_nothing_counts_as_failure() {
+        let mut h = Harness::new(Settings {
+            auto_clean_on_threshold: true,
+            auto_clean_threshold: GB,
+            ..Settings::default()
+        });
+        h.cache.grow(2 * GB);
+        // Cleaner "succeeds" but the cache it sees is already empty
+        h.cleaner.cache = FakeCache::default();
+        h.engine.cleaner = h.cleaner.clone();
+
+        h.run(MINUTE, 0);
+        assert_eq!(h.sink().failure_count(), 1);
+        assert_eq!(h.settings.lock().unwrap().auto_clean_backoff.consecutive_failures, 1);
+    }
+
+    #[test]
+    fn test_approval_notified_once() {
+        let mut h = Harness::new(Settings {
+            auto_clean_on_threshold: true,
+            auto_clean_threshold: GB,
+            ..Settings::default()
+        });
+        h.cleaner.requires_password.store(true, Ordering::SeqCst);
+
+        h.run(DAY, 100 * MB);
+        let approvals = h
+            .sink()
+            .notifications()
+            .iter()
+            .filter(|t| *t == "SymbolSweep - Approval Needed")
+            .count();
+        assert!(h.sink().clean_count() > 1);
+        assert_eq!(approvals, 1);
+    }
+
+    #[test]
+    fn test_signal_wait_outcomes() {
+        let signal = MonitorSignal::new();
+        assert_eq!(signal.wait(Duration::from_millis(10)), WaitOutcome::Elapsed);
+
+        // Wakes before the wait are not lost, and coalesce into one
+        signal.wake(WakeReason::SettingsChanged);
+        signal.wake(WakeReason::ManualClean);
+        assert_eq!(signal.wait(Duration::from_secs(60)), WaitOutcome::Woken(WakeReason::ManualClean));
+        assert_eq!(signal.wait(Duration::from_millis(10)), WaitOutcome::Elapsed);
+
+        signal.shutdown();
+        assert_eq!(signal.wait(Duration::from_secs(60)), WaitOutcome::Shutdown);
+        assert_eq!(signal.wait(Duration::from_secs(60)), WaitOutcome::Shutdown);
+    }
+
+    /// Sink whose status updates wait for the "main thread", like tray updates do
+    struct MainThreadSink {
+        entered: mpsc::Sender<()>,
+        main_thread: Arc<Mutex<mpsc::Receiver<()>>>,
+    }
+
+    impl EventSink for MainThreadSink {
+        fn emit(&mut self, event: MonitorEvent) {
+            if let MonitorEvent::StatusUpdated(_) = event {
+                let _ = self.entered.send(());
+                let _ = self.main_thread.lock().unwrap().recv();
+            }
+        }
+    }
+
+    #[test]
+    fn test_quitting_during_a_tick_does_not_block_the_main_thread() {
+        let h = Harness::new(Settings {
+            monitor_interval_secs: 3600,
+            ..Settings::default()
+        });
+        let (entered_tx, entered_rx) = mpsc::channel();
+        let (main_tx, main_rx) = mpsc::channel();
+        let mut engine = MonitorEngine::new(
+            Arc::clone(&h.settings),
+            Arc::new(AtomicBool::new(false)),
+            Arc::new(Mutex::new(LiveStatus::default())),
+            h.clock.clone(),
+            h.cache.clone(),
+            h.cleaner.clone(),
+            MainThreadSink {
+                entered: entered_tx,
+                main_thread: Arc::new(Mutex::new(main_rx)),
+            },
+        );
+        let signal = Arc::new(MonitorSignal::new());
+        let thread_signal = Arc::clone(&signal);
+        let handle = std::thread::spawn(move || engine.run(&thread_signal));
+
+        // A tick is now waiting on the main thread
+        signal.wake(WakeReason::SettingsChanged);
+        entered_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        let started = Instant::now();
+        assert!(!stop_monitor(&signal, handle, Duration::from_millis(100)));
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert!(signal.is_shutting_down());
+
+        // Once the main thread runs again the tick finishes and the loop stops
+        drop(main_tx);
+    }
+
+    #[test]
+    fn test_stop_monitor_joins_an_idle_loop() {
+        let h = Harness::new(Settings {
+            monitor_interval_secs: 3600,
+            ..Settings::default()
+        });
+        let signal = Arc::new(MonitorSignal::new());
+        let thread_signal = Arc::clone(&signal);
+        let mut engine = h.engine;
+        let handle = std::thread::spawn(move || engine.run(&thread_signal));
+        assert!(stop_monitor(&signal, handle, Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_sleep_detection() {
+        assert!(!slept_through(Duration::from_secs(30), Duration::from_secs(31)));
+        assert!(slept_through(Duration::from_secs(30), Duration::from_secs(2 * DAY)));
+        // Wall clock set backwards is not a wake
+        assert!(!slept_through(Duration::from_secs(30), Duration::ZERO));
+    }
+
+    #[test]
+    fn test_loop_ticks_on_wake_and_stops_on_shutdown() {
+        let h = Harness::new(Settings {
+            monitor_interval_secs: 3600,
+            ..Settings::default()
+        });
+        let signal = Arc::new(MonitorSignal::new());
+        let mut engine = h.engine;
+
+        let thread_signal = Arc::clone(&signal);
+        let handle = std::thread::spawn(move || {
+            engine.run(&thread_signal);
+            engine
+        });
+
+        let started = Instant::now();
+        signal.wake(WakeReason::SettingsChanged);
+        std::thread::sleep(Duration::from_millis(50));
+        signal.wake(WakeReason::ManualClean);
+        std::thread::sleep(Duration::from_millis(50));
+        signal.shutdown();
+
+        let mut engine = handle.join().unwrap();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        let ticks = engine
+            .sink()
+            .0
+            .iter()
+            .filter(|e| matches!(e, MonitorEvent::StatusUpdated(_)))
+            .count();
+        assert_eq!(ticks, 2);
+    }
+
+    fn scheduled_harness(policy: MissedCleanPolicy) -> Harness {
+        Harness::new(Settings {
+            auto_clean_scheduled: true,
+            auto_clean_interval_secs: 6 * HOUR,
+            last_clean_timestamp: START,
+            missed_clean_policy: policy,
+            ..Settings::default()
+        })
+    }
+
+    #[test]
+    fn test_never_cleaned_interval_counts_from_launch() {
+        let mut h = scheduled_harness(MissedCleanPolicy::Skip);
+        h.settings.lock().unwrap().last_clean_timestamp = 0;
+
+        // Nothing was missed on first launch; the first clean comes one interval later
+        h.run(6 * HOUR - MINUTE, MB);
+        assert_eq!(h.sink().clean_count(), 0);
+        assert!(!h.sink().logged().iter().any(|m| m.starts_with("missed ")));
+
+        h.run(2 * MINUTE, MB);
+        assert_eq!(h.sink().clean_count(), 1);
+    }
+
+    #[test]
+    fn test_clock_set_back_keeps_schedule() {
+        let mut h = scheduled_harness(MissedCleanPolicy::CatchUp);
+
+        h.run(3 * HOUR, MB);
+        h.clock.set_back(DAY);
+        h.run(3 * HOUR - MINUTE, MB);
+        assert_eq!(h.sink().clean_count(), 0);
+
+        // Six hours of real time after the last clean, despite the wall clock saying otherwise
+        h.run(2 * MINUTE, MB);
+        assert_eq!(h.sink().clean_count(), 1);
+        assert!(h.sink().logged().iter().any(|m| m.starts_with("wall clock set back 1 day")));
+    }
+
+    #[test]
+    fn test_future_last_clean_treated_as_now() {
+        let mut h = Harness::new(Settings {
+            auto_clean_scheduled: true,
+            auto_clean_interval_secs: 6 * HOUR,
+            last_clean_timestamp: START + 30 * DAY,
+            ..Settings::default()
+        });
+
+        h.run(6 * HOUR + MINUTE, MB);
+        assert_eq!(h.sink().clean_count(), 1);
+    }
+
+    #[test]
+    fn test_wake_waits_for_grace_then_catches_up_once() {
+        let mut h = scheduled_harness(MissedCleanPolicy::CatchUp);
+
+        h.run(HOUR, MB);
+        h.clock.sleep(2 * DAY);
+        // Grace is 5 minutes - nothing until it passes
+        h.run(4 * MINUTE, MB);
+        assert_eq!(h.sink().clean_count(), 0);
+        let deferral = h.engine.live.lock().unwrap().deferral.clone().unwrap();
+        let DeferralReason::WakeGrace { until } = deferral.reason else {
+            panic!("expected a wake grace deferral, got {:?}", deferral.reason);
+        };
+        assert_eq!(deferral.until, Some(until));
+        assert!(until > h.clock.now() && until <= h.clock.now() + 5 * MINUTE);
+
+        h.run(2 * MINUTE, MB);
+        assert_eq!(h.sink().clean_count(), 1);
+        // Eight missed windows produce a single catch-up clean
+        h.run(5 * HOUR, MB);
+        assert_eq!(h.sink().clean_count(), 1);
+    }
+
+    #[test]
+    fn test_wake_grace_also_holds_threshold_cleans() {
+        let mut h = Harness::new(Settings {
+            auto_clean_on_threshold: true,
+            auto_clean_threshold: GB,
+            ..Settings::default()
+        });
+
+        h.run(MINUTE, 0);
+        h.clock.sleep(8 * HOUR);
+        h.cache.grow(2 * GB);
+        h.run(4 * MINUTE, 0);
+        assert_eq!(h.sink().clean_count(), 0);
+        h.run(2 * MINUTE, 0);
+        assert_eq!(h.sink().clean_count(), 1);
+    }
+
+    #[test]
+    fn test_skip_policy_waits_for_next_window() {
+        let mut h = scheduled_harness(MissedCleanPolicy::Skip);
+
+        // Asleep from +1h to +1d+1h: windows at +6h and +12h/+18h/+24h are missed
+        h.run(HOUR, MB);
+        h.clock.sleep(DAY);
+        h.run(4 * HOUR + MINUTE, MB);
+        assert_eq!(h.sink().clean_count(), 0);
+
+        let logged = h.sink().logged();
+        assert!(logged.iter().any(|m| m.starts_with("wall clock jumped ahead")));
+        assert!(logged.iter().any(|m| m.starts_with("missed 4 scheduled clean(s)")));
+
+        // Next window is +30h
+        h.run(HOUR, MB);
+        assert_eq!(h.sink().clean_count(), 1);
+        assert!(h.settings.lock().unwrap().last_clean_timestamp >= START + 30 * HOUR);
+    }
+
+    #[test]
+    fn test_week_of_weekday_lunch_cleans() {
+        use crate::schedule::{CleanSchedule, TimeOfDay, WeekdayMask};
+
+        let mut h = Harness::new(Settings {
+            auto_clean_scheduled: true,
+            auto_clean_schedule: CleanSchedule::Daily {
+                times: vec![TimeOfDay { hour: 12, minute: 30 }],
+                weekdays: WeekdayMask::WEEKDAYS,
+            },
+            schedule_time_zone: Some("UTC".to_string()),
+            last_clean_timestamp: START,
+            ..Settings::default()
+        });
+
+        // START is a Tuesday, so the following week has five weekday lunches
+        h.run(7 * DAY, MB);
+        assert_eq!(h.sink().clean_count(), 5);
+        // Cleaned on the first tick after 12:30
+        let time_of_day = h.settings.lock().unwrap().last_clean_timestamp % DAY;
+        assert!((12 * HOUR + 30 * MINUTE..12 * HOUR + 31 * MINUTE).contains(&time_of_day));
+    }
+
+    #[test]
+    fn test_never_cleaned_daily_schedule_waits_for_its_first_window() {
+        use crate::schedule::{CleanSchedule, TimeOfDay, WeekdayMask};
+
+        let mut h = Harness::new(Settings {
+            auto_clean_scheduled: true,
+            auto_clean_schedule: CleanSchedule::Daily {
+                times: vec![TimeOfDay { hour: 12, minute: 30 }],
+                weekdays: WeekdayMask::EVERY_DAY,
+            },
+            schedule_time_zone: Some("UTC".to_string()),
+            missed_clean_policy: MissedCleanPolicy::CatchUp,
+            ..Settings::default()
+        });
+
+        // START is Tuesday 22:13 UTC; nothing was missed before the first launch
+        h.run(12 * HOUR, MB);
+        assert_eq!(h.sink().clean_count(), 0);
+        assert!(h.sink().logged().is_empty());
+
+        // Wednesday 12:30
+        h.run(3 * HOUR, MB);
+        assert_eq!(h.sink().clean_count(), 1);
+    }
+
+    #[test]
+    fn test_quiet_hours_hold_notifications_and_cleans() {
+        let mut h = Harness::new(Settings {
+            auto_clean_on_threshold: true,
+            auto_clean_threshold: 5 * GB,
+            quiet_hours_enabled: true,
+            quiet_hours_start: crate::schedule::TimeOfDay { hour: 22, minute: 0 },
+            quiet_hours_end: crate::schedule::TimeOfDay { hour: 8, minute: 0 },
+            quiet_defers_auto_clean: true,
+            schedule_time_zone: Some("UTC".to_string()),
+            ..Settings::default()
+        });
+
+        // START is 22:13 UTC; the cache passes 5 GB overnight but nothing happens until 08:00
+        h.run(9 * HOUR + 40 * MINUTE, 20 * MB);
+        assert_eq!(h.sink().clean_count(), 0);
+        assert!(h.sink().notifications().is_empty());
+        let deferral = h.engine.live.lock().unwrap().deferral.clone().unwrap();
+        assert_eq!(deferral.reason, DeferralReason::QuietHours);
+
+        h.run(10 * MINUTE, 20 * MB);
+        assert_eq!(h.sink().clean_count(), 1);
+        assert!(h.engine.live.lock().unwrap().deferral.is_none());
+        // The held warning and critical alerts arrive as one summary, then the clean notification
+        assert_eq!(h.sink().notifications(), vec!["SymbolSweep - 2 Updates While Quiet", "SymbolSweep"]);
+    }
+
+    #[test]
+    fn test_busy_tools_defer_clean_until_exit() {
+        let mut h = Harness::new(Settings {
+            auto_clean_on_threshold: true,
+            auto_clean_threshold: GB,
+            ..Settings::default()
+        });
+        h.cache.grow(2 * GB);
+        h.cache.set_processes(&["launchd", "Xcode", "lldb"]);
+
+        h.run(HOUR, 0);
+        assert_eq!(h.sink().clean_count(), 0);
+        let deferral = h.engine.live.lock().unwrap().deferral.clone().unwrap();
+        assert_eq!(
+            deferral.reason,
+            DeferralReason::BusyProcesses {
+                names: vec!["Xcode".to_string(), "lldb".to_string()]
+            }
+        );
+        assert_eq!(deferral.since, START + MINUTE);
+        // The deferral is reported in status
+        let reported = h.sink().0.iter().rev().find_map(|e| match e {
+            MonitorEvent::StatusUpdated(status) => Some(status.auto_clean.deferral.clone()),
+            _ => None,
+        });
+        assert_eq!(reported, Some(Some(deferral)));
+
+        h.cache.set_processes(&["launchd"]);
+        h.run(MINUTE, 0);
+        assert_eq!(h.sink().clean_count(), 1);
+        assert!(h.engine.live.lock().unwrap().deferral.is_none());
+    }
+
+    #[test]
+    fn test_busy_deferral_gives_up_after_max() {
+        let mut h = Harness::new(Settings {
+            auto_clean_on_threshold: true,
+            auto_clean_threshold: GB,
+            busy_max_defer_secs: 2 * HOUR,
+            ..Settings::default()
+        });
+        h.cache.grow(2 * GB);
+        h.cache.set_processes(&["Instruments"]);
+
+        h.run(2 * HOUR, 0);
+        assert_eq!(h.sink().clean_count(), 0);
+        h.run(MINUTE, 0);
+        assert_eq!(h.sink().clean_count(), 1);
+        let logged = h.sink().logged();
+        assert!(logged.iter().any(|m| m.starts_with("auto-clean deferred, ")));
+        assert!(logged
+            .iter()
+            .any(|m| m.starts_with("Instruments still running after ") && m.ends_with("; cleaning anyway")));
+        assert!(logged.iter().any(|m| m == "auto-clean no longer deferred"));
+
+        // Disabled - cleans straight away
+        h.settings.lock().unwrap().defer_while_busy = false;
+        h.cache.grow(2 * GB);
+        h.run(MINUTE, 0);
+        assert_eq!(h.sink().clean_count(), 2);
+    }
+
+    #[test]
+    fn test_busy_limit_counts_from_when_tools_block() {
+        let mut h = Harness::new(Settings {
+            auto_clean_on_threshold: true,
+            auto_clean_threshold: GB,
+            require_ac_power: true,
+            busy_max_defer_secs: 2 * HOUR,
+            ..Settings::default()
+        });
+        h.cache.grow(2 * GB);
+        h.cache.set_processes(&["Xcode"]);
+        *h.cache.on_ac_power.lock().unwrap() = Some(false);
+
+        // Held on battery for longer than the busy limit
+        h.run(3 * HOUR, 0);
+        assert_eq!(h.sink().clean_count(), 0);
+
+        // Plugged in with Xcode still running: the busy hold starts now, not at the battery hold
+        *h.cache.on_ac_power.lock().unwrap() = Some(true);
+        h.run(MINUTE, 0);
+        assert_eq!(h.sink().clean_count(), 0);
+        let deferral = h.engine.live.lock().unwrap().deferral.clone().unwrap();
+        assert!(matches!(deferral.reason, DeferralReason::BusyProcesses { .. }));
+        assert_eq!(deferral.until, Some(deferral.since + 2 * HOUR));
+        assert!(deferral.since > START + 3 * HOUR);
+
+        h.run(2 * HOUR, 0);
+        assert_eq!(h.sink().clean_count(), 1);
+    }
+
+    #[test]
+    fn test_power_and_idle_conditions_block_clean() {
+        let mut h = Harness::new(Settings {
+            auto_clean_on_threshold: true,
+            auto_clean_threshold: GB,
+            require_ac_power: true,
+            require_idle: true,
+            require_idle_secs: 10 * MINUTE,
+            ..Settings::default()
+        });
+        h.cache.grow(2 * GB);
+        *h.cache.on_ac_power.lock().unwrap() = Some(false);
+        *h.cache.idle_secs.lock().unwrap() = Some(30);
+
+        let blocking = |h: &Harness| h.engine.live.lock().unwrap().deferral.clone().map(|d| d.reason);
+
+        h.run(HOUR, 0);
+        assert_eq!(h.sink().clean_count(), 0);
+        assert_eq!(blocking(&h), Some(DeferralReason::OnBattery));
+
+        *h.cache.on_ac_power.lock().unwrap() = Some(true);
+        h.run(MINUTE, 0);
+        assert_eq!(h.sink().clean_count(), 0);
+        assert_eq!(blocking(&h), Some(DeferralReason::NotIdle { required_secs: 600 }));
+
+        *h.cache.idle_secs.lock().unwrap() = Some(15 * MINUTE);
+        h.run(MINUTE, 0);
+        assert_eq!(h.sink().clean_count(), 1);
+        assert_eq!(blocking(&h), None);
+    }
+
+    #[test]
+    fn test_unknown_power_state_does_not_block() {
+        let mut h = Harness::new(Settings {
+            auto_clean_on_threshold: true,
+            auto_clean_threshold: GB,
+            require_ac_power: true,
+            require_idle: true,
+            ..Settings::default()
+        });
+        h.cache.grow(2 * GB);
+
+        h.run(MINUTE, 0);
+        assert_eq!(h.sink().clean_count(), 1);
+    }
+
+    #[test]
+    fn test_threshold_reminders_survive_restart() {
+        let mut h = Harness::new(Settings::default());
+        h.cache.set_size(6 * GB);
+
+        h.run(MINUTE, 0);
+        assert_eq!(h.sink().notifications(), vec!["SymbolSweep - Warning"]);
+        let actions = h.sink().0.iter().find_map(|e| match e {
+            MonitorEvent::Notify { actions, .. } => Some(actions.clone()),
+            _ => None,
+        });
+        assert_eq!(
+            actions,
+            Some(vec![NotificationAction::CleanNow, NotificationAction::Snooze { secs: 4 * HOUR }])
+        );
+        h.run(23 * HOUR, 0);
+        assert_eq!(h.sink().notifications().len(), 1);
+        h.run(2 * HOUR, 0);
+        assert_eq!(h.sink().notifications(), vec!["SymbolSweep - Warning", "SymbolSweep - Warning"]);
+
+        // A restart picks up the persisted state instead of notifying again
+        let saved = h.settings.lock().unwrap().clone();
+        let mut restarted = Harness::new(saved);
+        restarted.clock.wall.store(h.clock.now(), Ordering::SeqCst);
+        restarted.cache.set_size(6 * GB);
+        restarted.run(HOUR, 0);
+        assert!(restarted.sink().notifications().is_empty());
+    }
+
+    #[test]
+    fn test_snooze_holds_threshold_notifications() {
+        let mut h = Harness::new(Settings::default());
+        h.settings.lock().unwrap().threshold_alerts.snooze(START, 4 * HOUR);
+
+        h.cache.set_size(11 * GB);
+        h.run(4 * HOUR - 2 * MINUTE, 0);
+        assert!(h.sink().notifications().is_empty());
+        h.run(5 * MINUTE, 0);
+        assert_eq!(h.sink().notifications(), vec!["SymbolSweep - Critical"]);
+    }
+
+    fn monitor_status(h: &Harness) -> (u64, IntervalReason) {
+        let live = h.engine.live.lock().unwrap();
+        (live.monitor.interval_secs, live.monitor.reason)
+    }
+
+    #[test]
+    fn test_fixed_interval_by_default() {
+        let mut h = Harness::new(Settings::default());
+        assert_eq!(h.engine.interval_secs(), 60);
+        h.run(HOUR, 0);
+        assert_eq!(monitor_status(&h), (60, IntervalReason::Fixed));
+    }
+
+    #[test]
+    fn test_adaptive_interval_backs_off_while_flat() {
+        let mut h = Harness::new(Settings {
+            adaptive_interval: true,
+            ..Settings::default()
+        });
+
+        h.run(MINUTE, 0);
+        assert_eq!(monitor_status(&h), (2 * MINUTE, IntervalReason::Steady));
+        h.run(HOUR, 0);
+        assert_eq!(monitor_status(&h), (10 * MINUTE, IntervalReason::Steady));
+
+        // The next scan reports the interval it was scheduled with
+        h.run(MINUTE, 0);
+        let reported = h.sink().0.iter().rev().find_map(|e| match e {
+            MonitorEvent::StatusUpdated(status) => Some(status.monitor.clone()),
+            _ => None,
+        });
+        assert_eq!(reported.map(|m| m.interval_secs), Some(10 * MINUTE));
+    }
+
+    #[test]
+    fn test_adaptive_interval_speeds_up_near_threshold() {
+        let mut h = Harness::new(Settings {
+            adaptive_interval: true,
+            ..Settings::default()
+        });
+        h.cache.set_size(4 * GB + 512 * MB);
+        h.run(MINUTE, 0);
+
+        // 400 MB in two minutes with ~100 MB left before the warning
+        h.run(MINUTE, 400 * MB);
+        assert_eq!(monitor_status(&h), (15, IntervalReason::Growing));
+
+        // Slow growth far from any threshold still backs off
+        h.cache.set_size(GB);
+        h.run(MINUTE, 0);
+        h.run(MINUTE, MB);
+        assert_eq!(monitor_status(&h).1, IntervalReason::Steady);
+    }
+
+    #[test]
+    fn test_adaptive_interval_slower_on_battery() {
+        let mut h = Harness::new(Settings {
+            adaptive_interval: true,
+            ..Settings::default()
+        });
+        *h.cache.on_ac_power.lock().unwrap() = Some(false);
+
+        h.run(MINUTE, 0);
+        assert_eq!(monitor_status(&h), (4 * MINUTE, IntervalReason::OnBattery));
+        h.run(HOUR, 0);
+        assert_eq!(monitor_status(&h), (10 * MINUTE, IntervalReason::Steady));
+    }
+}
@@ -0,0 +1,394 @@
+use serde::{Deserialize, Serialize};
+
+use crate::cache_monitor::CacheState;
+use crate::i18n::{tr_count, Msg};
+use crate::notifier::NotificationAction;
+use crate::schedule::{local_time_of_day, TimeOfDay};
+use crate::scheduler::Settings;
+
+/// Most notifications held for the summary; older ones are dropped first
+const MAX_QUEUED: usize = 20;
+/// Most queued messages quoted in the summary body
+const SUMMARY_LINES: usize = 3;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Why notifications are currently being held back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Suppression {
+    QuietHours,
+    Presenting,
+}
+
+/// A notification ready to show
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub title: String,
+    pub body: String,
+    /// Buttons offered, if the notification backend supports them
+    pub actions: Vec<NotificationAction>,
+}
+
+impl Notification {
+    pub fn new(title: &str, body: String) -> Self {
+        Self {
+            title: title.to_string(),
+            body,
+            actions: Vec::new(),
+        }
+    }
+
+    pub fn with_actions(mut self, actions: Vec<NotificationAction>) -> Self {
+        self.actions = actions;
+        self
+    }
+}
+
+/// Why a threshold notification is due
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThresholdAlert {
+    /// The cache reached a more severe state than already notified
+    Escalated,
+    /// Still in the same state after the reminder interval
+    Reminder,
+}
+
+/// Threshold notification state, persisted so a restart neither repeats nor forgets it
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThresholdAlerts {
+    /// Most severe state notified since the cache was last normal
+    pub notified: Option<CacheState>,
+    /// Unix timestamp of the last threshold notification or reminder
+    pub last_notified_at: u64,
+    /// Unix timestamp before which threshold notifications are held (0 = not snoozed)
+    pub snoozed_until: u64,
+}
+
+impl ThresholdAlerts {
+    /// Whether threshold notifications are snoozed at `now`
+    pub fn is_snoozed(&self, now: u64) -> bool {
+        now < self.snoozed_until
+    }
+
+    /// Hold threshold notifications for `secs` (0 resumes them)
+    pub fn snooze(&mut self, now: u64, secs: u64) {
+        self.snoozed_until = if secs == 0 { 0 } else { now.saturating_add(secs) };
+    }
+
+    /// Whether `state` warrants a notification at `now`, recording it if so
+    /// Escalations held by a snooze are delivered once it ends
+    pub fn check(&mut self, settings: &Settings, state: CacheState, now: u64) -> Option<ThresholdAlert> {
+        let severity = |state: CacheState| match state {
+            CacheState::Normal => 0,
+            CacheState::Warning => 1,
+            CacheState::Critical => 2,
+        };
+
+        if state == CacheState::Normal {
+            self.notified = None;
+            return None;
+        }
+        if self.is_snoozed(now) {
+            return None;
+        }
+
+        let alert = match self.notified {
+            Some(notified) if severity(state) <= severity(notified) => {
+                let interval = match state {
+                    CacheState::Critical => settings.critical_reminder_secs,
+                    _ => settings.warning_reminder_secs,
+                };
+                if interval == 0 || now < self.last_notified_at.saturating_add(interval) {
+                    return None;
+                }
+                ThresholdAlert::Reminder
+            }
+            _ => {
+                self.notified = Some(state);
+                ThresholdAlert::Escalated
+            }
+        };
+        self.last_notified_at = now;
+        Some(alert)
+    }
+
+    /// Shift stored timestamps back after the wall clock was set back
+    pub fn shift_back(&mut self, secs: u64) {
+        self.last_notified_at = self.last_notified_at.saturating_sub(secs);
+        if self.snoozed_until > 0 {
+            self.snoozed_until = self.snoozed_until.saturating_sub(secs);
+        }
+    }
+}
+
+// ============================================================================
+// Policy
+// ============================================================================
+
+/// Decides whether notifications are shown, held for a summary or dropped,
+/// and whether auto-clean should wait for quiet hours or a presentation to end
+#[derive(Debug, Default)]
+pub struct NotificationPolicy {
+    queued: Vec<Notification>,
+}
+
+impl NotificationPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// What is suppressing notifications at `now`, if anything
+    pub fn suppression(settings: &Settings, now: u64) -> Option<Suppression> {
+        if settings.presenting_mode {
+            return Some(Suppression::Presenting);
+        }
+        if settings.quiet_hours_enabled
+            && local_time_of_day(settings, now).is_some_and(|t| {
+                in_window(t, settings.quiet_hours_start, settings.quiet_hours_end)
+            })
+        {
+            return Some(Suppression::QuietHours);
+        }
+        None
+    }
+
+    /// What is holding auto-clean back at `now`, if the user asked for it to wait
+    pub fn auto_clean_deferral(settings: &Settings, now: u64) -> Option<Suppression> {
+        if settings.quiet_defers_auto_clean {
+            Self::suppression(settings, now)
+        } else {
+            None
+        }
+    }
+
+    /// Offer a notification; returns it if it should be shown now
+    /// Suppressed notifications are queued for a summary, and everything is
+    /// dropped when notifications are turned off
+    pub fn submit(&mut self, settings: &Settings, now: u64, notification: Notification) -> Option<Notification> {
+        if !settings.show_notifications {
+            return None;
+        }
+
+        if Self::suppression(settings, now).is_none() {
+            return Some(notification);
+        }
+
+        if self.queued.len() == MAX_QUEUED {
+            self.queued.remove(0);
+        }
+        self.queued.push(notification);
+        None
+    }
+
+    /// Once suppression ends, a single notification summarising what was held back
+    pub fn take_summary(&mut self, settings: &Settings, now: u64) -> Option<Notification> {
+        if self.queued.is_empty() || Self::suppression(settings, now).is_some() {
+            return None;
+        }
+
+        let queued = std::mem::take(&mut self.queued);
+        if !settings.show_notifications {
+            return None;
+        }
+        if let [only] = &queued[..] {
+            return Some(only.clone());
+        }
+
+        let mut lines: Vec<String> = queued.iter().take(SUMMARY_LINES).map(|n| n.body.clone()).collect();
+        if queued.len() > SUMMARY_LINES {
+            lines.push(tr_count(Msg::QuietSummaryMore, (queued.len() - SUMMARY_LINES) as u64, &[]));
+        }
+        // Offer every distinct action from the held notifications
+        let mut actions = Vec::new();
+        for action in queued.iter().flat_map(|n| &n.actions) {
+            if !actions.contains(action) {
+                actions.push(*action);
+            }
+        }
+        Some(Notification {
+            title: tr_count(Msg::TitleQuietSummary, queued.len() as u64, &[]),
+            body: lines.join("\n"),
+            actions,
+        })
+    }
+}
+
+/// Whether `time` falls in [start, end), wrapping past midnight when start > end
+/// An empty window (start == end) never matches
+fn in_window(time: TimeOfDay, start: TimeOfDay, end: TimeOfDay) -> bool {
+    if start <= end {
+        start <= time && time < end
+    } else {
+        time >= start || time < end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: u64 = 3600;
+    /// 2023-11-14 00:00 UTC
+    const MIDNIGHT: u64 = 1_699_920_000;
+
+    fn at(hour: u8, minute: u8) -> TimeOfDay {
+        TimeOfDay { hour, minute }
+    }
+
+    fn quiet_settings() -> Settings {
+        Settings {
+            quiet_hours_enabled: true,
+            quiet_hours_start: at(22, 0),
+            quiet_hours_end: at(8, 0),
+            schedule_time_zone: Some("UTC".to_string()),
+            ..Settings::default()
+        }
+    }
+
+    #[test]
+    fn test_window_wraps_midnight() {
+        assert!(in_window(at(23, 0), at(22, 0), at(8, 0)));
+        assert!(in_window(at(7, 59), at(22, 0), at(8, 0)));
+        assert!(!in_window(at(8, 0), at(22, 0), at(8, 0)));
+        assert!(in_window(at(12, 30), at(12, 0), at(13, 0)));
+        assert!(!in_window(at(13, 0), at(12, 0), at(13, 0)));
+        assert!(!in_window(at(9, 0), at(9, 0), at(9, 0)));
+    }
+
+    #[test]
+    fn test_quiet_hours_queue_then_summarise() {
+        let settings = quiet_settings();
+        let mut policy = NotificationPolicy::new();
+        let night = MIDNIGHT + 2 * HOUR;
+        let morning = MIDNIGHT + 9 * HOUR;
+
+        assert_eq!(NotificationPolicy::suppression(&settings, night), Some(Suppression::QuietHours));
+        let clean = Notification::new("A", "first".to_string()).with_actions(vec![NotificationAction::CleanNow]);
+        assert!(policy.submit(&settings, night, clean).is_none());
+        assert!(policy.submit(&settings, night, Notification::new("B", "second".to_string())).is_none());
+        assert!(policy.take_summary(&settings, night).is_none());
+
+        let summary = policy.take_summary(&settings, morning).unwrap();
+        assert_eq!(summary.title, "SymbolSweep - 2 Updates While Quiet");
+        assert_eq!(summary.body, "first\nsecond");
+        assert_eq!(summary.actions, vec![NotificationAction::CleanNow]);
+        assert!(policy.take_summary(&settings, morning).is_none());
+
+        // Daytime notifications go straight through
+        assert!(policy.submit(&settings, morning, Notification::new("C", "third".to_string())).is_some());
+    }
+
+    #[test]
+    fn test_single_queued_notification_shown_as_is() {
+        let settings = Settings {
+            presenting_mode: true,
+            ..Settings::default()
+        };
+        let mut policy = NotificationPolicy::new();
+        assert!(policy.submit(&settings, MIDNIGHT, Notification::new("SymbolSweep", "Cleaned 5 GB".to_string())).is_none());
+
+        let done = Settings::default();
+        let shown = policy.take_summary(&done, MIDNIGHT).unwrap();
+        assert_eq!(shown.title, "SymbolSweep");
+        assert_eq!(shown.body, "Cleaned 5 GB");
+    }
+
+    #[test]
+    fn test_notifications_off_drops_everything() {
+        let mut settings = quiet_settings();
+        settings.show_notifications = false;
+        let mut policy = NotificationPolicy::new();
+
+        assert!(policy.submit(&settings, MIDNIGHT + 12 * HOUR, Notification::new("A", "x".to_string())).is_none());
+        assert!(policy.submit(&settings, MIDNIGHT, Notification::new("A", "x".to_string())).is_none());
+        assert!(policy.take_summary(&settings, MIDNIGHT + 12 * HOUR).is_none());
+    }
+
+    #[test]
+    fn test_threshold_reminders_per_severity() {
+        let settings = Settings {
+            warning_reminder_secs: 24 * HOUR,
+            critical_reminder_secs: 4 * HOUR,
+            ..Settings::default()
+        };
+        let mut alerts = ThresholdAlerts::default();
+
+        assert_eq!(alerts.check(&settings, CacheState::Normal, MIDNIGHT), None);
+        assert_eq!(alerts.check(&settings, CacheState::Warning, MIDNIGHT), Some(ThresholdAlert::Escalated));
+        assert_eq!(alerts.check(&settings, CacheState::Warning, MIDNIGHT + 23 * HOUR), None);
+        assert_eq!(
+            alerts.check(&settings, CacheState::Critical, MIDNIGHT + 23 * HOUR),
+            Some(ThresholdAlert::Escalated)
+        );
+        assert_eq!(alerts.check(&settings, CacheState::Critical, MIDNIGHT + 26 * HOUR), None);
+        assert_eq!(
+            alerts.check(&settings, CacheState::Critical, MIDNIGHT + 27 * HOUR),
+            Some(ThresholdAlert::Reminder)
+        );
+        // Dropping back to warning isn't a new alert
+        assert_eq!(alerts.check(&settings, CacheState::Warning, MIDNIGHT + 28 * HOUR), None);
+
+        // Back to normal starts over
+        assert_eq!(alerts.check(&settings, CacheState::Normal, MIDNIGHT + 29 * HOUR), None);
+        assert_eq!(
+            alerts.check(&settings, CacheState::Warning, MIDNIGHT + 30 * HOUR),
+            Some(ThresholdAlert::Escalated)
+        );
+    }
+
+    #[test]
+    fn test_zero_reminder_interval_never_reminds() {
+        let settings = Settings {
+            warning_reminder_secs: 0,
+            ..Settings::default()
+        };
+        let mut alerts = ThresholdAlerts::default();
+        assert!(alerts.check(&settings, CacheState::Warning, MIDNIGHT).is_some());
+        assert_eq!(alerts.check(&settings, CacheState::Warning, MIDNIGHT + 365 * 24 * HOUR), None);
+    }
+
+    #[test]
+    fn test_snooze_holds_alerts_until_it_ends() {
+        let settings = Settings::default();
+        let mut alerts = ThresholdAlerts::default();
+        alerts.check(&settings, CacheState::Warning, MIDNIGHT);
+
+        alerts.snooze(MIDNIGHT, 4 * HOUR);
+        assert!(alerts.is_snoozed(MIDNIGHT + HOUR));
+        assert_eq!(alerts.check(&settings, CacheState::Critical, MIDNIGHT + HOUR), None);
+        assert_eq!(
+            alerts.check(&settings, CacheState::Critical, MIDNIGHT + 4 * HOUR),
+            Some(ThresholdAlert::Escalated)
+        );
+
+        alerts.snooze(MIDNIGHT + 5 * HOUR, 4 * HOUR);
+        alerts.snooze(MIDNIGHT + 5 * HOUR, 0);
+        assert!(!alerts.is_snoozed(MIDNIGHT + 5 * HOUR));
+    }
+
+    #[test]
+    fn test_threshold_alerts_round_trip() {
+        let alerts = ThresholdAlerts {
+            notified: Some(CacheState::Critical),
+            last_notified_at: MIDNIGHT,
+            snoozed_until: MIDNIGHT + HOUR,
+        };
+        let json = serde_json::to_string(&alerts).unwrap();
+        assert_eq!(serde_json::from_str::<ThresholdAlerts>(&json).unwrap(), alerts);
+    }
+
+    #[test]
+    fn test_auto_clean_deferral_is_opt_in() {
+        let mut settings = quiet_settings();
+        assert_eq!(NotificationPolicy::auto_clean_deferral(&settings, MIDNIGHT), None);
+
+        settings.quiet_defers_auto_clean = true;
+        assert_eq!(
+            NotificationPolicy::auto_clean_deferral(&settings, MIDNIGHT),
+            Some(Suppression::QuietHours)
+        );
+        assert_eq!(NotificationPolicy::auto_clean_deferral(&settings, MIDNIGHT + 12 * HOUR), None);
+    }
+}
@@ -0,0 +1,488 @@
+use serde::{Deserialize, Serialize};
+
+use crate::command_runner::{CommandError, CommandRunner, SystemCommandRunner, ACTION_TIMEOUT, NOTIFY_TIMEOUT};
+use crate::i18n::{tr, tr_with, Msg};
+use crate::notification_policy::Notification;
+use crate::scheduler::format_duration;
+
+/// App name shown by the notification server
+const APP_NAME: &str = "SymbolSweep";
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// A button on a notification that routes back into the app
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NotificationAction {
+    /// Clean the cache now
+    CleanNow,
+    /// Open the SymbolSweep window
+    OpenWindow,
+    /// Hold cache alerts for a while
+    Snooze { secs: u64 },
+}
+
+impl NotificationAction {
+    /// Stable identifier passed to the notification backend
+    pub fn id(&self) -> String {
+        match self {
+            NotificationAction::CleanNow => "clean".to_string(),
+            NotificationAction::OpenWindow => "open".to_string(),
+            NotificationAction::Snooze { secs } => format!("snooze-{}", secs),
+        }
+    }
+
+    /// Parse an identifier produced by `id`
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "clean" => Some(NotificationAction::CleanNow),
+            "open" => Some(NotificationAction::OpenWindow),
+            _ => id
+                .strip_prefix("snooze-")
+                .and_then(|secs| secs.parse().ok())
+                .map(|secs| NotificationAction::Snooze { secs }),
+        }
+    }
+
+    /// Button text
+    pub fn label(&self) -> String {
+        match self {
+            NotificationAction::CleanNow => tr(Msg::ActionCleanNow),
+            NotificationAction::OpenWindow => tr(Msg::ActionOpenWindow),
+            NotificationAction::Snooze { secs } => tr_with(Msg::ActionSnooze, &[("duration", &format_duration(*secs))]),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotifyError {
+    /// The backend's tool or service isn't available
+    Unavailable(String),
+    /// The backend is available but couldn't show the notification
+    Failed(String),
+}
+
+impl std::fmt::Display for NotifyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotifyError::Unavailable(msg) => write!(f, "Notifications unavailable: {}", msg),
+            NotifyError::Failed(msg) => write!(f, "Notification failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for NotifyError {}
+
+impl From<CommandError> for NotifyError {
+    fn from(error: CommandError) -> Self {
+        match error {
+            CommandError::SpawnFailed { .. } => NotifyError::Unavailable(error.to_string()),
+            _ => NotifyError::Failed(error.to_string()),
+        }
+    }
+}
+
+// ============================================================================
+// Backends
+// ============================================================================
+
+/// One way of showing notifications
+pub trait NotifierBackend: Send + Sync {
+    /// Name for logging
+    fn name(&self) -> &'static str;
+    /// Whether this backend shows buttons and reports which one was clicked
+    fn supports_actions(&self) -> bool;
+    /// Show a notification; with actions this blocks until one is clicked,
+    /// the notification is dismissed or `ACTION_TIMEOUT` passes
+    fn show(&self, notification: &Notification) -> Result<Option<NotificationAction>, NotifyError>;
+}
+
+/// Run a notification helper, treating a timeout while waiting for a click as "no action"
+fn run_waiting<C: CommandRunner>(runner: &C, program: &str, args: &[&str]) -> Result<Option<String>, NotifyError> {
+    match runner.run(program, args, ACTION_TIMEOUT) {
+        Ok(output) if output.success() => Ok(Some(output.stdout.trim().to_string())),
+        Ok(output) => Err(NotifyError::Failed(format!("{} exited with {:?}: {}", program, output.code, output.stderr.trim()))),
+        Err(CommandError::TimedOut { .. }) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Run a notification helper that returns as soon as the notification is posted
+fn run_quick<C: CommandRunner>(runner: &C, program: &str, args: &[&str]) -> Result<(), NotifyError> {
+    let output = runner.run(program, args, NOTIFY_TIMEOUT)?;
+    if output.success() {
+        Ok(())
+    } else {
+        Err(NotifyError::Failed(format!("{} exited with {:?}: {}", program, output.code, output.stderr.trim())))
+    }
+}
+
+/// freedesktop notifications through `notify-send` (Linux)
+/// Actions need libnotify 0.7.9+; older versions fall back to a plain notification
+pub struct FreedesktopBackend<C: CommandRunner> {
+    runner: C,
+}
+
+impl<C: CommandRunner> FreedesktopBackend<C> {
+    pub fn new(runner: C) -> Self {
+        Self { runner }
+    }
+}
+
+impl<C: CommandRunner> NotifierBackend for FreedesktopBackend<C> {
+    fn name(&self) -> &'static str {
+        "notify-send"
+    }
+
+    fn supports_actions(&self) -> bool {
+        true
+    }
+
+    fn show(&self, notification: &Notification) -> Result<Option<NotificationAction>, NotifyError> {
+        let app_name = format!("--app-name={}", APP_NAME);
+        if !notification.actions.is_empty() {
+            // notify-send prints the id of the clicked action and exits when the notification closes
+            let actions: Vec<String> = notification
+                .actions
+                .iter()
+                .map(|a| format!("--action={}={}", a.id(), a.label()))
+                .collect();
+            let mut args = vec![app_name.as_str(), "--wait"];
+            args.extend(actions.iter().map(String::as_str));
+            args.extend([notification.title.as_str(), notification.body.as_str()]);
+
+            match run_waiting(&self.runner, "notify-send", &args) {
+                Ok(clicked) => return Ok(clicked.as_deref().and_then(NotificationAction::from_id)),
+                Err(NotifyError::Failed(e)) => eprintln!("notify-send without actions: {}", e),
+                Err(e) => return Err(e),
+            }
+        }
+
+        run_quick(
+            &self.runner,
+            "notify-send",
+            &[&app_name, &notification.title, &notification.body],
+        )?;
+        Ok(None)
+    }
+}
+
+/// `alerter` (macOS), a terminal-notifier fork that shows buttons and prints the one clicked
+pub struct AlerterBackend<C: CommandRunner> {
+    runner: C,
+}
+
+impl<C: CommandRunner> AlerterBackend<C> {
+    pub fn new(runner: C) -> Self {
+        Self { runner }
+    }
+}
+
+impl<C: CommandRunner> NotifierBackend for AlerterBackend<C> {
+    fn name(&self) -> &'static str {
+        "alerter"
+    }
+
+    fn supports_actions(&self) -> bool {
+        true
+    }
+
+    fn show(&self, notification: &Notification) -> Result<Option<NotificationAction>, NotifyError> {
+        let mut args = vec!["-title", &notification.title, "-message", &notification.body, "-group", APP_NAME];
+        if notification.actions.is_empty() {
+            run_quick(&self.runner, "alerter", &args)?;
+            return Ok(None);
+        }
+
+        let labels: Vec<String> = notification.actions.iter().map(NotificationAction::label).collect();
+        let actions = labels.join(",");
+        let timeout = ACTION_TIMEOUT.as_secs().to_string();
+        args.extend(["-timeout", &timeout, "-actions", &actions]);
+
+        // Prints the clicked button's label, or @CONTENTCLICKED / @CLOSED / @TIMEOUT
+        let clicked = run_waiting(&self.runner, "alerter", &args)?;
+        Ok(clicked.and_then(|clicked| match clicked.as_str() {
+            "@CONTENTCLICKED" => Some(NotificationAction::OpenWindow),
+            label => notification.actions.iter().find(|a| a.label() == label).copied(),
+        }))
+    }
+}
+
+/// `terminal-notifier` (macOS, if installed via Homebrew) - no buttons
+pub struct TerminalNotifierBackend<C: CommandRunner> {
+    runner: C,
+}
+
+impl<C: CommandRunner> TerminalNotifierBackend<C> {
+    pub fn new(runner: C) -> Self {
+        Self { runner }
+    }
+}
+
+impl<C: CommandRunner> NotifierBackend for TerminalNotifierBackend<C> {
+    fn name(&self) -> &'static str {
+        "terminal-notifier"
+    }
+
+    fn supports_actions(&self) -> bool {
+        false
+    }
+
+    fn show(&self, notification: &Notification) -> Result<Option<NotificationAction>, NotifyError> {
+        run_quick(
+            &self.runner,
+            "terminal-notifier",
+            &["-title", &notification.title, "-message", &notification.body, "-sound", "default"],
+        )?;
+        Ok(None)
+    }
+}
+
+/// `osascript` (macOS, may require Script Editor permissions) - no buttons
+pub struct OsascriptBackend<C: CommandRunner> {
+    runner: C,
+}
+
+impl<C: CommandRunner> OsascriptBackend<C> {
+    pub fn new(runner: C) -> Self {
+        Self { runner }
+    }
+}
+
+impl<C: CommandRunner> NotifierBackend for OsascriptBackend<C> {
+    fn name(&self) -> &'static str {
+        "osascript"
+    }
+
+    fn supports_actions(&self) -> bool {
+        false
+    }
+
+    fn show(&self, notification: &Notification) -> Result<Option<NotificationAction>, NotifyError> {
+        let script = format!(
+            r#"display notification "{}" with title "{}" sound name "Glass""#,
+            notification.body.replace('"', r#"\""#),
+            notification.title.replace('"', r#"\""#),
+        );
+        run_quick(&self.runner, "osascript", &["-e", &script])?;
+        Ok(None)
+    }
+}
+
+// ============================================================================
+// Notifier
+// ============================================================================
+
+/// Shows notifications through the best backend that works
+pub struct Notifier {
+    backends: Vec<Box<dyn NotifierBackend>>,
+}
+
+impl Notifier {
+    /// Backends are tried in the order given, except that notifications with
+    /// actions try backends that support them first
+    pub fn new(backends: Vec<Box<dyn NotifierBackend>>) -> Self {
+        Self { backends }
+    }
+
+    /// Command-line backends for the current platform
+    pub fn platform_backends() -> Vec<Box<dyn NotifierBackend>> {
+        if cfg!(target_os = "macos") {
+            vec![
+                Box::new(AlerterBackend::new(SystemCommandRunner)),
+                Box::new(TerminalNotifierBackend::new(SystemCommandRunner)),
+                Box::new(OsascriptBackend::new(SystemCommandRunner)),
+            ]
+        } else {
+            vec![Box::new(FreedesktopBackend::new(SystemCommandRunner))]
+        }
+    }
+
+    /// Show a notification, returning the action the user picked (if any)
+    pub fn show(&self, notification: &Notification) -> Result<Option<NotificationAction>, NotifyError> {
+        let mut order: Vec<&dyn NotifierBackend> = self.backends.iter().map(|b| b.as_ref()).collect();
+        if !notification.actions.is_empty() {
+            // Stable sort keeps the configured order within each group
+            order.sort_by_key(|b| !b.supports_actions());
+        }
+
+        let mut last_error = NotifyError::Unavailable("no notification backends".to_string());
+        for backend in order {
+            match backend.show(notification) {
+                Ok(action) => return Ok(action),
+                Err(e) => {
+                    eprintln!("Notifier: {} failed: {}", backend.name(), e);
+                    last_error = e;
+                }
+            }
+        }
+        Err(last_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_runner::{CommandOutput, RecordingRunner};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    fn notification(actions: Vec<NotificationAction>) -> Notification {
+        Notification {
+            title: "SymbolSweep - Warning".to_string(),
+            body: "Cache at 6 GB - consider cleaning soon".to_string(),
+            actions,
+        }
+    }
+
+    /// Backend that records what it was asked to show
+    struct FakeBackend {
+        name: &'static str,
+        actions: bool,
+        result: Result<Option<NotificationAction>, NotifyError>,
+        shown: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    impl NotifierBackend for FakeBackend {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn supports_actions(&self) -> bool {
+            self.actions
+        }
+
+        fn show(&self, _: &Notification) -> Result<Option<NotificationAction>, NotifyError> {
+            self.shown.lock().unwrap().push(self.name);
+            self.result.clone()
+        }
+    }
+
+    #[test]
+    fn test_action_ids_round_trip() {
+        for action in [
+            NotificationAction::CleanNow,
+            NotificationAction::OpenWindow,
+            NotificationAction::Snooze { secs: 4 * 3600 },
+        ] {
+            assert_eq!(NotificationAction::from_id(&action.id()), Some(action));
+        }
+        assert_eq!(NotificationAction::Snooze { secs: 4 * 3600 }.label(), "Snooze 4 hours");
+        assert_eq!(NotificationAction::from_id("snooze-soon"), None);
+        assert_eq!(NotificationAction::from_id(""), None);
+    }
+
+    #[test]
+    fn test_freedesktop_reports_clicked_action() {
+        // Stands in for a notification daemon: the user clicked "Snooze"
+        let runner = RecordingRunner::new().respond("notify-send", Ok(CommandOutput::ok("snooze-14400\n")));
+        let backend = FreedesktopBackend::new(runner);
+        let shown = notification(vec![NotificationAction::CleanNow, NotificationAction::Snooze { secs: 14400 }]);
+
+        assert_eq!(backend.show(&shown), Ok(Some(NotificationAction::Snooze { secs: 14400 })));
+        let calls = backend.runner.calls();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].timeout, ACTION_TIMEOUT);
+        assert_eq!(
+            calls[0].args,
+            vec![
+                "--app-name=SymbolSweep",
+                "--wait",
+                "--action=clean=Clean Now",
+                "--action=snooze-14400=Snooze 4 hours",
+                "SymbolSweep - Warning",
+                "Cache at 6 GB - consider cleaning soon",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_freedesktop_dismissed_or_timed_out() {
+        let runner = RecordingRunner::new()
+            .respond("notify-send", Ok(CommandOutput::ok("")))
+            .respond(
+                "notify-send",
+                Err(CommandError::TimedOut {
+                    program: "notify-send".to_string(),
+                    timeout: Duration::from_secs(600),
+                }),
+            );
+        let backend = FreedesktopBackend::new(runner);
+        let shown = notification(vec![NotificationAction::CleanNow]);
+
+        assert_eq!(backend.show(&shown), Ok(None));
+        assert_eq!(backend.show(&shown), Ok(None));
+    }
+
+    #[test]
+    fn test_freedesktop_falls_back_without_action_support() {
+        let runner = RecordingRunner::new()
+            .respond("notify-send", Ok(CommandOutput::failed(1, "Unknown option --wait")))
+            .respond("notify-send", Ok(CommandOutput::ok("")));
+        let backend = FreedesktopBackend::new(runner);
+
+        assert_eq!(backend.show(&notification(vec![NotificationAction::CleanNow])), Ok(None));
+        let calls = backend.runner.calls();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[1].timeout, NOTIFY_TIMEOUT);
+        assert!(!calls[1].args.iter().any(|a| a.starts_with("--action")));
+    }
+
+    #[test]
+    fn test_alerter_maps_labels_and_content_click() {
+        let runner = RecordingRunner::new()
+            .respond("alerter", Ok(CommandOutput::ok("Clean Now\n")))
+            .respond("alerter", Ok(CommandOutput::ok("@CONTENTCLICKED\n")))
+            .respond("alerter", Ok(CommandOutput::ok("@CLOSED\n")));
+        let backend = AlerterBackend::new(runner);
+        let shown = notification(vec![NotificationAction::CleanNow, NotificationAction::Snooze { secs: 3600 }]);
+
+        assert_eq!(backend.show(&shown), Ok(Some(NotificationAction::CleanNow)));
+        assert_eq!(backend.show(&shown), Ok(Some(NotificationAction::OpenWindow)));
+        assert_eq!(backend.show(&shown), Ok(None));
+        let args = &backend.runner.calls()[0].args;
+        let actions = args.iter().position(|a| a == "-actions").unwrap();
+        assert_eq!(args[actions + 1], "Clean Now,Snooze 1 hour");
+    }
+
+    #[test]
+    fn test_alerter_without_actions_does_not_wait() {
+        let runner = RecordingRunner::new().respond("alerter", Ok(CommandOutput::ok("")));
+        let backend = AlerterBackend::new(runner);
+
+        assert_eq!(backend.show(&notification(Vec::new())), Ok(None));
+        let calls = backend.runner.calls();
+        assert_eq!(calls[0].timeout, NOTIFY_TIMEOUT);
+        assert!(!calls[0].args.iter().any(|a| a == "-timeout" || a == "-actions"));
+    }
+
+    #[test]
+    fn test_notifier_prefers_action_backends_then_falls_back() {
+        let shown = Arc::new(Mutex::new(Vec::new()));
+        let backend = |name, actions, result| -> Box<dyn NotifierBackend> {
+            Box::new(FakeBackend {
+                name,
+                actions,
+                result,
+                shown: Arc::clone(&shown),
+            })
+        };
+        let notifier = Notifier::new(vec![
+            backend("plain", false, Ok(None)),
+            backend("buttons", true, Err(NotifyError::Unavailable("not installed".to_string()))),
+        ]);
+
+        // Plain notifications use the first backend
+        assert_eq!(notifier.show(&notification(Vec::new())), Ok(None));
+        assert_eq!(*shown.lock().unwrap(), vec!["plain"]);
+
+        // Actions try the action-capable backend first, then fall back
+        shown.lock().unwrap().clear();
+        assert_eq!(notifier.show(&notification(vec![NotificationAction::CleanNow])), Ok(None));
+        assert_eq!(*shown.lock().unwrap(), vec!["buttons", "plain"]);
+
+        let empty = Notifier::new(Vec::new());
+        assert!(matches!(empty.show(&notification(Vec::new())), Err(NotifyError::Unavailable(_))));
+    }
+}
@@ -0,0 +1,205 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::command_runner::{CommandRunner, SystemCommandRunner, QUICK_TIMEOUT};
+
+/// Where Linux exposes batteries and chargers
+const SYSFS_POWER_SUPPLY: &str = "/sys/class/power_supply";
+
+/// Source of power and user-activity state
+/// Implemented over `pmset`/`ioreg` on macOS and sysfs on Linux so tests can run against fixtures
+pub trait PowerSource: Send + Sync {
+    /// Whether the machine is running on AC power (None if unknown)
+    fn on_ac_power(&self) -> Option<bool>;
+    /// Seconds since the last keyboard or mouse input (None if unknown)
+    fn idle_secs(&self) -> Option<u64>;
+}
+
+/// Get the power source for the current platform
+/// Uses sysfs on Linux and `pmset`/`ioreg` everywhere else (macOS)
+pub fn default_power_source() -> Box<dyn PowerSource> {
+    if cfg!(target_os = "linux") {
+        Box::new(SysfsPowerSource::new(SYSFS_POWER_SUPPLY))
+    } else {
+        Box::new(PmsetPowerSource::new(SystemCommandRunner))
+    }
+}
+
+// ============================================================================
+// pmset/ioreg backend (macOS)
+// ============================================================================
+
+/// Reads power state from `pmset` and idle time from `ioreg`
+pub struct PmsetPowerSource<C: CommandRunner> {
+    runner: C,
+}
+
+impl<C: CommandRunner> PmsetPowerSource<C> {
+    pub fn new(runner: C) -> Self {
+        Self { runner }
+    }
+
+    fn stdout(&self, program: &str, args: &[&str]) -> Option<String> {
+        let output = self.runner.run(program, args, QUICK_TIMEOUT).ok()?;
+        output.success().then_some(output.stdout)
+    }
+}
+
+impl<C: CommandRunner> PowerSource for PmsetPowerSource<C> {
+    fn on_ac_power(&self) -> Option<bool> {
+        // "Now drawing from 'AC Power'" / 'Battery Power' / 'UPS Power'
+        let output = self.stdout("pmset", &["-g", "batt"])?;
+        let source = output.split('\'').nth(1)?;
+        Some(source == "AC Power")
+    }
+
+    fn idle_secs(&self) -> Option<u64> {
+        // `"HIDIdleTime" = 1234567890` in nanoseconds
+        let output = self.stdout("ioreg", &["-c", "IOHIDSystem", "-d", "4", "-r", "-k", "HIDIdleTime"])?;
+        output
+            .lines()
+            .find_map(|line| line.split_once("\"HIDIdleTime\" = "))
+            .and_then(|(_, value)| value.trim().parse::<u64>().ok())
+            .map(|nanos| nanos / 1_000_000_000)
+    }
+}
+
+// ============================================================================
+// sysfs backend (Linux)
+// ============================================================================
+
+/// Reads power state from a /sys/class/power_supply tree
+/// The root is configurable so tests can point it at a fixture directory
+pub struct SysfsPowerSource {
+    root: PathBuf,
+}
+
+impl SysfsPowerSource {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// (type, online, status) of every supply, missing attributes read as empty
+    fn supplies(&self) -> Vec<(String, String, String)> {
+        let read = |dir: &Path, attr: &str| {
+            fs::read_to_string(dir.join(attr))
+                .map(|v| v.trim().to_string())
+                .unwrap_or_default()
+        };
+
+        fs::read_dir(&self.root)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|e| e.path())
+                    .map(|dir| (read(&dir, "type"), read(&dir, "online"), read(&dir, "status")))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl PowerSource for SysfsPowerSource {
+    fn on_ac_power(&self) -> Option<bool> {
+        let supplies = self.supplies();
+        let is_charger = |kind: &str| kind == "Mains" || kind == "USB";
+
+        if supplies.iter().any(|(kind, online, _)| is_charger(kind) && online == "1") {
+            return Some(true);
+        }
+        if supplies
+            .iter()
+            .any(|(kind, _, status)| kind == "Battery" && status == "Discharging")
+        {
+            return Some(false);
+        }
+        // A charger that is present but offline means battery power
+        if supplies.iter().any(|(kind, _, _)| is_charger(kind)) {
+            return Some(false);
+        }
+        None
+    }
+
+    fn idle_secs(&self) -> Option<u64> {
+        // sysfs has no notion of user input
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_runner::{CommandOutput, RecordingRunner};
+
+    /// (directory name, type, online, status)
+    type Supply<'a> = (&'a str, &'a str, Option<&'a str>, Option<&'a str>);
+
+    /// Build a power_supply tree in a fresh temp directory
+    fn sysfs_fixture(test_name: &str, supplies: &[Supply]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("symbolsweep-power-{}-{}", test_name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        for (name, kind, online, status) in supplies {
+            let dir = root.join(name);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("type"), format!("{}\n", kind)).unwrap();
+            if let Some(online) = online {
+                fs::write(dir.join("online"), format!("{}\n", online)).unwrap();
+            }
+            if let Some(status) = status {
+                fs::write(dir.join("status"), format!("{}\n", status)).unwrap();
+            }
+        }
+        root
+    }
+
+    #[test]
+    fn test_sysfs_power_states() {
+        let cases: [(&str, &[Supply], Option<bool>); 4] = [
+            (
+                "plugged",
+                &[("AC", "Mains", Some("1"), None), ("BAT0", "Battery", None, Some("Charging"))],
+                Some(true),
+            ),
+            (
+                "unplugged",
+                &[("AC", "Mains", Some("0"), None), ("BAT0", "Battery", None, Some("Discharging"))],
+                Some(false),
+            ),
+            ("usb-c", &[("ucsi-source-psy-1", "USB", Some("1"), None)], Some(true)),
+            ("desktop", &[], None),
+        ];
+
+        for (name, supplies, expected) in cases {
+            let root = sysfs_fixture(name, supplies);
+            assert_eq!(SysfsPowerSource::new(&root).on_ac_power(), expected, "{}", name);
+            assert_eq!(SysfsPowerSource::new(&root).idle_secs(), None);
+            let _ = fs::remove_dir_all(&root);
+        }
+    }
+
+    #[test]
+    fn test_pmset_power_source() {
+        let runner = RecordingRunner::new()
+            .respond(
+                "pmset",
+                Ok(CommandOutput::ok(
+                    "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=1234)\t85%; discharging; 5:12 remaining present: true\n",
+                )),
+            )
+            .respond(
+                "ioreg",
+                Ok(CommandOutput::ok(
+                    "+-o IOHIDSystem  <class IOHIDSystem>\n    {\n      \"HIDIdleTime\" = 754000000000\n    }\n",
+                )),
+            );
+        let source = PmsetPowerSource::new(runner);
+        assert_eq!(source.on_ac_power(), Some(false));
+        assert_eq!(source.idle_secs(), Some(754));
+
+        let runner = RecordingRunner::new().respond("pmset", Ok(CommandOutput::ok("Now drawing from 'AC Power'\n")));
+        assert_eq!(PmsetPowerSource::new(runner).on_ac_power(), Some(true));
+        // Missing tools mean unknown, not blocked
+        assert_eq!(PmsetPowerSource::new(RecordingRunner::new()).idle_secs(), None);
+    }
+}
@@ -0,0 +1,417 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::command_runner::{CommandRunner, SystemCommandRunner, QUICK_TIMEOUT};
+
+/// Kernel clock ticks per second used by /proc/<pid>/stat (USER_HZ)
+/// This is 100 on every mainstream Linux configuration
+const PROC_TICKS_PER_SEC: u64 = 100;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Point-in-time details about a running process
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    /// Unix timestamp the process started at
+    pub start_time: u64,
+    /// Seconds since the process started
+    pub uptime_secs: u64,
+    /// Resident memory in bytes
+    pub resident_bytes: u64,
+    /// Total CPU time (user + system) consumed since start, in seconds
+    pub cpu_time_secs: f64,
+    /// CPU usage as a percentage of one core
+    /// macOS reports the kernel's decaying average; /proc reports the lifetime average
+    pub cpu_percent: f64,
+}
+
+/// Source of process details
+/// Implemented over `ps` on macOS and `/proc` on Linux so tests can run against fixtures
+pub trait ProcessInfoSource: Send + Sync {
+    /// Find a process by exact executable name
+    fn find_process(&self, name: &str) -> Option<ProcessInfo>;
+    /// Executable names of all running processes, sorted and deduplicated
+    fn running_names(&self) -> Vec<String>;
+}
+
+/// Get the process info source for the current platform
+/// Uses /proc where it exists (Linux) and `ps` everywhere else (macOS)
+pub fn default_source() -> Box<dyn ProcessInfoSource> {
+    if Path::new("/proc/self/stat").exists() {
+        Box::new(ProcfsSource::new("/proc"))
+    } else {
+        Box::new(PsSource::new(SystemCommandRunner))
+    }
+}
+
+fn current_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs()
+}
+
+// ============================================================================
+// ps backend (macOS)
+// ============================================================================
+
+/// Reads process details from `ps` output
+pub struct PsSource<C: CommandRunner> {
+    runner: C,
+}
+
+impl<C: CommandRunner> PsSource<C> {
+    pub fn new(runner: C) -> Self {
+        Self { runner }
+    }
+}
+
+impl Default for PsSource<SystemCommandRunner> {
+    fn default() -> Self {
+        Self::new(SystemCommandRunner)
+    }
+}
+
+impl<C: CommandRunner> ProcessInfoSource for PsSource<C> {
+    fn find_process(&self, name: &str) -> Option<ProcessInfo> {
+        // comm goes last because it is a full path that may contain spaces
+        let output = self
+            .runner
+            .run("ps", &["-A", "-o", "pid=,etime=,rss=,%cpu=,time=,comm="], QUICK_TIMEOUT)
+            .ok()?;
+        if !output.success() {
+            return None;
+        }
+
+        let now = current_timestamp();
+        output
+            .stdout
+            .lines()
+            .find_map(|line| parse_ps_line(line, name, now))
+    }
+
+    fn running_names(&self) -> Vec<String> {
+        let output = match self.runner.run("ps", &["-A", "-o", "comm="], QUICK_TIMEOUT) {
+            Ok(output) if output.success() => output,
+            _ => return Vec::new(),
+        };
+
+        let mut names: Vec<String> = output
+            .stdout
+            .lines()
+            .filter_map(|line| Path::new(line.trim()).file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+}
+
+/// Parse one line of `ps -o pid=,etime=,rss=,%cpu=,time=,comm=` if its command matches `name`
+fn parse_ps_line(line: &str, name: &str, now: u64) -> Option<ProcessInfo> {
+    let mut fields = line.split_whitespace();
+    let pid = fields.next()?.parse().ok()?;
+    let uptime_secs = parse_clock(fields.next()?)? as u64;
+    let rss_kb: u64 = fields.next()?.parse().ok()?;
+    let cpu_percent = fields.next()?.parse().ok()?;
+    let cpu_time_secs = parse_clock(fields.next()?)?;
+    let comm = fields.collect::<Vec<_>>().join(" ");
+
+    let executable = Path::new(&comm).file_name()?.to_string_lossy();
+    if executable != name {
+        return None;
+    }
+
+    Some(ProcessInfo {
+        pid,
+        start_time: now.saturating_sub(uptime_secs),
+        uptime_secs,
+        resident_bytes: rss_kb * 1024,
+        cpu_time_secs,
+        cpu_percent,
+    })
+}
+
+/// Parse a ps clock value such as "05:12", "01:05:12", "3-01:05:12" or "12:34.56" into seconds
+fn parse_clock(value: &str) -> Option<f64> {
+    let (days, clock) = match value.split_once('-') {
+        Some((days, rest)) => (days.parse::<f64>().ok()?, rest),
+        None => (0.0, value),
+    };
+
+    let mut secs = 0.0;
+    for part in clock.split(':') {
+        secs = secs * 60.0 + part.parse::<f64>().ok()?;
+    }
+
+    Some(days * 86400.0 + secs)
+}
+
+// ============================================================================
+// /proc backend (Linux)
+// ============================================================================
+
+/// Reads process details from a procfs tree
+/// The root is configurable so tests can point it at a fixture directory
+pub struct ProcfsSource {
+    root: PathBuf,
+}
+
+impl ProcfsSource {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// PIDs of all processes, in ascending order
+    fn pids(&self) -> Vec<u32> {
+        let mut pids: Vec<u32> = fs::read_dir(&self.root)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter_map(|e| e.file_name().to_str()?.parse().ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        pids.sort_unstable();
+        pids
+    }
+
+    /// Whether the process matches `name`
+    /// comm is truncated to 15 characters by the kernel, so longer names are
+    /// confirmed against argv[0] from cmdline
+    fn matches(&self, pid: u32, name: &str) -> bool {
+        let dir = self.root.join(pid.to_string());
+        let comm = fs::read_to_string(dir.join("comm")).unwrap_or_default();
+        if comm.trim_end() == name {
+            return true;
+        }
+
+        let cmdline = fs::read(dir.join("cmdline")).unwrap_or_default();
+        let argv0 = cmdline.split(|b| *b == 0).next().unwrap_or_default();
+        Path::new(&*String::from_utf8_lossy(argv0))
+            .file_name()
+            .is_some_and(|n| n == name)
+    }
+
+    /// Executable name of a process: argv[0]'s file name, or comm for kernel threads
+    fn name(&self, pid: u32) -> Option<String> {
+        let dir = self.root.join(pid.to_string());
+        let cmdline = fs::read(dir.join("cmdline")).unwrap_or_default();
+        let argv0 = cmdline.split(|b| *b == 0).next().unwrap_or_default();
+        if let Some(name) = Path::new(&*String::from_utf8_lossy(argv0)).file_name() {
+            return Some(name.to_string_lossy().to_string());
+        }
+
+        let comm = fs::read_to_string(dir.join("comm")).ok()?;
+        Some(comm.trim_end().to_string())
+    }
+
+    fn read_process(&self, pid: u32) -> Option<ProcessInfo> {
+        let dir = self.root.join(pid.to_string());
+
+        // Fields after the parenthesised comm, which may itself contain spaces
+        let stat = fs::read_to_string(dir.join("stat")).ok()?;
+        let after_comm = &stat[stat.rfind(')')? + 1..];
+        let fields: Vec<&str> = after_comm.split_whitespace().collect();
+        // stat(5) field numbers are 1-based and the first two (pid, comm) are stripped
+        let field = |n: usize| -> Option<u64> { fields.get(n - 3)?.parse().ok() };
+        let utime = field(14)?;
+        let stime = field(15)?;
+        let start_ticks = field(22)?;
+
+        let boot_time = read_boot_time(&self.root.join("stat"))?;
+        let system_uptime = read_system_uptime(&self.root.join("uptime"))?;
+
+        let started_after_boot = start_ticks as f64 / PROC_TICKS_PER_SEC as f64;
+        let uptime = (system_uptime - started_after_boot).max(0.0);
+        let cpu_time_secs = (utime + stime) as f64 / PROC_TICKS_PER_SEC as f64;
+        let cpu_percent = if uptime > 0.0 {
+            cpu_time_secs / uptime * 100.0
+        } else {
+            0.0
+        };
+
+        let status = fs::read_to_string(dir.join("status")).unwrap_or_default();
+        let resident_kb = status
+            .lines()
+            .find_map(|l| l.strip_prefix("VmRSS:"))
+            .and_then(|v| v.split_whitespace().next()?.parse::<u64>().ok())
+            .unwrap_or(0);
+
+        Some(ProcessInfo {
+            pid,
+            start_time: boot_time + started_after_boot as u64,
+            uptime_secs: uptime as u64,
+            resident_bytes: resident_kb * 1024,
+            cpu_time_secs,
+            cpu_percent,
+        })
+    }
+}
+
+impl ProcessInfoSource for ProcfsSource {
+    fn find_process(&self, name: &str) -> Option<ProcessInfo> {
+        self.pids()
+            .into_iter()
+            .filter(|pid| self.matches(*pid, name))
+            .find_map(|pid| self.read_process(pid))
+    }
+
+    fn running_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.pids().into_iter().filter_map(|pid| self.name(pid)).collect();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+}
+
+/// Boot time (Unix timestamp) from the `btime` line of /proc/stat
+fn read_boot_time(path: &Path) -> Option<u64> {
+    fs::read_to_string(path)
+        .ok()?
+        .lines()
+        .find_map(|l| l.strip_prefix("btime "))
+        .and_then(|v| v.trim().parse().ok())
+}
+
+/// Seconds since boot from the first field of /proc/uptime
+fn read_system_uptime(path: &Path) -> Option<f64> {
+    fs::read_to_string(path)
+        .ok()?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_runner::{CommandOutput, RecordingRunner};
+
+    /// Build a minimal procfs tree in a fresh temp directory
+    fn proc_fixture(test_name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("symbolsweep-proc-{}-{}", test_name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("stat"), "cpu  1 2 3 4\nbtime 1700000000\nprocesses 42\n").unwrap();
+        fs::write(root.join("uptime"), "10000.00 9000.00\n").unwrap();
+        root
+    }
+
+    fn add_process(root: &Path, pid: u32, comm: &str, argv0: &str, cpu_ticks: u64, start_ticks: u64, rss_kb: u64) {
+        let dir = root.join(pid.to_string());
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("comm"), format!("{}\n", comm)).unwrap();
+        fs::write(dir.join("cmdline"), format!("{}\0--flag\0", argv0)).unwrap();
+        fs::write(
+            dir.join("stat"),
+            format!(
+                "{} ({}) S 1 1 1 0 -1 4194560 100 0 0 0 {} {} 0 0 20 0 3 0 {} 123456 789 18446744073709551615",
+                pid,
+                comm,
+                cpu_ticks / 2,
+                cpu_ticks - cpu_ticks / 2,
+                start_ticks
+            ),
+        )
+        .unwrap();
+        fs::write(dir.join("status"), format!("Name:\t{}\nVmRSS:\t  {} kB\n", comm, rss_kb)).unwrap();
+    }
+
+    #[test]
+    fn test_procfs_finds_truncated_comm() {
+        let root = proc_fixture("truncated");
+        add_process(&root, 10, "launchd", "/sbin/launchd", 100, 0, 1000);
+        // Started 2 hours before the fixture's uptime, burning a full core the whole time
+        add_process(&root, 77, "coresymbolicati", "/usr/libexec/coresymbolicationd", 720_000, 280_000, 2048);
+
+        let info = ProcfsSource::new(&root).find_process("coresymbolicationd").unwrap();
+        assert_eq!(info.pid, 77);
+        assert_eq!(info.start_time, 1_700_000_000 + 2800);
+        assert_eq!(info.uptime_secs, 7200);
+        assert_eq!(info.resident_bytes, 2048 * 1024);
+        assert!((info.cpu_time_secs - 7200.0).abs() < 0.01);
+        assert!((info.cpu_percent - 100.0).abs() < 0.01);
+
+        assert!(ProcfsSource::new(&root).find_process("missing").is_none());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_procfs_running_names() {
+        let root = proc_fixture("names");
+        add_process(&root, 10, "launchd", "/sbin/launchd", 0, 0, 0);
+        add_process(&root, 11, "lldb", "/usr/bin/lldb", 0, 0, 0);
+        add_process(&root, 12, "lldb", "lldb", 0, 0, 0);
+        add_process(&root, 13, "coresymbolicati", "/usr/libexec/coresymbolicationd", 0, 0, 0);
+        // Kernel thread with no cmdline
+        add_process(&root, 2, "kthreadd", "", 0, 0, 0);
+        fs::write(root.join("2/cmdline"), "").unwrap();
+
+        assert_eq!(
+            ProcfsSource::new(&root).running_names(),
+            vec!["coresymbolicationd", "kthreadd", "launchd", "lldb"]
+        );
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_procfs_comm_with_spaces() {
+        let root = proc_fixture("spaces");
+        add_process(&root, 5, "my (odd) proc", "my (odd) proc", 0, 100, 10);
+
+        let info = ProcfsSource::new(&root).find_process("my (odd) proc").unwrap();
+        assert_eq!(info.pid, 5);
+        assert_eq!(info.cpu_percent, 0.0);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_parse_clock() {
+        assert_eq!(parse_clock("05:12"), Some(312.0));
+        assert_eq!(parse_clock("01:05:12"), Some(3912.0));
+        assert_eq!(parse_clock("3-01:05:12"), Some(3.0 * 86400.0 + 3912.0));
+        assert_eq!(parse_clock("12:34.50"), Some(754.5));
+        assert_eq!(parse_clock("abc"), None);
+    }
+
+    #[test]
+    fn test_ps_source_parses_output() {
+        let ps_output = "\
+    1 3-00:00:00  12000   0.0   1:00.00 /sbin/launchd
+  431    02:00:00 524288  99.5 119:30.25 /System/Library/PrivateFrameworks/CoreSymbolication.framework/coresymbolicationd
+  900       00:05   1024   0.1   0:00.01 /Applications/My App.app/Contents/MacOS/My App
+";
+        let source = PsSource::new(RecordingRunner::new().respond("ps", Ok(CommandOutput::ok(ps_output))));
+        let info = source.find_process("coresymbolicationd").unwrap();
+        assert_eq!(info.pid, 431);
+        assert_eq!(info.uptime_secs, 7200);
+        assert_eq!(info.resident_bytes, 512 * 1024 * 1024);
+        assert_eq!(info.cpu_percent, 99.5);
+        assert!((info.cpu_time_secs - 7170.25).abs() < 0.01);
+
+        let source = PsSource::new(RecordingRunner::new().respond("ps", Ok(CommandOutput::ok(ps_output))));
+        assert_eq!(source.find_process("My App").unwrap().pid, 900);
+    }
+
+    #[test]
+    fn test_ps_running_names() {
+        let ps_output = "\
+/sbin/launchd
+/Applications/Xcode.app/Contents/MacOS/Xcode
+/Applications/Xcode.app/Contents/SharedFrameworks/LLDB.framework/Resources/debugserver
+/Applications/Xcode.app/Contents/MacOS/Xcode
+";
+        let runner = RecordingRunner::new().respond("ps", Ok(CommandOutput::ok(ps_output)));
+        let source = PsSource::new(runner);
+        assert_eq!(source.running_names(), vec!["Xcode", "debugserver", "launchd"]);
+        assert_eq!(source.runner.calls()[0].args, vec!["-A", "-o", "comm="]);
+    }
+}
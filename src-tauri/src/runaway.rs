@@ -0,0 +1,333 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use crate::cache_monitor::{format_size, DAEMON_NAME};
+use crate::i18n::{format_number, tr_with, Msg};
+use crate::process_info::ProcessInfo;
+use crate::scheduler::{format_duration, Settings};
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Thresholds for flagging a runaway daemon
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunawayConfig {
+    /// CPU usage (percent of one core) considered "high"
+    pub cpu_percent: f64,
+    /// How long CPU must stay high before flagging, in seconds
+    pub cpu_duration_secs: u64,
+    /// Cache growth that counts as runaway within the window, in bytes
+    pub growth_bytes: u64,
+    /// Window for measuring cache growth, in seconds
+    pub growth_window_secs: u64,
+}
+
+impl From<&Settings> for RunawayConfig {
+    fn from(settings: &Settings) -> Self {
+        Self {
+            cpu_percent: settings.runaway_cpu_percent,
+            cpu_duration_secs: settings.runaway_cpu_duration_secs,
+            growth_bytes: settings.runaway_growth_bytes,
+            growth_window_secs: settings.runaway_growth_window_secs,
+        }
+    }
+}
+
+/// Why the daemon was flagged
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RunawayReason {
+    /// CPU stayed at or above the threshold for `duration_secs`
+    HighCpu { cpu_percent: f64, duration_secs: u64 },
+    /// Cache grew by `bytes` within `window_secs`
+    RapidGrowth { bytes: u64, window_secs: u64 },
+}
+
+/// Payload of the `runaway-detected` event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunawayEvent {
+    pub timestamp: u64,
+    pub reason: RunawayReason,
+    /// Daemon PID at detection time (None if not running)
+    pub pid: Option<u32>,
+    pub cache_size_bytes: u64,
+}
+
+impl RunawayEvent {
+    /// Human-readable description for notifications and logs
+    pub fn message(&self) -> String {
+        match &self.reason {
+            RunawayReason::HighCpu { cpu_percent, duration_secs } => tr_with(
+                Msg::RunawayCpu,
+                &[
+                    ("daemon", DAEMON_NAME),
+                    ("percent", &format_number(cpu_percent.round() as u64)),
+                    ("duration", &format_duration(*duration_secs)),
+                ],
+            ),
+            RunawayReason::RapidGrowth { bytes, window_secs } => tr_with(
+                Msg::RunawayGrowth,
+                &[("size", &format_size(*bytes)), ("duration", &format_duration(*window_secs))],
+            ),
+        }
+    }
+}
+
+// ============================================================================
+// Detector
+// ============================================================================
+
+/// Previous CPU sample used to compute usage between ticks
+#[derive(Debug, Clone, Copy)]
+struct CpuSample {
+    pid: u32,
+    timestamp: u64,
+    cpu_time_secs: f64,
+}
+
+/// Watches daemon CPU and cache growth across monitor ticks
+/// Each condition fires once when it starts and re-arms after it clears,
+/// so a daemon that stays pegged doesn't produce an alert every tick
+#[derive(Debug, Default)]
+pub struct RunawayDetector {
+    last_cpu: Option<CpuSample>,
+    /// When CPU first went above the threshold in the current streak
+    high_cpu_since: Option<u64>,
+    cpu_flagged: bool,
+    /// (timestamp, cache size) samples within the growth window
+    sizes: VecDeque<(u64, u64)>,
+    growth_flagged: bool,
+}
+
+impl RunawayDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed one monitor tick and return any newly detected runaway conditions
+    pub fn observe(
+        &mut self,
+        config: &RunawayConfig,
+        now: u64,
+        cache_size_bytes: u64,
+        process: Option<&ProcessInfo>,
+    ) -> Vec<RunawayEvent> {
+        let pid = process.map(|p| p.pid);
+        let mut events = Vec::new();
+
+        if let Some(reason) = self.check_cpu(config, now, process) {
+            events.push(RunawayEvent { timestamp: now, reason, pid, cache_size_bytes });
+        }
+        if let Some(reason) = self.check_growth(config, now, cache_size_bytes) {
+            events.push(RunawayEvent { timestamp: now, reason, pid, cache_size_bytes });
+        }
+
+        events
+    }
+
+    fn check_cpu(&mut self, config: &RunawayConfig, now: u64, process: Option<&ProcessInfo>) -> Option<RunawayReason> {
+        let Some(process) = process else {
+            self.last_cpu = None;
+            self.high_cpu_since = None;
+            self.cpu_flagged = false;
+            return None;
+        };
+
+        let sample = CpuSample {
+            pid: process.pid,
+            timestamp: now,
+            cpu_time_secs: process.cpu_time_secs,
+        };
+        let previous = self.last_cpu.replace(sample);
+
+        // Need two samples from the same process to measure usage between ticks
+        let usage = match previous {
+            Some(prev) if prev.pid == sample.pid && now > prev.timestamp => {
+                (sample.cpu_time_secs - prev.cpu_time_secs).max(0.0) / (now - prev.timestamp) as f64 * 100.0
+            }
+            _ => {
+                self.high_cpu_since = None;
+                self.cpu_flagged = false;
+                return None;
+            }
+        };
+
+        if usage < config.cpu_percent {
+            self.high_cpu_since = None;
+            self.cpu_flagged = false;
+            return None;
+        }
+
+        // The streak started at the previous sample, since usage is measured since then
+        let since = *self.high_cpu_since.get_or_insert(previous.map_or(now, |p| p.timestamp));
+        let duration_secs = now.saturating_sub(since);
+        if duration_secs >= config.cpu_duration_secs && !self.cpu_flagged {
+            self.cpu_flagged = true;
+            return Some(RunawayReason::HighCpu { cpu_percent: usage, duration_secs });
+        }
+
+        None
+    }
+
+    fn check_growth(&mut self, config: &RunawayConfig, now: u64, cache_size_bytes: u64) -> Option<RunawayReason> {
+        self.sizes.push_back((now, cache_size_bytes));
+        while let Some(&(timestamp, _)) = self.sizes.front() {
+            if now.saturating_sub(timestamp) > config.growth_window_secs {
+                self.sizes.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        // Measure from the smallest size in the window so a clean mid-window
+        // doesn't hide growth after it
+        let (min_timestamp, min_size) = self
+            .sizes
+            .iter()
+            .copied()
+            .min_by_key(|&(_, size)| size)
+            .unwrap_or((now, cache_size_bytes));
+        let growth = cache_size_bytes.saturating_sub(min_size);
+
+        if growth < config.growth_bytes {
+            self.growth_flagged = false;
+            return None;
+        }
+
+        if self.growth_flagged {
+            return None;
+        }
+        self.growth_flagged = true;
+        Some(RunawayReason::RapidGrowth {
+            bytes: growth,
+            window_secs: now.saturating_sub(min_timestamp),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GB: u64 = 1024 * 1024 * 1024;
+
+    fn config() -> RunawayConfig {
+        RunawayConfig {
+            cpu_percent: 80.0,
+            cpu_duration_secs: 30 * 60,
+            growth_bytes: GB,
+            growth_window_secs: 10 * 60,
+        }
+    }
+
+    fn daemon(pid: u32, cpu_time_secs: f64) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            start_time: 0,
+            uptime_secs: 0,
+            resident_bytes: 0,
+            cpu_time_secs,
+            cpu_percent: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_sustained_high_cpu_flags_once() {
+        let mut detector = RunawayDetector::new();
+        let config = config();
+        let mut events = Vec::new();
+
+        // One sample per minute, burning 57s of CPU per minute (95%)
+        for minute in 0..=40u64 {
+            let p = daemon(7, minute as f64 * 57.0);
+            events.extend(detector.observe(&config, minute * 60, 0, Some(&p)));
+        }
+
+        assert_eq!(events.len(), 1);
+        match &events[0].reason {
+            RunawayReason::HighCpu { cpu_percent, duration_secs } => {
+                assert!((cpu_percent - 95.0).abs() < 0.01);
+                assert_eq!(*duration_secs, 30 * 60);
+            }
+            other => panic!("unexpected reason {:?}", other),
+        }
+        assert_eq!(events[0].timestamp, 30 * 60);
+        assert_eq!(events[0].pid, Some(7));
+    }
+
+    #[test]
+    fn test_cpu_dip_or_restart_resets_streak() {
+        let mut detector = RunawayDetector::new();
+        let config = config();
+        let mut cpu = 0.0;
+        let mut events = Vec::new();
+
+        for minute in 0..=60u64 {
+            // Idle for one minute every 20 minutes
+            cpu += if minute % 20 == 0 { 1.0 } else { 57.0 };
+            events.extend(detector.observe(&config, minute * 60, 0, Some(&daemon(7, cpu))));
+        }
+        assert!(events.is_empty());
+
+        // A new PID starts a new streak
+        let mut detector = RunawayDetector::new();
+        for minute in 0..=40u64 {
+            let pid = if minute < 20 { 7 } else { 8 };
+            events.extend(detector.observe(&config, minute * 60, 0, Some(&daemon(pid, minute as f64 * 57.0))));
+        }
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_rapid_growth_flags() {
+        let mut detector = RunawayDetector::new();
+        let config = config();
+
+        // 150 MB per minute: 1 GB after ~7 minutes
+        let mut events = Vec::new();
+        for minute in 0..=12u64 {
+            events.extend(detector.observe(&config, minute * 60, minute * 150 * 1024 * 1024, None));
+        }
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].timestamp, 7 * 60);
+        assert!(matches!(events[0].reason, RunawayReason::RapidGrowth { window_secs: 420, .. }));
+    }
+
+    #[test]
+    fn test_slow_growth_not_flagged() {
+        let mut detector = RunawayDetector::new();
+        let config = config();
+
+        // 50 MB per minute never reaches 1 GB within a 10 minute window
+        for minute in 0..=600u64 {
+            assert!(detector
+                .observe(&config, minute * 60, minute * 50 * 1024 * 1024, None)
+                .is_empty());
+        }
+    }
+
+    #[test]
+    fn test_growth_rearms_after_clean() {
+        let mut detector = RunawayDetector::new();
+        let config = config();
+
+        assert!(detector.observe(&config, 0, 0, None).is_empty());
+        assert_eq!(detector.observe(&config, 60, 2 * GB, None).len(), 1);
+        // Still high - no repeat
+        assert!(detector.observe(&config, 120, 2 * GB, None).is_empty());
+        // Cleaned, then balloons again
+        assert!(detector.observe(&config, 180, 0, None).is_empty());
+        assert_eq!(detector.observe(&config, 240, 2 * GB, None).len(), 1);
+    }
+
+    #[test]
+    fn test_event_message() {
+        let event = RunawayEvent {
+            timestamp: 0,
+            reason: RunawayReason::RapidGrowth { bytes: 2 * GB, window_secs: 600 },
+            pid: None,
+            cache_size_bytes: 2 * GB,
+        };
+        assert_eq!(event.message(), "Cache grew by 2 GiB in 10 minutes");
+    }
+}
@@ -0,0 +1,501 @@
+use chrono::{
+    Datelike, Duration as ChronoDuration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike, Weekday,
+};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::scheduler::{MissedCleanPolicy, Settings};
+
+/// How many days ahead to look for the next calendar occurrence
+/// Four years plus a day covers cron expressions that only match on Feb 29
+const MAX_SEARCH_DAYS: i64 = 4 * 366 + 1;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// When scheduled auto-clean runs
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum CleanSchedule {
+    /// Every `auto_clean_interval_secs` after the last clean
+    #[default]
+    Interval,
+    /// At fixed times of day on the selected weekdays
+    Daily { times: Vec<TimeOfDay>, weekdays: WeekdayMask },
+    /// Cron-style "minute hour day-of-month month day-of-week"
+    Cron { expression: String },
+}
+
+/// A wall-clock time in the schedule's time zone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct TimeOfDay {
+    pub hour: u8,
+    pub minute: u8,
+}
+
+/// Set of weekdays, bit 0 = Monday through bit 6 = Sunday
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct WeekdayMask(pub u8);
+
+impl WeekdayMask {
+    pub const EVERY_DAY: Self = Self(0b111_1111);
+    pub const WEEKDAYS: Self = Self(0b001_1111);
+
+    pub fn contains(self, day: Weekday) -> bool {
+        self.0 & (1 << day.num_days_from_monday()) != 0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScheduleError {
+    InvalidTimeZone(String),
+    InvalidTime { hour: u8, minute: u8 },
+    InvalidCron { expression: String, reason: String },
+}
+
+impl std::fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScheduleError::InvalidTimeZone(name) => write!(f, "Unknown time zone: {}", name),
+            ScheduleError::InvalidTime { hour, minute } => write!(f, "Invalid time: {:02}:{:02}", hour, minute),
+            ScheduleError::InvalidCron { expression, reason } => {
+                write!(f, "Invalid cron expression \"{}\": {}", expression, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScheduleError {}
+
+// ============================================================================
+// Next occurrence
+// ============================================================================
+
+/// First scheduled clean strictly after `after` (Unix seconds)
+/// Returns None if the schedule can never fire (e.g. no weekdays selected)
+pub fn next_clean_after(settings: &Settings, after: u64) -> Result<Option<u64>, ScheduleError> {
+    let rule = match &settings.auto_clean_schedule {
+        CleanSchedule::Interval => {
+            return Ok(Some(after.saturating_add(settings.auto_clean_interval_secs.max(1))));
+        }
+        CleanSchedule::Daily { times, weekdays } => DayRule::daily(times, *weekdays)?,
+        CleanSchedule::Cron { expression } => DayRule::Cron(CronExpr::parse(expression)?),
+    };
+
+    match &settings.schedule_time_zone {
+        Some(name) => {
+            let zone: Tz = name
+                .parse()
+                .map_err(|_| ScheduleError::InvalidTimeZone(name.clone()))?;
+            Ok(next_in_zone(&zone, &rule, after))
+        }
+        None => Ok(next_in_zone(&chrono::Local, &rule, after)),
+    }
+}
+
+/// Wall-clock time of day at `timestamp` in the configured time zone
+/// Returns None if the time zone is invalid
+pub fn local_time_of_day(settings: &Settings, timestamp: u64) -> Option<TimeOfDay> {
+    fn in_zone<Z: TimeZone>(zone: &Z, timestamp: u64) -> Option<TimeOfDay> {
+        let local = zone.timestamp_opt(timestamp as i64, 0).single()?.naive_local();
+        Some(TimeOfDay {
+            hour: local.hour() as u8,
+            minute: local.minute() as u8,
+        })
+    }
+
+    match &settings.schedule_time_zone {
+        Some(name) => in_zone(&name.parse::<Tz>().ok()?, timestamp),
+        None => in_zone(&chrono::Local, timestamp),
+    }
+}
+
+/// Time scheduled windows are counted from: the last clean, or `since` if it has
+/// never cleaned, so a first launch doesn't treat every window since 1970 as missed
+pub fn schedule_anchor(settings: &Settings, since: u64) -> u64 {
+    if settings.last_clean_timestamp == 0 {
+        since
+    } else {
+        settings.last_clean_timestamp
+    }
+}
+
+/// When the next scheduled clean will run, for display in the UI and tray
+/// Overdue cleans are reported as `now` under catch-up, or as the next window under skip
+pub fn next_scheduled_clean(settings: &Settings, now: u64) -> Option<u64> {
+    if !settings.auto_clean_scheduled {
+        return None;
+    }
+
+    let due = next_clean_after(settings, schedule_anchor(settings, now)).ok()??;
+    if due >= now {
+        return Some(due);
+    }
+    match settings.missed_clean_policy {
+        MissedCleanPolicy::CatchUp => Some(now),
+        MissedCleanPolicy::Skip => next_clean_after(settings, now).ok()?,
+    }
+}
+
+/// Which times of day a calendar schedule fires on a given date
+enum DayRule {
+    Daily { times: Vec<NaiveTime>, weekdays: WeekdayMask },
+    Cron(CronExpr),
+}
+
+impl DayRule {
+    fn daily(times: &[TimeOfDay], weekdays: WeekdayMask) -> Result<Self, ScheduleError> {
+        let mut parsed = times
+            .iter()
+            .map(|t| {
+                NaiveTime::from_hms_opt(t.hour.into(), t.minute.into(), 0).ok_or(ScheduleError::InvalidTime {
+                    hour: t.hour,
+                    minute: t.minute,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        parsed.sort_unstable();
+        Ok(DayRule::Daily { times: parsed, weekdays })
+    }
+
+    /// Firing times on `date`, in ascending order
+    fn times_on(&self, date: NaiveDate) -> Vec<NaiveTime> {
+        match self {
+            DayRule::Daily { times, weekdays } if weekdays.contains(date.weekday()) => times.clone(),
+            DayRule::Daily { .. } => Vec::new(),
+            DayRule::Cron(cron) if cron.matches_date(date) => cron.times(),
+            DayRule::Cron(_) => Vec::new(),
+        }
+    }
+}
+
+fn next_in_zone<Z: TimeZone>(zone: &Z, rule: &DayRule, after: u64) -> Option<u64> {
+    let start = zone.timestamp_opt(after as i64, 0).single()?.naive_local().date();
+
+    // Start a day early in case a DST shift moves an occurrence across midnight
+    (-1..MAX_SEARCH_DAYS)
+        .filter_map(|offset| start.checked_add_signed(ChronoDuration::days(offset)))
+        .flat_map(|date| rule.times_on(date).into_iter().map(move |time| date.and_time(time)))
+        .filter_map(|local| resolve_local(zone, local))
+        .find(|&timestamp| timestamp > after)
+}
+
+/// Convert a local time to a timestamp
+/// Ambiguous times (clocks going back) use the first occurrence; times skipped
+/// by clocks going forward run an hour later instead of being dropped
+fn resolve_local<Z: TimeZone>(zone: &Z, local: NaiveDateTime) -> Option<u64> {
+    // Some zones map skipped times to an instant anyway, so check it round-trips
+    let exact = |local: NaiveDateTime| {
+        zone.from_local_datetime(&local)
+            .earliest()
+            .filter(|resolved| resolved.naive_local() == local)
+    };
+    let resolved = exact(local).or_else(|| exact(local + ChronoDuration::hours(1)))?;
+    u64::try_from(resolved.timestamp()).ok()
+}
+
+// ============================================================================
+// Cron expressions
+// ============================================================================
+
+/// Parsed five-field cron expression
+/// Each field is a bit set of allowed values
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CronExpr {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    /// Bit 0 = Sunday
+    days_of_week: u64,
+    /// Whether day-of-month / day-of-week were restricted (not starting with '*')
+    /// When both are, a date matching either one fires, as in cron(8)
+    days_of_month_restricted: bool,
+    days_of_week_restricted: bool,
+}
+
+impl CronExpr {
+    fn parse(expression: &str) -> Result<Self, ScheduleError> {
+        let invalid = |reason: String| ScheduleError::InvalidCron {
+            expression: expression.to_string(),
+            reason,
+        };
+
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(invalid(format!("expected 5 fields, found {}", fields.len())));
+        };
+
+        let mut days_of_week = parse_cron_field(weekday, 0, 7).map_err(&invalid)?;
+        // 7 is an alias for Sunday
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week & !(1 << 7)) | 1;
+        }
+
+        Ok(Self {
+            minutes: parse_cron_field(minute, 0, 59).map_err(&invalid)?,
+            hours: parse_cron_field(hour, 0, 23).map_err(&invalid)?,
+            days_of_month: parse_cron_field(day, 1, 31).map_err(&invalid)?,
+            months: parse_cron_field(month, 1, 12).map_err(&invalid)?,
+            days_of_week,
+            days_of_month_restricted: !day.starts_with('*'),
+            days_of_week_restricted: !weekday.starts_with('*'),
+        })
+    }
+
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        if self.months & (1 << date.month()) == 0 {
+            return false;
+        }
+
+        let day_of_month = self.days_of_month & (1 << date.day()) != 0;
+        let day_of_week = self.days_of_week & (1 << date.weekday().num_days_from_sunday()) != 0;
+        if self.days_of_month_restricted && self.days_of_week_restricted {
+            day_of_month || day_of_week
+        } else {
+            day_of_month && day_of_week
+        }
+    }
+
+    /// All matching times of day, in ascending order
+    fn times(&self) -> Vec<NaiveTime> {
+        (0..24)
+            .filter(|h| self.hours & (1 << h) != 0)
+            .flat_map(|h| {
+                (0..60)
+                    .filter(|m| self.minutes & (1 << m) != 0)
+                    .filter_map(move |m| NaiveTime::from_hms_opt(h, m, 0))
+            })
+            .collect()
+    }
+}
+
+/// Parse one cron field ("*", "5", "1-5", "*/15", "0-30/10", or a comma list of those)
+fn parse_cron_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut bits = 0u64;
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| format!("invalid step \"{}\"", step))?;
+                if step == 0 {
+                    return Err("step must be at least 1".to_string());
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (parse_cron_value(start, min, max)?, parse_cron_value(end, min, max)?)
+        } else {
+            let value = parse_cron_value(range, min, max)?;
+            // "5/10" means "from 5 to the end in steps of 10"
+            (value, if part.contains('/') { max } else { value })
+        };
+        if start > end {
+            return Err(format!("range {}-{} is backwards", start, end));
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+
+    Ok(bits)
+}
+
+fn parse_cron_value(value: &str, min: u32, max: u32) -> Result<u32, String> {
+    let parsed: u32 = value.parse().map_err(|_| format!("invalid value \"{}\"", value))?;
+    if parsed < min || parsed > max {
+        return Err(format!("{} is outside {}-{}", parsed, min, max));
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timestamp(zone: &Tz, y: i32, mo: u32, d: u32, h: u32, mi: u32) -> u64 {
+        zone.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap().timestamp() as u64
+    }
+
+    fn settings(schedule: CleanSchedule, zone: &str) -> Settings {
+        Settings {
+            auto_clean_scheduled: true,
+            auto_clean_schedule: schedule,
+            schedule_time_zone: Some(zone.to_string()),
+            ..Settings::default()
+        }
+    }
+
+    #[test]
+    fn test_daily_at_fixed_time() {
+        let zone = Tz::Europe__London;
+        let s = settings(
+            CleanSchedule::Daily {
+                times: vec![TimeOfDay { hour: 3, minute: 0 }],
+                weekdays: WeekdayMask::EVERY_DAY,
+            },
+            "Europe/London",
+        );
+
+        let before = timestamp(&zone, 2025, 6, 10, 2, 59);
+        assert_eq!(next_clean_after(&s, before).unwrap(), Some(timestamp(&zone, 2025, 6, 10, 3, 0)));
+        // Strictly after: 03:00 itself moves on to tomorrow
+        let at = timestamp(&zone, 2025, 6, 10, 3, 0);
+        assert_eq!(next_clean_after(&s, at).unwrap(), Some(timestamp(&zone, 2025, 6, 11, 3, 0)));
+    }
+
+    #[test]
+    fn test_weekdays_at_lunch() {
+        let zone = Tz::America__New_York;
+        let s = settings(
+            CleanSchedule::Daily {
+                times: vec![TimeOfDay { hour: 12, minute: 30 }],
+                weekdays: WeekdayMask::WEEKDAYS,
+            },
+            "America/New_York",
+        );
+
+        // Friday afternoon -> Monday lunch
+        let friday = timestamp(&zone, 2025, 6, 13, 15, 0);
+        assert_eq!(next_clean_after(&s, friday).unwrap(), Some(timestamp(&zone, 2025, 6, 16, 12, 30)));
+    }
+
+    #[test]
+    fn test_time_zone_changes_instant() {
+        let schedule = CleanSchedule::Daily {
+            times: vec![TimeOfDay { hour: 3, minute: 0 }],
+            weekdays: WeekdayMask::EVERY_DAY,
+        };
+        let after = timestamp(&Tz::UTC, 2025, 1, 1, 0, 0);
+        let tokyo = next_clean_after(&settings(schedule.clone(), "Asia/Tokyo"), after).unwrap();
+        let utc = next_clean_after(&settings(schedule, "UTC"), after).unwrap();
+        assert_eq!(utc, Some(timestamp(&Tz::UTC, 2025, 1, 1, 3, 0)));
+        // 03:00 in Tokyo is 18:00 UTC the previous day
+        assert_eq!(tokyo, Some(timestamp(&Tz::UTC, 2025, 1, 1, 18, 0)));
+    }
+
+    #[test]
+    fn test_dst_gap_runs_after_gap() {
+        // Clocks go forward 01:00 -> 02:00 on 2025-03-30 in London
+        let zone = Tz::Europe__London;
+        let s = settings(
+            CleanSchedule::Daily {
+                times: vec![TimeOfDay { hour: 1, minute: 30 }],
+                weekdays: WeekdayMask::EVERY_DAY,
+            },
+            "Europe/London",
+        );
+        let after = timestamp(&zone, 2025, 3, 30, 0, 0);
+        assert_eq!(next_clean_after(&s, after).unwrap(), Some(timestamp(&zone, 2025, 3, 30, 2, 30)));
+    }
+
+    #[test]
+    fn test_cron_expressions() {
+        let zone = Tz::UTC;
+        let next = |expr: &str, after: u64| {
+            next_clean_after(&settings(CleanSchedule::Cron { expression: expr.to_string() }, "UTC"), after).unwrap()
+        };
+        // Wednesday 2025-06-11 10:07
+        let now = timestamp(&zone, 2025, 6, 11, 10, 7);
+
+        assert_eq!(next("*/15 * * * *", now), Some(timestamp(&zone, 2025, 6, 11, 10, 15)));
+        assert_eq!(next("30 12 * * 1-5", now), Some(timestamp(&zone, 2025, 6, 11, 12, 30)));
+        assert_eq!(next("0 3 * * 0", now), Some(timestamp(&zone, 2025, 6, 15, 3, 0)));
+        assert_eq!(next("0 3 * * 7", now), Some(timestamp(&zone, 2025, 6, 15, 3, 0)));
+        assert_eq!(next("0 0 1 * *", now), Some(timestamp(&zone, 2025, 7, 1, 0, 0)));
+        // Day-of-month OR day-of-week when both are restricted
+        assert_eq!(next("0 0 20 * 5", now), Some(timestamp(&zone, 2025, 6, 13, 0, 0)));
+        // Leap day only
+        assert_eq!(next("0 0 29 2 *", now), Some(timestamp(&zone, 2028, 2, 29, 0, 0)));
+        // Never matches
+        assert_eq!(next("0 0 31 2 *", now), None);
+    }
+
+    #[test]
+    fn test_cron_parse_errors() {
+        for bad in ["", "* * * *", "60 * * * *", "* 24 * * *", "*/0 * * * *", "5-1 * * * *", "a * * * *"] {
+            assert!(
+                matches!(CronExpr::parse(bad), Err(ScheduleError::InvalidCron { .. })),
+                "{:?} should not parse",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_time_zone() {
+        let s = settings(CleanSchedule::Cron { expression: "0 3 * * *".to_string() }, "Mars/Olympus");
+        assert_eq!(
+            next_clean_after(&s, 0),
+            Err(ScheduleError::InvalidTimeZone("Mars/Olympus".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_next_scheduled_clean_for_display() {
+        let now = 1_700_000_000;
+        let mut s = Settings {
+            auto_clean_scheduled: true,
+            auto_clean_interval_secs: 3600,
+            last_clean_timestamp: now - 600,
+            ..Settings::default()
+        };
+        assert_eq!(next_scheduled_clean(&s, now), Some(now + 3000));
+
+        // Overdue by a day
+        s.last_clean_timestamp = now - 86400;
+        assert_eq!(next_scheduled_clean(&s, now), Some(now));
+        s.missed_clean_policy = MissedCleanPolicy::Skip;
+        assert_eq!(next_scheduled_clean(&s, now), Some(now + 3600));
+
+        s.auto_clean_scheduled = false;
+        assert_eq!(next_scheduled_clean(&s, now), None);
+    }
+
+    #[test]
+    fn test_never_cleaned_schedule_has_no_missed_window() {
+        // Tue 2023-11-14 22:13:20 UTC
+        let now = 1_700_000_000;
+        let s = Settings {
+            auto_clean_scheduled: true,
+            auto_clean_schedule: CleanSchedule::Daily {
+                times: vec![TimeOfDay { hour: 12, minute: 30 }],
+                weekdays: WeekdayMask::EVERY_DAY,
+            },
+            schedule_time_zone: Some("UTC".to_string()),
+            last_clean_timestamp: 0,
+            ..Settings::default()
+        };
+        // Wednesday's 12:30, not "now"
+        assert_eq!(next_scheduled_clean(&s, now), Some(1_700_051_400));
+
+        // An interval schedule that never ran counts its first interval from now
+        let interval = Settings {
+            auto_clean_schedule: CleanSchedule::Interval,
+            ..s
+        };
+        assert_eq!(next_scheduled_clean(&interval, now), Some(now + interval.auto_clean_interval_secs));
+    }
+
+    #[test]
+    fn test_schedule_serde() {
+        let schedule = CleanSchedule::Daily {
+            times: vec![TimeOfDay { hour: 12, minute: 30 }],
+            weekdays: WeekdayMask::WEEKDAYS,
+        };
+        let json = serde_json::to_value(&schedule).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "kind": "Daily", "times": [{ "hour": 12, "minute": 30 }], "weekdays": 31 })
+        );
+        assert_eq!(serde_json::from_value::<CleanSchedule>(json).unwrap(), schedule);
+    }
+}
@@ -1,36 +1,81 @@
 use serde::{Deserialize, Serialize};
-use std::fs;
 use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use crate::cache_cleaner::{clean_cache, CleanResult};
-use crate::cache_monitor::{get_cache_status, CacheState, WARNING_THRESHOLD};
+use crate::cache_monitor::WARNING_THRESHOLD;
+use crate::i18n::{format_duration_in, format_relative, locale, tr, tr_with, Msg};
+use crate::notification_policy::ThresholdAlerts;
+use crate::schedule::{next_scheduled_clean, CleanSchedule, TimeOfDay};
+use crate::settings_schema::SCHEMA_VERSION;
+use crate::settings_store::{load_from, save_to, LoadedSettings};
+use crate::units::{deserialize_duration, deserialize_size, duration_style, DurationStyle, SizeUnits};
 
 /// Settings for auto-clean behavior
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
+    /// Layout version of settings.json (0 = written before versioning)
+    #[serde(default)]
+    pub schema_version: u32,
     /// Enable auto-clean when threshold is reached
     pub auto_clean_on_threshold: bool,
     /// Threshold in bytes for auto-clean (default: 5GB)
+    #[serde(deserialize_with = "deserialize_size")]
     pub auto_clean_threshold: u64,
     /// Enable scheduled auto-clean
     pub auto_clean_scheduled: bool,
     /// Interval in seconds for scheduled clean (default: 6 hours)
+    #[serde(deserialize_with = "deserialize_duration")]
     pub auto_clean_interval_secs: u64,
+    /// When scheduled clean runs (default: every `auto_clean_interval_secs`)
+    #[serde(default)]
+    pub auto_clean_schedule: CleanSchedule,
+    /// IANA time zone for calendar schedules and quiet hours, e.g. "Europe/London" (None = system time zone)
+    #[serde(default)]
+    pub schedule_time_zone: Option<String>,
     /// Show notifications
     pub show_notifications: bool,
+    /// How sizes are shown (and which round thresholds are offered)
+    #[serde(default)]
+    pub size_units: SizeUnits,
+    /// How durations are written ("1 day 23 hours", "1d 23h" or "1 day")
+    #[serde(default)]
+    pub duration_style: DurationStyle,
+    /// Hold notifications between `quiet_hours_start` and `quiet_hours_end`
+    #[serde(default)]
+    pub quiet_hours_enabled: bool,
+    /// Start of quiet hours (default: 22:00)
+    #[serde(default = "default_quiet_hours_start")]
+    pub quiet_hours_start: TimeOfDay,
+    /// End of quiet hours (default: 08:00)
+    #[serde(default = "default_quiet_hours_end")]
+    pub quiet_hours_end: TimeOfDay,
+    /// Hold notifications while presenting or screen sharing
+    #[serde(default)]
+    pub presenting_mode: bool,
+    /// Also hold auto-clean during quiet hours and while presenting
+    #[serde(default)]
+    pub quiet_defers_auto_clean: bool,
     /// Launch at login
     pub launch_at_login: bool,
     /// Last clean timestamp
     pub last_clean_timestamp: u64,
     /// Monitoring interval in seconds
+    #[serde(deserialize_with = "deserialize_duration")]
     pub monitor_interval_secs: u64,
+    /// Scan more often while the cache grows toward a threshold and less often while it's flat
+    #[serde(default)]
+    pub adaptive_interval: bool,
+    /// Shortest adaptive interval in seconds (default: 15 seconds)
+    #[serde(default = "default_min_monitor_interval_secs", deserialize_with = "deserialize_duration")]
+    pub min_monitor_interval_secs: u64,
+    /// Longest adaptive interval in seconds (default: 10 minutes)
+    #[serde(default = "default_max_monitor_interval_secs", deserialize_with = "deserialize_duration")]
+    pub max_monitor_interval_secs: u64,
     /// Debug mode - simulate cache sizes
     #[serde(default)]
     pub debug_mode: bool,
     /// Simulated cache size in bytes (only used when debug_mode is true)
-    #[serde(default)]
+    #[serde(default, deserialize_with = "deserialize_size")]
     pub debug_simulated_size: u64,
     /// First run completed - hide welcome screen after first launch
     #[serde(default)]
@@ -38,69 +83,403 @@ pub struct Settings {
     /// First clean confirmed - user has acknowledged the safety message
     #[serde(default)]
     pub first_clean_confirmed: bool,
+    /// Watch for a runaway daemon (sustained high CPU or rapid cache growth)
+    #[serde(default = "default_true")]
+    pub runaway_detection_enabled: bool,
+    /// CPU usage (percent of one core) considered high for runaway detection
+    #[serde(default = "default_runaway_cpu_percent")]
+    pub runaway_cpu_percent: f64,
+    /// How long CPU must stay high before the daemon is flagged (default: 30 minutes)
+    #[serde(default = "default_runaway_cpu_duration_secs", deserialize_with = "deserialize_duration")]
+    pub runaway_cpu_duration_secs: u64,
+    /// Cache growth in bytes that counts as runaway within the window (default: 1GB)
+    #[serde(default = "default_runaway_growth_bytes", deserialize_with = "deserialize_size")]
+    pub runaway_growth_bytes: u64,
+    /// Window in seconds for measuring cache growth (default: 10 minutes)
+    #[serde(default = "default_runaway_growth_window_secs", deserialize_with = "deserialize_duration")]
+    pub runaway_growth_window_secs: u64,
+    /// Restart the daemon and clean when a runaway is detected
+    #[serde(default)]
+    pub runaway_auto_remediate: bool,
+    /// Consecutive auto-clean failures before auto-clean is paused
+    #[serde(default = "default_auto_clean_max_failures")]
+    pub auto_clean_max_failures: u32,
+    /// Auto-clean failure backoff (managed by the monitor, persisted across restarts)
+    #[serde(default)]
+    pub auto_clean_backoff: AutoCleanBackoff,
+    /// Hold auto-clean while any of `busy_process_names` is running
+    #[serde(default = "default_true")]
+    pub defer_while_busy: bool,
+    /// Debugging and symbolication tools that make a clean disruptive
+    #[serde(default = "default_busy_process_names")]
+    pub busy_process_names: Vec<String>,
+    /// Longest auto-clean waits for busy tools before cleaning anyway (default: 4 hours)
+    #[serde(default = "default_busy_max_defer_secs", deserialize_with = "deserialize_duration")]
+    pub busy_max_defer_secs: u64,
+    /// Only auto-clean once there has been no keyboard or mouse input for `require_idle_secs`
+    #[serde(default)]
+    pub require_idle: bool,
+    /// Idle time required before auto-clean (default: 10 minutes)
+    #[serde(default = "default_require_idle_secs", deserialize_with = "deserialize_duration")]
+    pub require_idle_secs: u64,
+    /// Only auto-clean while on AC power
+    #[serde(default)]
+    pub require_ac_power: bool,
+    /// Seconds to hold off auto-clean after the machine wakes (default: 5 minutes)
+    #[serde(default = "default_wake_grace_secs", deserialize_with = "deserialize_duration")]
+    pub wake_grace_secs: u64,
+    /// What to do about scheduled cleans that came due while asleep or not running
+    #[serde(default)]
+    pub missed_clean_policy: MissedCleanPolicy,
+    /// Repeat the warning notification this often while the cache stays there (0 = never, default: 1 day)
+    #[serde(default = "default_warning_reminder_secs", deserialize_with = "deserialize_duration")]
+    pub warning_reminder_secs: u64,
+    /// Repeat the critical notification this often while the cache stays there (0 = never, default: 4 hours)
+    #[serde(default = "default_critical_reminder_secs", deserialize_with = "deserialize_duration")]
+    pub critical_reminder_secs: u64,
+    /// How long the "Snooze" notification button holds cache alerts (default: 4 hours)
+    #[serde(default = "default_notification_snooze_secs", deserialize_with = "deserialize_duration")]
+    pub notification_snooze_secs: u64,
+    /// Threshold notification and snooze state
+    #[serde(default)]
+    pub threshold_alerts: ThresholdAlerts,
+}
+
+/// Handling of scheduled cleans missed while the machine was asleep or the app wasn't running
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MissedCleanPolicy {
+    /// Run one clean for all missed windows (after the wake grace delay)
+    #[default]
+    CatchUp,
+    /// Drop the missed windows and wait for the next one
+    Skip,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_warning_reminder_secs() -> u64 {
+    24 * 60 * 60 // 1 day
+}
+
+fn default_critical_reminder_secs() -> u64 {
+    4 * 60 * 60 // 4 hours
+}
+
+fn default_notification_snooze_secs() -> u64 {
+    4 * 60 * 60 // 4 hours
+}
+
+fn default_min_monitor_interval_secs() -> u64 {
+    15
+}
+
+fn default_max_monitor_interval_secs() -> u64 {
+    10 * 60
+}
+
+fn default_runaway_cpu_percent() -> f64 {
+    80.0
+}
+
+fn default_runaway_cpu_duration_secs() -> u64 {
+    30 * 60
+}
+
+fn default_runaway_growth_bytes() -> u64 {
+    1024 * 1024 * 1024
+}
+
+fn default_runaway_growth_window_secs() -> u64 {
+    10 * 60
+}
+
+fn default_auto_clean_max_failures() -> u32 {
+    3
+}
+
+fn default_quiet_hours_start() -> TimeOfDay {
+    TimeOfDay { hour: 22, minute: 0 }
+}
+
+fn default_quiet_hours_end() -> TimeOfDay {
+    TimeOfDay { hour: 8, minute: 0 }
+}
+
+fn default_busy_process_names() -> Vec<String> {
+    ["Xcode", "lldb", "debugserver", "Instruments"]
+        .iter()
+        .map(|name| name.to_string())
+        .collect()
+}
+
+fn default_busy_max_defer_secs() -> u64 {
+    4 * 60 * 60
+}
+
+fn default_require_idle_secs() -> u64 {
+    10 * 60
+}
+
+fn default_wake_grace_secs() -> u64 {
+    5 * 60
+}
+
+/// First retry delay after a failed auto-clean
+const BACKOFF_BASE_SECS: u64 = 60;
+/// Longest delay between auto-clean retries
+const BACKOFF_MAX_SECS: u64 = 60 * 60;
+
+/// Retry state for auto-clean after failures
+/// Each consecutive failure doubles the delay before the next attempt (capped),
+/// and after `auto_clean_max_failures` in a row auto-clean is paused until the
+/// user resumes it or a manual clean succeeds
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AutoCleanBackoff {
+    /// Failures since the last successful auto-clean
+    pub consecutive_failures: u32,
+    /// Unix timestamp before which auto-clean won't be retried (0 = no backoff)
+    pub next_attempt_timestamp: u64,
+    /// Circuit breaker tripped - auto-clean is paused
+    pub paused: bool,
+    /// Error from the most recent failure
+    pub last_error: Option<String>,
+}
+
+impl AutoCleanBackoff {
+    /// Whether auto-clean may run at `now`
+    pub fn allows_attempt(&self, now: u64) -> bool {
+        !self.paused && now >= self.next_attempt_timestamp
+    }
+
+    /// Record a failed attempt
+    /// Returns true if this failure tripped the circuit breaker
+    pub fn record_failure(&mut self, now: u64, error: String, max_failures: u32) -> bool {
+        self.consecutive_failures += 1;
+        let exponent = (self.consecutive_failures - 1).min(16);
+        let delay = BACKOFF_BASE_SECS.saturating_mul(1 << exponent).min(BACKOFF_MAX_SECS);
+        self.next_attempt_timestamp = now + delay;
+        self.last_error = Some(error);
+
+        let tripped = !self.paused && self.consecutive_failures >= max_failures.max(1);
+        self.paused |= tripped;
+        tripped
+    }
+
+    /// Clear all failure state (after a success or when the user resumes)
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Why a due auto-clean is waiting
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeferralReason {
+    /// Debugging or symbolication tools are running
+    BusyProcesses { names: Vec<String> },
+    /// Running on battery and AC power is required
+    OnBattery,
+    /// The user is active and `required_secs` of idle time is required
+    NotIdle { required_secs: u64 },
+    /// Quiet hours are on and set to hold auto-clean
+    QuietHours,
+    /// A presentation or screen share is running and set to hold auto-clean
+    Presenting,
+    /// The Mac woke recently; waits until the Unix timestamp `until`
+    WakeGrace { until: u64 },
+}
+
+impl DeferralReason {
+    /// Human-readable description for the UI and logs
+    pub fn message(&self) -> String {
+        match self {
+            DeferralReason::BusyProcesses { names } => tr_with(Msg::DeferredBusy, &[("names", &names.join(", "))]),
+            DeferralReason::OnBattery => tr(Msg::DeferredOnBattery),
+            DeferralReason::NotIdle { required_secs } => {
+                tr_with(Msg::DeferredNotIdle, &[("duration", &format_duration(*required_secs))])
+            }
+            DeferralReason::QuietHours => tr(Msg::DeferredQuietHours),
+            DeferralReason::Presenting => tr(Msg::DeferredPresenting),
+            DeferralReason::WakeGrace { .. } => tr(Msg::DeferredWakeGrace),
+        }
+    }
+}
+
+/// A due auto-clean being held back
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AutoCleanDeferral {
+    pub reason: DeferralReason,
+    /// Unix timestamp the clean first became due and was held
+    pub since: u64,
+    /// Unix timestamp after which it runs regardless (None = no limit)
+    pub until: Option<u64>,
+}
+
+/// Auto-clean state reported alongside the cache status
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AutoCleanStatus {
+    pub backoff: AutoCleanBackoff,
+    /// Unix timestamp of the next scheduled clean (None if scheduling is off)
+    pub next_scheduled_clean: Option<u64>,
+    /// Why a due auto-clean is currently waiting
+    pub deferral: Option<AutoCleanDeferral>,
+    /// Unix timestamp of the last clean (None if never cleaned)
+    #[serde(default)]
+    pub last_clean: Option<u64>,
+}
+
+/// Why the monitor is scanning at its current interval
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IntervalReason {
+    /// Adaptive interval is off
+    #[default]
+    Fixed,
+    /// The cache is flat or far from any threshold
+    Steady,
+    /// The cache is growing toward a threshold
+    Growing,
+    /// Scanning less often to save battery
+    OnBattery,
+}
+
+/// Monitor loop state reported alongside the cache status
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MonitorStatus {
+    /// Seconds until the next scan (0 before the first scan)
+    pub interval_secs: u64,
+    pub reason: IntervalReason,
+}
+
+impl AutoCleanStatus {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self::from_settings_at(settings, current_timestamp())
+    }
+
+    pub fn from_settings_at(settings: &Settings, now: u64) -> Self {
+        Self {
+            backoff: settings.auto_clean_backoff.clone(),
+            next_scheduled_clean: next_scheduled_clean(settings, now),
+            deferral: None,
+            last_clean: Some(settings.last_clean_timestamp).filter(|&timestamp| timestamp > 0),
+        }
+    }
 }
 
 impl Default for Settings {
     fn default() -> Self {
         Self {
+            schema_version: SCHEMA_VERSION,
             auto_clean_on_threshold: false,
             auto_clean_threshold: WARNING_THRESHOLD,
             auto_clean_scheduled: false,
             auto_clean_interval_secs: 6 * 60 * 60, // 6 hours
+            auto_clean_schedule: CleanSchedule::default(),
+            schedule_time_zone: None,
             show_notifications: true,
+            size_units: SizeUnits::default(),
+            duration_style: DurationStyle::default(),
+            quiet_hours_enabled: false,
+            quiet_hours_start: default_quiet_hours_start(),
+            quiet_hours_end: default_quiet_hours_end(),
+            presenting_mode: false,
+            quiet_defers_auto_clean: false,
             launch_at_login: false,
             last_clean_timestamp: 0,
             monitor_interval_secs: 60, // 1 minute
+            adaptive_interval: false,
+            min_monitor_interval_secs: default_min_monitor_interval_secs(),
+            max_monitor_interval_secs: default_max_monitor_interval_secs(),
             debug_mode: false,
             debug_simulated_size: 0,
             first_run_completed: false,
             first_clean_confirmed: false,
+            runaway_detection_enabled: default_true(),
+            runaway_cpu_percent: default_runaway_cpu_percent(),
+            runaway_cpu_duration_secs: default_runaway_cpu_duration_secs(),
+            runaway_growth_bytes: default_runaway_growth_bytes(),
+            runaway_growth_window_secs: default_runaway_growth_window_secs(),
+            runaway_auto_remediate: false,
+            auto_clean_max_failures: default_auto_clean_max_failures(),
+            auto_clean_backoff: AutoCleanBackoff::default(),
+            defer_while_busy: default_true(),
+            busy_process_names: default_busy_process_names(),
+            busy_max_defer_secs: default_busy_max_defer_secs(),
+            require_idle: false,
+            require_idle_secs: default_require_idle_secs(),
+            require_ac_power: false,
+            wake_grace_secs: default_wake_grace_secs(),
+            missed_clean_policy: MissedCleanPolicy::default(),
+            warning_reminder_secs: default_warning_reminder_secs(),
+            critical_reminder_secs: default_critical_reminder_secs(),
+            notification_snooze_secs: default_notification_snooze_secs(),
+            threshold_alerts: ThresholdAlerts::default(),
         }
     }
 }
 
 impl Settings {
-    /// Get the settings file path
-    fn file_path() -> PathBuf {
+    /// Get the settings directory
+    pub(crate) fn dir() -> PathBuf {
         let home = std::env::var("HOME").unwrap_or_else(|_| "/Users".to_string());
-        PathBuf::from(home)
-            .join("Library/Application Support/com.mvarley07.symbolsweep")
-            .join("settings.json")
+        PathBuf::from(home).join("Library/Application Support/com.mvarley07.symbolsweep")
     }
 
-    /// Load settings from disk
-    pub fn load() -> Self {
-        let path = Self::file_path();
-        if path.exists() {
-            match fs::read_to_string(&path) {
-                Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
-                Err(_) => Self::default(),
-            }
-        } else {
-            Self::default()
+    /// Load settings from disk, migrating older layouts and recovering from a corrupt file
+    pub fn load() -> LoadedSettings {
+        load_from(&Self::dir())
+    }
+
+    /// Whether settings.json was written by a newer version with a layout we don't know
+    pub fn is_from_newer_version(&self) -> bool {
+        self.schema_version > SCHEMA_VERSION
+    }
+
+    /// Refuse to overwrite settings written by a newer version, which may hold fields we'd lose
+    pub fn ensure_writable(&self) -> Result<(), String> {
+        if self.is_from_newer_version() {
+            return Err(format!(
+                "Settings were saved by a newer version of SymbolSweep (schema v{}) and won't be overwritten",
+                self.schema_version
+            ));
         }
+        Ok(())
     }
 
-    /// Save settings to disk
+    /// Save settings to disk atomically, keeping rolling backups of the previous file
     pub fn save(&self) -> Result<(), String> {
-        let path = Self::file_path();
+        self.ensure_writable()?;
+        save_to(&Self::dir(), self)
+    }
 
-        // Ensure directory exists
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)
-                .map_err(|e| format!("Failed to create settings directory: {}", e))?;
-        }
+    /// Update last clean timestamp (call `save` to persist)
+    pub fn record_clean(&mut self) {
+        self.record_clean_at(current_timestamp());
+    }
 
-        let content = serde_json::to_string_pretty(self)
-            .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    /// Update last clean timestamp to a specific time (call `save` to persist)
+    pub fn record_clean_at(&mut self, timestamp: u64) {
+        self.last_clean_timestamp = timestamp;
+    }
 
-        fs::write(&path, content).map_err(|e| format!("Failed to write settings: {}", e))
+    /// Move stored timestamps back after the wall clock was set back by `secs`,
+    /// so the time elapsed since them is preserved (call `save` to persist)
+    pub fn shift_timestamps_back(&mut self, secs: u64) {
+        self.last_clean_timestamp = self.last_clean_timestamp.saturating_sub(secs);
+        let backoff = &mut self.auto_clean_backoff;
+        if backoff.next_attempt_timestamp > 0 {
+            backoff.next_attempt_timestamp = backoff.next_attempt_timestamp.saturating_sub(secs);
+        }
+        self.threshold_alerts.shift_back(secs);
     }
 
-    /// Update last clean timestamp
-    pub fn record_clean(&mut self) {
-        self.last_clean_timestamp = current_timestamp();
-        let _ = self.save();
+    /// Keep the monitor-owned state and layout version from `current`
+    /// Used when settings arrive from the frontend or an edited file, whose copy may be stale
+    pub fn keep_runtime_state(&mut self, current: &Settings) {
+        self.last_clean_timestamp = current.last_clean_timestamp;
+        self.auto_clean_backoff = current.auto_clean_backoff.clone();
+        self.threshold_alerts = current.threshold_alerts.clone();
+        self.schema_version = current.schema_version;
     }
 }
 
@@ -111,201 +490,77 @@ fn current_timestamp() -> u64 {
         .as_secs()
 }
 
-/// Scheduler state
-pub struct Scheduler {
-    settings: Arc<Mutex<Settings>>,
-    running: Arc<Mutex<bool>>,
+/// Format duration for display, in the chosen duration style and the app's locale
+pub fn format_duration(secs: u64) -> String {
+    format_duration_in(secs, duration_style(), locale())
 }
 
-impl Scheduler {
-    pub fn new(settings: Settings) -> Self {
-        Self {
-            settings: Arc::new(Mutex::new(settings)),
-            running: Arc::new(Mutex::new(false)),
-        }
-    }
-
-    /// Get current settings
-    pub fn get_settings(&self) -> Settings {
-        self.settings.lock().unwrap().clone()
-    }
-
-    /// Update settings
-    pub fn update_settings(&self, new_settings: Settings) -> Result<(), String> {
-        let mut settings = self.settings.lock().unwrap();
-        *settings = new_settings;
-        settings.save()
+/// Get time since last clean
+pub fn time_since_last_clean(settings: &Settings) -> String {
+    if settings.last_clean_timestamp == 0 {
+        return tr(Msg::Never);
     }
+    format_last_clean(settings.last_clean_timestamp)
+}
 
-    /// Check if auto-clean should run based on threshold
-    pub fn should_auto_clean_threshold(&self) -> bool {
-        let settings = self.settings.lock().unwrap();
-        if !settings.auto_clean_on_threshold {
-            return false;
-        }
+/// How long ago a clean at `timestamp` was
+/// A clean stamped slightly ahead (clock adjustments) still reads as just now
+pub fn format_last_clean(timestamp: u64) -> String {
+    format_relative(timestamp.min(current_timestamp()))
+}
 
-        let status = get_cache_status();
-        status.size_bytes >= settings.auto_clean_threshold
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    /// Check if scheduled auto-clean should run
-    pub fn should_auto_clean_scheduled(&self) -> bool {
-        let settings = self.settings.lock().unwrap();
-        if !settings.auto_clean_scheduled {
-            return false;
-        }
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        let mut backoff = AutoCleanBackoff::default();
+        assert!(backoff.allows_attempt(0));
 
-        let now = current_timestamp();
-        let elapsed = now.saturating_sub(settings.last_clean_timestamp);
-        elapsed >= settings.auto_clean_interval_secs
-    }
-
-    /// Perform auto-clean if conditions are met
-    /// Returns Some(CleanResult) if clean was performed, None otherwise
-    pub fn check_and_auto_clean(&self) -> Option<CleanResult> {
-        let should_clean = self.should_auto_clean_threshold() || self.should_auto_clean_scheduled();
-
-        if should_clean {
-            match clean_cache(false) {
-                Ok(result) => {
-                    // Update last clean timestamp
-                    let mut settings = self.settings.lock().unwrap();
-                    settings.record_clean();
-                    Some(result)
-                }
-                Err(_) => None,
-            }
-        } else {
-            None
+        let mut delays = Vec::new();
+        for _ in 0..8 {
+            backoff.record_failure(1000, "boom".to_string(), 100);
+            delays.push(backoff.next_attempt_timestamp - 1000);
         }
+        assert_eq!(delays, vec![60, 120, 240, 480, 960, 1920, 3600, 3600]);
+        assert!(!backoff.allows_attempt(1000 + 3599));
+        assert!(backoff.allows_attempt(1000 + 3600));
     }
 
-    /// Start the scheduler loop (call from a background thread)
-    pub fn start(&self, callback: impl Fn(SchedulerEvent) + Send + 'static) {
-        let settings = Arc::clone(&self.settings);
-        let running = Arc::clone(&self.running);
-
-        // Set running flag
-        *running.lock().unwrap() = true;
-
-        std::thread::spawn(move || {
-            while *running.lock().unwrap() {
-                let interval = {
-                    let s = settings.lock().unwrap();
-                    s.monitor_interval_secs
-                };
-
-                // Get current cache status
-                let status = get_cache_status();
-                callback(SchedulerEvent::CacheStatusUpdate(status.clone()));
-
-                // Check if auto-clean should run
-                let should_clean_threshold = {
-                    let s = settings.lock().unwrap();
-                    s.auto_clean_on_threshold && status.size_bytes >= s.auto_clean_threshold
-                };
-
-                let should_clean_scheduled = {
-                    let s = settings.lock().unwrap();
-                    if !s.auto_clean_scheduled {
-                        false
-                    } else {
-                        let now = current_timestamp();
-                        let elapsed = now.saturating_sub(s.last_clean_timestamp);
-                        elapsed >= s.auto_clean_interval_secs
-                    }
-                };
-
-                if should_clean_threshold || should_clean_scheduled {
-                    callback(SchedulerEvent::AutoCleanTriggered);
-
-                    match clean_cache(false) {
-                        Ok(result) => {
-                            // Update last clean timestamp
-                            let mut s = settings.lock().unwrap();
-                            s.record_clean();
-                            callback(SchedulerEvent::AutoCleanCompleted(result));
-                        }
-                        Err(e) => {
-                            callback(SchedulerEvent::AutoCleanFailed(e.to_string()));
-                        }
-                    }
-                }
-
-                // Check for state changes that need notifications
-                if status.state == CacheState::Warning {
-                    callback(SchedulerEvent::WarningThresholdReached);
-                } else if status.state == CacheState::Critical {
-                    callback(SchedulerEvent::CriticalThresholdReached);
-                }
-
-                // Sleep until next check
-                std::thread::sleep(Duration::from_secs(interval));
-            }
-        });
-    }
-
-    /// Stop the scheduler
-    pub fn stop(&self) {
-        *self.running.lock().unwrap() = false;
+    #[test]
+    fn test_circuit_breaker_trips_once() {
+        let mut backoff = AutoCleanBackoff::default();
+        assert!(!backoff.record_failure(0, "a".to_string(), 3));
+        assert!(!backoff.record_failure(100, "b".to_string(), 3));
+        assert!(backoff.record_failure(500, "c".to_string(), 3));
+        assert!(backoff.paused);
+        assert!(!backoff.allows_attempt(u64::MAX));
+        assert_eq!(backoff.last_error.as_deref(), Some("c"));
+
+        // Already paused - doesn't trip again
+        assert!(!backoff.record_failure(600, "d".to_string(), 3));
+
+        backoff.reset();
+        assert_eq!(backoff, AutoCleanBackoff::default());
     }
 
-    /// Check if scheduler is running
-    pub fn is_running(&self) -> bool {
-        *self.running.lock().unwrap()
+    #[test]
+    fn test_backoff_persists_in_settings() {
+        let mut settings = Settings::default();
+        settings.auto_clean_backoff.record_failure(10, "nothing freed".to_string(), 3);
+
+        let json = serde_json::to_string(&settings).unwrap();
+        let restored: Settings = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.auto_clean_backoff, settings.auto_clean_backoff);
+
+        // Settings files from before backoff existed still load
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let map = value.as_object_mut().unwrap();
+        map.remove("auto_clean_backoff");
+        map.remove("auto_clean_max_failures");
+        let old: Settings = serde_json::from_value(value).unwrap();
+        assert_eq!(old.auto_clean_backoff, AutoCleanBackoff::default());
+        assert_eq!(old.auto_clean_max_failures, 3);
     }
 }
-
-/// Events emitted by the scheduler
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum SchedulerEvent {
-    CacheStatusUpdate(crate::cache_monitor::CacheStatus),
-    WarningThresholdReached,
-    CriticalThresholdReached,
-    AutoCleanTriggered,
-    AutoCleanCompleted(CleanResult),
-    AutoCleanFailed(String),
-}
-
-/// Format duration for display
-pub fn format_duration(secs: u64) -> String {
-    if secs < 60 {
-        if secs == 1 {
-            "1 second".to_string()
-        } else {
-            format!("{} seconds", secs)
-        }
-    } else if secs < 3600 {
-        let mins = secs / 60;
-        if mins == 1 {
-            "1 minute".to_string()
-        } else {
-            format!("{} minutes", mins)
-        }
-    } else if secs < 86400 {
-        let hours = secs / 3600;
-        if hours == 1 {
-            "1 hour".to_string()
-        } else {
-            format!("{} hours", hours)
-        }
-    } else {
-        let days = secs / 86400;
-        if days == 1 {
-            "1 day".to_string()
-        } else {
-            format!("{} days", days)
-        }
-    }
-}
-
-/// Get time since last clean
-pub fn time_since_last_clean(settings: &Settings) -> String {
-    if settings.last_clean_timestamp == 0 {
-        return "Never".to_string();
-    }
-
-    let now = current_timestamp();
-    let elapsed = now.saturating_sub(settings.last_clean_timestamp);
-    format!("{} ago", format_duration(elapsed))
-}
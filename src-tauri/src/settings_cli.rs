@@ -0,0 +1,163 @@
+use serde_json::{Map, Value};
+
+use crate::scheduler::Settings;
+use crate::settings_policy::Policy;
+use crate::settings_schema::settings_fields;
+use crate::settings_validation::{validate, FieldError, SettingsError};
+
+/// Command-line flag that overrides one setting: `--set auto_clean_threshold=8GB`
+pub const SET_FLAG: &str = "--set";
+
+/// Collect `--set field=value` (or `--set=field=value`) overrides from the arguments
+/// Values are read as JSON when they can be (`true`, `300`, `["Xcode"]`) and as
+/// text otherwise, so durations and sizes can be written as "6h" or "8GB".
+/// Arguments other than `--set` are left for whoever else reads them.
+pub fn parse_overrides(args: &[String]) -> Result<Map<String, Value>, SettingsError> {
+    let mut overrides = Map::new();
+    let mut errors = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let assignment = if arg == SET_FLAG {
+            args.next().map(String::as_str).unwrap_or("")
+        } else if let Some(assignment) = arg.strip_prefix("--set=") {
+            assignment
+        } else {
+            continue;
+        };
+
+        let Some((field, value)) = assignment.split_once('=') else {
+            errors.push(FieldError::Invalid {
+                field: SET_FLAG.to_string(),
+                reason: format!("expected field=value, got \"{}\"", assignment),
+            });
+            continue;
+        };
+        let value = serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()));
+        overrides.insert(field.trim().to_string(), value);
+    }
+
+    if errors.is_empty() {
+        Ok(overrides)
+    } else {
+        Err(SettingsError::Invalid { errors })
+    }
+}
+
+/// `current` with the overrides applied, checked like an update from the app
+pub fn apply_overrides(
+    current: &Settings,
+    overrides: Map<String, Value>,
+    policy: &Policy,
+) -> Result<Settings, SettingsError> {
+    let mut fields = settings_fields(current);
+    let mut errors = Vec::new();
+    for (field, value) in overrides {
+        if !fields.contains_key(&field) {
+            errors.push(FieldError::Invalid {
+                field,
+                reason: "not a setting".to_string(),
+            });
+            continue;
+        }
+        // Check each value on its own so every bad one is reported
+        let mut single = fields.clone();
+        single.insert(field.clone(), value.clone());
+        if let Err(e) = serde_json::from_value::<Settings>(Value::Object(single)) {
+            errors.push(FieldError::Invalid {
+                field,
+                reason: e.to_string(),
+            });
+            continue;
+        }
+        fields.insert(field, value);
+    }
+    if !errors.is_empty() {
+        return Err(SettingsError::Invalid { errors });
+    }
+
+    let mut settings: Settings = serde_json::from_value(Value::Object(fields))
+        .map_err(|e| SettingsError::Unreadable { message: e.to_string() })?;
+    settings.keep_runtime_state(current);
+    policy.check_update(&settings)?;
+    let errors = validate(&settings);
+    if !errors.is_empty() {
+        return Err(SettingsError::Invalid { errors });
+    }
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_overrides() {
+        let overrides = parse_overrides(&args(&[
+            "/Applications/SymbolSweep.app/Contents/MacOS/symbolsweep",
+            "--hidden",
+            "--set",
+            "auto_clean_threshold=8GB",
+            "--set=show_notifications=false",
+            "--set",
+            "busy_process_names=[\"Xcode\"]",
+        ]))
+        .unwrap();
+        assert_eq!(overrides["auto_clean_threshold"], "8GB");
+        assert_eq!(overrides["show_notifications"], false);
+        assert_eq!(overrides["busy_process_names"], serde_json::json!(["Xcode"]));
+
+        assert!(parse_overrides(&args(&["--hidden"])).unwrap().is_empty());
+        assert!(matches!(
+            parse_overrides(&args(&["--set", "auto_clean_threshold"])),
+            Err(SettingsError::Invalid { .. })
+        ));
+        assert!(matches!(parse_overrides(&args(&["--set"])), Err(SettingsError::Invalid { .. })));
+    }
+
+    #[test]
+    fn test_apply_overrides_parses_durations_and_sizes() {
+        let overrides = parse_overrides(&args(&[
+            "--set",
+            "auto_clean_threshold=4.5 GiB",
+            "--set",
+            "auto_clean_interval_secs=1d12h",
+        ]))
+        .unwrap();
+        let current = Settings {
+            last_clean_timestamp: 1_700_000_000,
+            ..Settings::default()
+        };
+        let settings = apply_overrides(&current, overrides, &Policy::default()).unwrap();
+        assert_eq!(settings.auto_clean_threshold, 4_831_838_208);
+        assert_eq!(settings.auto_clean_interval_secs, 36 * 60 * 60);
+        assert_eq!(settings.last_clean_timestamp, 1_700_000_000);
+    }
+
+    #[test]
+    fn test_apply_overrides_reports_every_bad_value() {
+        let overrides = parse_overrides(&args(&[
+            "--set",
+            "auto_clean_threshold=lots",
+            "--set",
+            "colour=blue",
+            "--set",
+            "monitor_interval_secs=1m",
+        ]))
+        .unwrap();
+        let Err(SettingsError::Invalid { errors }) = apply_overrides(&Settings::default(), overrides, &Policy::default())
+        else {
+            panic!("expected invalid overrides");
+        };
+        let mut fields: Vec<&str> = errors.iter().map(FieldError::field).collect();
+        fields.sort();
+        assert_eq!(fields, vec!["auto_clean_threshold", "colour"]);
+
+        // Readable but out of range
+        let overrides = parse_overrides(&args(&["--set", "monitor_interval_secs=1s"])).unwrap();
+        assert!(apply_overrides(&Settings::default(), overrides, &Policy::default()).is_err());
+    }
+}
@@ -0,0 +1,364 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::scheduler::Settings;
+use crate::settings_schema::settings_fields;
+use crate::settings_validation::{validate, FieldError, SettingsError};
+
+/// System-wide policy file, installed by an admin (MDM, provisioning scripts)
+pub const POLICY_PATH: &str = "/Library/Application Support/com.mvarley07.symbolsweep/policy.json";
+/// Environment variable that points at a different policy file (tests, trying a policy out)
+pub const POLICY_ENV: &str = "SYMBOLSWEEP_POLICY_FILE";
+
+/// State the app records for itself, which a policy can't pin
+const UNLOCKABLE: [&str; 4] = [
+    "schema_version",
+    "last_clean_timestamp",
+    "auto_clean_backoff",
+    "threshold_alerts",
+];
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Settings enforced by the policy file, layered on top of the user's settings
+///
+/// The file looks like `{ "locked": { "auto_clean_threshold": 8589934592 } }`.
+/// Every field under `locked` takes that value and can't be changed from the app
+/// or by editing settings.json.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Policy {
+    locked: Map<String, Value>,
+}
+
+/// What the frontend is told about the policy
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PolicyStatus {
+    /// Where the policy is read from
+    pub path: PathBuf,
+    /// Fields the policy locks (empty without a policy)
+    pub locked: Vec<String>,
+    /// Why the policy file couldn't be used, if it couldn't
+    pub error: Option<String>,
+    /// Saved settings reset to their defaults because they clashed with a locked value
+    pub reset: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PolicyError {
+    /// The file exists but couldn't be read
+    Unreadable(String),
+    /// The file isn't valid JSON
+    InvalidJson(String),
+    /// The file, or its `locked` entry, isn't a JSON object
+    NotAnObject,
+    /// The locked values together aren't valid settings
+    Invalid(Vec<FieldError>),
+}
+
+impl std::fmt::Display for PolicyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PolicyError::Unreadable(msg) => write!(f, "Policy file couldn't be read: {}", msg),
+            PolicyError::InvalidJson(msg) => write!(f, "Policy file is not valid JSON: {}", msg),
+            PolicyError::NotAnObject => write!(f, "Policy file must be an object with a \"locked\" object"),
+            PolicyError::Invalid(errors) => {
+                let messages: Vec<String> = errors.iter().map(FieldError::message).collect();
+                write!(f, "Policy file locks invalid values: {}", messages.join("; "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for PolicyError {}
+
+// ============================================================================
+// Loading
+// ============================================================================
+
+/// Policy file location, honouring `SYMBOLSWEEP_POLICY_FILE`
+pub fn policy_path() -> PathBuf {
+    policy_path_from(std::env::var_os(POLICY_ENV))
+}
+
+/// Policy file location given the value of `SYMBOLSWEEP_POLICY_FILE`, if set
+fn policy_path_from(env: Option<OsString>) -> PathBuf {
+    env.filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(POLICY_PATH))
+}
+
+/// Read the policy at `path` (None when there's no policy file)
+pub fn load_from(path: &Path) -> Result<Option<Policy>, PolicyError> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(PolicyError::Unreadable(e.to_string())),
+    };
+    let value: Value = serde_json::from_str(&content).map_err(|e| PolicyError::InvalidJson(e.to_string()))?;
+    let Value::Object(mut file) = value else {
+        return Err(PolicyError::NotAnObject);
+    };
+    match file.remove("locked") {
+        Some(Value::Object(locked)) => Policy::from_locked(locked).map(Some),
+        None => Ok(Some(Policy::default())),
+        Some(_) => Err(PolicyError::NotAnObject),
+    }
+}
+
+/// Load the policy and describe it for the frontend
+/// An unusable policy file is reported but locks nothing
+pub fn load_policy() -> (Policy, PolicyStatus) {
+    let path = policy_path();
+    let (policy, error) = match load_from(&path) {
+        Ok(policy) => (policy.unwrap_or_default(), None),
+        Err(e) => {
+            eprintln!("Settings: ignoring policy {}: {}", path.display(), e);
+            (Policy::default(), Some(e.to_string()))
+        }
+    };
+    let status = PolicyStatus {
+        path,
+        locked: policy.locked_fields(),
+        error,
+        reset: Vec::new(),
+    };
+    (policy, status)
+}
+
+impl Policy {
+    /// Keep the locked entries that name a lockable setting and hold a usable value
+    pub(crate) fn from_locked(locked: Map<String, Value>) -> Result<Self, PolicyError> {
+        let defaults = settings_fields(&Settings::default());
+        let mut fields = defaults.clone();
+        let mut kept = Vec::new();
+        for (key, value) in locked {
+            if UNLOCKABLE.contains(&key.as_str()) || !defaults.contains_key(&key) {
+                eprintln!("Settings: policy can't lock \"{}\", ignoring it", key);
+                continue;
+            }
+            let mut single = defaults.clone();
+            single.insert(key.clone(), value.clone());
+            if serde_json::from_value::<Settings>(Value::Object(single)).is_err() {
+                eprintln!("Settings: policy value for \"{}\" couldn't be read, ignoring it", key);
+                continue;
+            }
+            fields.insert(key.clone(), value);
+            kept.push(key);
+        }
+
+        let enforced: Settings = serde_json::from_value(Value::Object(fields))
+            .map_err(|e| PolicyError::InvalidJson(e.to_string()))?;
+        let errors: Vec<FieldError> = validate(&enforced)
+            .into_iter()
+            .filter(|error| kept.iter().any(|key| key == error.field()))
+            .collect();
+        if !errors.is_empty() {
+            return Err(PolicyError::Invalid(errors));
+        }
+
+        // Store values as the settings serialize them, so comparisons are exact (80 vs 80.0)
+        let normalized = settings_fields(&enforced);
+        let locked = kept
+            .into_iter()
+            .filter_map(|key| normalized.get(&key).cloned().map(|value| (key, value)))
+            .collect();
+        Ok(Self { locked })
+    }
+
+    /// Names of the locked fields, sorted
+    pub fn locked_fields(&self) -> Vec<String> {
+        self.locked.keys().cloned().collect()
+    }
+
+    /// Overwrite the locked fields in `settings`
+    /// Returns None if nothing changed, otherwise the unlocked fields that clashed
+    /// with a locked value and were reset to their defaults.
+    pub fn apply(&self, settings: &mut Settings) -> Option<Vec<String>> {
+        if self.locked.is_empty() {
+            return None;
+        }
+        let current = settings_fields(settings);
+        let mut fields = current.clone();
+        fields.extend(self.locked.iter().map(|(k, v)| (k.clone(), v.clone())));
+        if fields == current {
+            return None;
+        }
+
+        // A locked value can clash with the user's own (e.g. a locked minimum above their
+        // maximum); reset only the unlocked side of each clash and keep everything else
+        let defaults = settings_fields(&Settings::default());
+        let mut reset = Vec::new();
+        loop {
+            let mut enforced = serde_json::from_value::<Settings>(Value::Object(fields.clone())).ok()?;
+            let mut progressed = false;
+            for error in validate(&enforced) {
+                let Some(key) = self.unlocked_side(&error) else { continue };
+                let Some(default) = defaults.get(key) else { continue };
+                if fields.get(key) != Some(default) {
+                    fields.insert(key.to_string(), default.clone());
+                    reset.push(key.to_string());
+                    progressed = true;
+                }
+            }
+            if !progressed {
+                enforced.keep_runtime_state(settings);
+                *settings = enforced;
+                return Some(reset);
+            }
+        }
+    }
+
+    /// Field to reset to clear `error`: the field itself, or the other side of a
+    /// relationship when the field is locked
+    fn unlocked_side<'a>(&self, error: &'a FieldError) -> Option<&'a str> {
+        let other = match error {
+            FieldError::NotAtMost { other, .. } | FieldError::Requires { other, .. } => Some(other.as_str()),
+            _ => None,
+        };
+        std::iter::once(error.field())
+            .chain(other)
+            .find(|key| !self.locked.contains_key(*key))
+    }
+
+    /// Reject an update that changes a locked field
+    pub fn check_update(&self, settings: &Settings) -> Result<(), SettingsError> {
+        let fields = settings_fields(settings);
+        let errors: Vec<FieldError> = self
+            .locked
+            .iter()
+            .filter(|(key, value)| fields.get(*key) != Some(*value))
+            .map(|(key, _)| FieldError::Locked { field: key.clone() })
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(SettingsError::Invalid { errors })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write `content` to a policy file under the system temp dir
+    fn policy_file(test_name: &str, content: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("symbolsweep-policy-{}-{}.json", test_name, std::process::id()));
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn policy(test_name: &str, content: &str) -> Policy {
+        let path = policy_file(test_name, content);
+        let policy = load_from(&path).unwrap().unwrap();
+        let _ = fs::remove_file(path);
+        policy
+    }
+
+    #[test]
+    fn test_missing_file_is_no_policy() {
+        let path = std::env::temp_dir().join("symbolsweep-policy-does-not-exist.json");
+        assert_eq!(load_from(&path), Ok(None));
+    }
+
+    #[test]
+    fn test_env_overrides_the_system_path() {
+        assert_eq!(
+            policy_path_from(Some("/tmp/fleet-policy.json".into())),
+            PathBuf::from("/tmp/fleet-policy.json")
+        );
+        assert_eq!(policy_path_from(Some("".into())), PathBuf::from(POLICY_PATH));
+        assert_eq!(policy_path_from(None), PathBuf::from(POLICY_PATH));
+    }
+
+    #[test]
+    fn test_locked_fields_override_user_settings() {
+        let policy = policy(
+            "apply",
+            r#"{ "locked": { "auto_clean_threshold": 8589934592, "show_notifications": true, "runaway_cpu_percent": 90 } }"#,
+        );
+        assert_eq!(
+            policy.locked_fields(),
+            vec!["auto_clean_threshold", "runaway_cpu_percent", "show_notifications"]
+        );
+
+        let mut settings = Settings {
+            show_notifications: false,
+            last_clean_timestamp: 1_700_000_000,
+            ..Settings::default()
+        };
+        assert_eq!(policy.apply(&mut settings), Some(Vec::new()));
+        assert_eq!(settings.auto_clean_threshold, 8 * 1024 * 1024 * 1024);
+        assert!(settings.show_notifications);
+        assert_eq!(settings.runaway_cpu_percent, 90.0);
+        assert_eq!(settings.last_clean_timestamp, 1_700_000_000);
+        // Already enforced
+        assert_eq!(policy.apply(&mut settings), None);
+    }
+
+    #[test]
+    fn test_updates_to_locked_fields_are_rejected() {
+        let policy = policy("check", r#"{ "locked": { "show_notifications": true } }"#);
+        let mut settings = Settings::default();
+        policy.apply(&mut settings);
+        assert_eq!(policy.check_update(&settings), Ok(()));
+
+        // Unlocked fields are still the user's
+        settings.monitor_interval_secs = 300;
+        assert_eq!(policy.check_update(&settings), Ok(()));
+
+        settings.show_notifications = false;
+        assert_eq!(
+            policy.check_update(&settings),
+            Err(SettingsError::Invalid {
+                errors: vec![FieldError::Locked {
+                    field: "show_notifications".to_string(),
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn test_unusable_entries_are_ignored() {
+        let policy = policy(
+            "ignored",
+            r#"{ "locked": { "last_clean_timestamp": 0, "not_a_setting": 1, "monitor_interval_secs": "often", "require_ac_power": true } }"#,
+        );
+        assert_eq!(policy.locked_fields(), vec!["require_ac_power"]);
+    }
+
+    #[test]
+    fn test_invalid_policies_are_errors() {
+        let path = policy_file("invalid", r#"{ "locked": { "monitor_interval_secs": 0 } }"#);
+        assert!(matches!(load_from(&path), Err(PolicyError::Invalid(_))));
+        fs::write(&path, r#"{ "locked": [] }"#).unwrap();
+        assert_eq!(load_from(&path), Err(PolicyError::NotAnObject));
+        fs::write(&path, "{").unwrap();
+        assert!(matches!(load_from(&path), Err(PolicyError::InvalidJson(_))));
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_conflicting_user_settings_reset_only_the_clashing_field() {
+        let policy = policy("conflict", r#"{ "locked": { "min_monitor_interval_secs": 600 } }"#);
+        let mut settings = Settings {
+            max_monitor_interval_secs: 60,
+            show_notifications: false,
+            quiet_hours_enabled: true,
+            ..Settings::default()
+        };
+        assert_eq!(policy.apply(&mut settings), Some(vec!["max_monitor_interval_secs".to_string()]));
+        assert_eq!(settings.min_monitor_interval_secs, 600);
+        assert_eq!(settings.max_monitor_interval_secs, Settings::default().max_monitor_interval_secs);
+        // Unrelated preferences survive
+        assert!(!settings.show_notifications);
+        assert!(settings.quiet_hours_enabled);
+        assert!(validate(&settings).is_empty());
+    }
+}
@@ -0,0 +1,526 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::cache_monitor::WARNING_THRESHOLD;
+use crate::scheduler::Settings;
+use crate::settings_policy::Policy;
+use crate::settings_schema::{migrate, settings_fields, SCHEMA_VERSION};
+use crate::settings_store::{lock_saves, write_atomic};
+use crate::settings_validation::{validate, FieldError, SettingsError};
+
+/// Saved profiles, next to settings.json
+pub const PROFILES_FILE: &str = "profiles.json";
+/// Longest profile name (it's shown in the tray menu)
+const MAX_NAME_LEN: usize = 40;
+
+/// Fields that describe this Mac or this install rather than a preference,
+/// left out of exports and profiles and never changed by applying one
+const MACHINE_FIELDS: [&str; 10] = [
+    "schema_version",
+    "launch_at_login",
+    "last_clean_timestamp",
+    "presenting_mode",
+    "debug_mode",
+    "debug_simulated_size",
+    "first_run_completed",
+    "first_clean_confirmed",
+    "auto_clean_backoff",
+    "threshold_alerts",
+];
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// A named set of portable settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    pub name: String,
+    /// Shipped with the app (can't be overwritten or deleted)
+    pub builtin: bool,
+    /// Portable fields the profile sets; the rest take their defaults
+    fields: Map<String, Value>,
+}
+
+/// Profile summary for the frontend and tray menu
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProfileInfo {
+    pub name: String,
+    pub builtin: bool,
+}
+
+/// Portable settings as written to an export file or a profiles.json entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Document {
+    #[serde(default)]
+    schema_version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    settings: Map<String, Value>,
+}
+
+/// profiles.json
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ProfilesFile {
+    #[serde(default)]
+    profiles: Vec<Document>,
+}
+
+// ============================================================================
+// Import and export
+// ============================================================================
+
+/// Settings without the machine-specific fields
+pub fn portable_fields(settings: &Settings) -> Map<String, Value> {
+    let mut fields = settings_fields(settings);
+    fields.retain(|key, _| !MACHINE_FIELDS.contains(&key.as_str()));
+    fields
+}
+
+/// Portable copy of `settings` for importing on another Mac
+pub fn export(settings: &Settings) -> String {
+    let document = Document {
+        schema_version: SCHEMA_VERSION,
+        name: None,
+        settings: portable_fields(settings),
+    };
+    serde_json::to_string_pretty(&document).unwrap_or_default()
+}
+
+/// Read an exported file, checking every field it sets
+pub fn parse_export(content: &str) -> Result<Profile, SettingsError> {
+    let document: Document = serde_json::from_str(content).map_err(|e| SettingsError::Unreadable {
+        message: format!("Not a SymbolSweep settings file: {}", e),
+    })?;
+    Profile::from_document(document, false)
+}
+
+impl Profile {
+    /// Migrate a document to the current layout and keep its portable fields
+    fn from_document(document: Document, builtin: bool) -> Result<Self, SettingsError> {
+        if document.schema_version > SCHEMA_VERSION {
+            return Err(SettingsError::Unreadable {
+                message: format!(
+                    "Settings were exported by a newer SymbolSweep (schema v{})",
+                    document.schema_version
+                ),
+            });
+        }
+
+        let mut fields = document.settings;
+        fields.insert("schema_version".to_string(), Value::from(document.schema_version));
+        migrate(&mut fields);
+
+        let defaults = settings_fields(&Settings::default());
+        fields.retain(|key, _| defaults.contains_key(key) && !MACHINE_FIELDS.contains(&key.as_str()));
+        let errors: Vec<FieldError> = fields
+            .iter()
+            .filter(|(key, value)| {
+                let mut single = defaults.clone();
+                single.insert((*key).clone(), (*value).clone());
+                serde_json::from_value::<Settings>(Value::Object(single)).is_err()
+            })
+            .map(|(key, _)| FieldError::Invalid {
+                field: key.clone(),
+                reason: "couldn't be read".to_string(),
+            })
+            .collect();
+        if !errors.is_empty() {
+            return Err(SettingsError::Invalid { errors });
+        }
+
+        Ok(Self {
+            name: document.name.unwrap_or_default(),
+            builtin,
+            fields,
+        })
+    }
+
+    pub fn info(&self) -> ProfileInfo {
+        ProfileInfo {
+            name: self.name.clone(),
+            builtin: self.builtin,
+        }
+    }
+
+    /// `current` with the fields this profile sets replaced by its values
+    /// Everything the profile leaves out keeps the user's value.
+    pub fn apply_to(&self, current: &Settings, policy: &Policy) -> Result<Settings, SettingsError> {
+        let mut fields = settings_fields(current);
+        fields.extend(self.fields.iter().map(|(k, v)| (k.clone(), v.clone())));
+
+        let mut settings: Settings = serde_json::from_value(Value::Object(fields))
+            .map_err(|e| SettingsError::Unreadable { message: e.to_string() })?;
+        policy.apply(&mut settings);
+        let errors = validate(&settings);
+        if !errors.is_empty() {
+            return Err(SettingsError::Invalid { errors });
+        }
+        Ok(settings)
+    }
+}
+
+// ============================================================================
+// Profiles
+// ============================================================================
+
+/// Profiles shipped with the app
+pub fn builtin_profiles() -> Vec<Profile> {
+    let profile = |name: &str, fields: Value| Profile {
+        name: name.to_string(),
+        builtin: true,
+        fields: match fields {
+            Value::Object(fields) => fields,
+            _ => Map::new(),
+        },
+    };
+    let mut profiles = vec![
+        // Only ever cleans when asked, and stays out of the way of running work
+        profile(
+            "Conservative",
+            json!({
+                "auto_clean_on_threshold": false,
+                "auto_clean_scheduled": false,
+                "runaway_auto_remediate": false,
+                "defer_while_busy": true,
+                "require_idle": true,
+            }),
+        ),
+        // Unattended builds: clean early and on a schedule, restart a runaway daemon
+        profile(
+            "Build machine",
+            json!({
+                "auto_clean_on_threshold": true,
+                "auto_clean_threshold": WARNING_THRESHOLD,
+                "auto_clean_scheduled": true,
+                "auto_clean_interval_secs": 6 * 60 * 60,
+                "runaway_auto_remediate": true,
+                "defer_while_busy": true,
+                "missed_clean_policy": "CatchUp",
+            }),
+        ),
+        // Check less often and only clean on AC power
+        profile(
+            "Laptop on battery",
+            json!({
+                "auto_clean_on_threshold": true,
+                "auto_clean_threshold": 7 * 1024 * 1024 * 1024u64,
+                "monitor_interval_secs": 5 * 60,
+                "adaptive_interval": true,
+                "max_monitor_interval_secs": 30 * 60,
+                "require_ac_power": true,
+            }),
+        ),
+    ];
+
+    // Each built-in covers every field any of them sets, so switching between them
+    // doesn't depend on which was active before, and leaves other preferences alone
+    let defaults = settings_fields(&Settings::default());
+    let keys: Vec<String> = profiles.iter().flat_map(|p| p.fields.keys().cloned()).collect();
+    for profile in &mut profiles {
+        for key in &keys {
+            if profile.fields.contains_key(key) {
+                continue;
+            }
+            if let Some(default) = defaults.get(key) {
+                profile.fields.insert(key.clone(), default.clone());
+            }
+        }
+    }
+    profiles
+}
+
+/// Built-in profiles followed by saved ones (sorted by name)
+pub fn list_profiles(dir: &Path) -> Vec<Profile> {
+    let mut profiles = builtin_profiles();
+    match read_saved(dir) {
+        Ok(saved) => profiles.extend(saved.into_iter().filter_map(|document| {
+            let name = document.name.clone().unwrap_or_default();
+            Profile::from_document(document, false)
+                .map_err(|e| eprintln!("Settings: skipping profile \"{}\": {}", name, e))
+                .ok()
+        })),
+        Err(e) => eprintln!("Settings: couldn't read profiles: {}", e),
+    }
+    profiles
+}
+
+/// Find a profile by name (case-insensitive)
+pub fn find_profile(dir: &Path, name: &str) -> Result<Profile, SettingsError> {
+    list_profiles(dir)
+        .into_iter()
+        .find(|profile| profile.name.eq_ignore_ascii_case(name.trim()))
+        .ok_or_else(|| SettingsError::UnknownProfile { name: name.to_string() })
+}
+
+/// Save the portable part of `settings` as `name`, replacing a saved profile of that name
+pub fn save_profile(dir: &Path, name: &str, settings: &Settings) -> Result<(), SettingsError> {
+    let name = name.trim();
+    let invalid = |reason: &str| SettingsError::Invalid {
+        errors: vec![FieldError::Invalid {
+            field: "name".to_string(),
+            reason: reason.to_string(),
+        }],
+    };
+    if name.is_empty() {
+        return Err(invalid("profile names can't be empty"));
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(invalid(&format!("profile names can be at most {} characters", MAX_NAME_LEN)));
+    }
+    if builtin_profiles().iter().any(|p| p.name.eq_ignore_ascii_case(name)) {
+        return Err(invalid("that name belongs to a built-in profile"));
+    }
+
+    // Don't overwrite a profiles file we couldn't read
+    let _saving = lock_saves();
+    let mut saved = read_saved(dir)?;
+    saved.retain(|document| !document.name.as_deref().is_some_and(|n| n.eq_ignore_ascii_case(name)));
+    saved.push(Document {
+        schema_version: SCHEMA_VERSION,
+        name: Some(name.to_string()),
+        settings: portable_fields(settings),
+    });
+    write_saved(dir, saved)
+}
+
+/// Delete a saved profile
+pub fn delete_profile(dir: &Path, name: &str) -> Result<(), SettingsError> {
+    let name = name.trim();
+    if builtin_profiles().iter().any(|p| p.name.eq_ignore_ascii_case(name)) {
+        return Err(SettingsError::Invalid {
+            errors: vec![FieldError::Invalid {
+                field: "name".to_string(),
+                reason: "built-in profiles can't be deleted".to_string(),
+            }],
+        });
+    }
+
+    let _saving = lock_saves();
+    let mut saved = read_saved(dir)?;
+    let before = saved.len();
+    saved.retain(|document| !document.name.as_deref().is_some_and(|n| n.eq_ignore_ascii_case(name)));
+    if saved.len() == before {
+        return Err(SettingsError::UnknownProfile { name: name.to_string() });
+    }
+    write_saved(dir, saved)
+}
+
+fn read_saved(dir: &Path) -> Result<Vec<Document>, SettingsError> {
+    let content = match fs::read_to_string(dir.join(PROFILES_FILE)) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(SettingsError::Unreadable {
+                message: format!("Failed to read profiles: {}", e),
+            })
+        }
+    };
+    let file: ProfilesFile = serde_json::from_str(&content).map_err(|e| SettingsError::Unreadable {
+        message: format!("Profiles file is not valid: {}", e),
+    })?;
+    Ok(file.profiles)
+}
+
+fn write_saved(dir: &Path, mut profiles: Vec<Document>) -> Result<(), SettingsError> {
+    profiles.sort_by_key(|document| document.name.clone().unwrap_or_default().to_lowercase());
+    let save_failed = |message: String| SettingsError::SaveFailed { message };
+    let content = serde_json::to_string_pretty(&ProfilesFile { profiles })
+        .map_err(|e| save_failed(format!("Failed to serialize profiles: {}", e)))?;
+    fs::create_dir_all(dir).map_err(|e| save_failed(format!("Failed to create settings directory: {}", e)))?;
+    write_atomic(&dir.join(PROFILES_FILE), content.as_bytes())
+        .map_err(|e| save_failed(format!("Failed to write profiles: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::units::SizeUnits;
+    use std::path::PathBuf;
+
+    /// Fresh settings directory under the system temp dir
+    fn temp_dir(test_name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("symbolsweep-profiles-{}-{}", test_name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn names(profiles: &[Profile]) -> Vec<&str> {
+        profiles.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_builtin_profiles_are_valid() {
+        for profile in builtin_profiles() {
+            let applied = profile.apply_to(&Settings::default(), &Policy::default());
+            assert!(applied.is_ok(), "{}: {:?}", profile.name, applied);
+        }
+    }
+
+    #[test]
+    fn test_export_leaves_out_machine_fields() {
+        let settings = Settings {
+            last_clean_timestamp: 1_700_000_000,
+            debug_mode: true,
+            debug_simulated_size: 42,
+            monitor_interval_secs: 300,
+            ..Settings::default()
+        };
+        let exported: Value = serde_json::from_str(&export(&settings)).unwrap();
+        assert_eq!(exported["schema_version"], SCHEMA_VERSION);
+        let fields = exported["settings"].as_object().unwrap();
+        assert_eq!(fields["monitor_interval_secs"], 300);
+        for field in MACHINE_FIELDS {
+            assert!(!fields.contains_key(field), "{} was exported", field);
+        }
+    }
+
+    #[test]
+    fn test_import_round_trip_keeps_this_machines_state() {
+        let other_mac = Settings {
+            require_ac_power: true,
+            busy_process_names: vec!["swift-frontend".to_string()],
+            launch_at_login: true,
+            ..Settings::default()
+        };
+        let here = Settings {
+            last_clean_timestamp: 1_700_000_000,
+            first_run_completed: true,
+            ..Settings::default()
+        };
+
+        let imported = parse_export(&export(&other_mac))
+            .unwrap()
+            .apply_to(&here, &Policy::default())
+            .unwrap();
+        assert!(imported.require_ac_power);
+        assert_eq!(imported.busy_process_names, vec!["swift-frontend"]);
+        assert!(!imported.launch_at_login);
+        assert_eq!(imported.last_clean_timestamp, 1_700_000_000);
+        assert!(imported.first_run_completed);
+    }
+
+    #[test]
+    fn test_import_rejects_bad_files() {
+        assert!(matches!(parse_export("{}"), Err(SettingsError::Unreadable { .. })));
+        assert!(matches!(
+            parse_export(r#"{ "schema_version": 99, "settings": {} }"#),
+            Err(SettingsError::Unreadable { .. })
+        ));
+
+        let Err(SettingsError::Invalid { errors }) =
+            parse_export(r#"{ "schema_version": 1, "settings": { "monitor_interval_secs": "often" } }"#)
+        else {
+            panic!("expected an unreadable field");
+        };
+        assert_eq!(errors[0].field(), "monitor_interval_secs");
+
+        // Readable but out of range fails when applied
+        let profile = parse_export(r#"{ "schema_version": 1, "settings": { "monitor_interval_secs": 1 } }"#).unwrap();
+        assert!(matches!(
+            profile.apply_to(&Settings::default(), &Policy::default()),
+            Err(SettingsError::Invalid { .. })
+        ));
+    }
+
+    #[test]
+    fn test_unversioned_exports_are_migrated() {
+        let profile = parse_export(r#"{ "settings": { "show_notifications": false } }"#).unwrap();
+        let applied = profile.apply_to(&Settings::default(), &Policy::default()).unwrap();
+        assert!(!applied.show_notifications);
+        assert_eq!(applied.schema_version, SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_switching_profiles_is_independent_of_the_previous_one() {
+        let dir = temp_dir("switch");
+        let battery = find_profile(&dir, "laptop on battery").unwrap();
+        let conservative = find_profile(&dir, "Conservative").unwrap();
+
+        let on_battery = battery.apply_to(&Settings::default(), &Policy::default()).unwrap();
+        assert!(on_battery.require_ac_power);
+        let switched = conservative.apply_to(&on_battery, &Policy::default()).unwrap();
+        assert!(!switched.require_ac_power);
+        assert_eq!(switched.monitor_interval_secs, Settings::default().monitor_interval_secs);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_switching_profiles_keeps_unrelated_preferences() {
+        let dir = temp_dir("unrelated");
+        let conservative = find_profile(&dir, "Conservative").unwrap();
+        let current = Settings {
+            size_units: SizeUnits::Si,
+            quiet_hours_enabled: true,
+            show_notifications: false,
+            busy_process_names: vec!["MyTool".to_string()],
+            ..Settings::default()
+        };
+
+        let switched = conservative.apply_to(&current, &Policy::default()).unwrap();
+        assert!(switched.require_idle);
+        assert_eq!(switched.size_units, SizeUnits::Si);
+        assert!(switched.quiet_hours_enabled);
+        assert!(!switched.show_notifications);
+        assert_eq!(switched.busy_process_names, vec!["MyTool".to_string()]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_save_list_and_delete() {
+        let dir = temp_dir("saved");
+        let settings = Settings {
+            monitor_interval_secs: 120,
+            ..Settings::default()
+        };
+        save_profile(&dir, "  Travel ", &settings).unwrap();
+        save_profile(&dir, "Office", &Settings::default()).unwrap();
+        // Same name, different case: replaced rather than duplicated
+        save_profile(&dir, "travel", &settings).unwrap();
+
+        let profiles = list_profiles(&dir);
+        assert_eq!(
+            names(&profiles),
+            vec!["Conservative", "Build machine", "Laptop on battery", "Office", "travel"]
+        );
+        let travel = find_profile(&dir, "TRAVEL").unwrap();
+        assert!(!travel.builtin);
+        let applied = travel.apply_to(&Settings::default(), &Policy::default()).unwrap();
+        assert_eq!(applied.monitor_interval_secs, 120);
+
+        delete_profile(&dir, "Office").unwrap();
+        assert_eq!(
+            delete_profile(&dir, "Office"),
+            Err(SettingsError::UnknownProfile {
+                name: "Office".to_string(),
+            })
+        );
+        assert!(matches!(delete_profile(&dir, "Conservative"), Err(SettingsError::Invalid { .. })));
+        assert!(matches!(
+            save_profile(&dir, "Build Machine", &settings),
+            Err(SettingsError::Invalid { .. })
+        ));
+        assert!(matches!(save_profile(&dir, " ", &settings), Err(SettingsError::Invalid { .. })));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_policy_wins_over_a_profile() {
+        let dir = temp_dir("policy");
+        let Value::Object(locked) = json!({ "require_ac_power": false }) else {
+            unreachable!()
+        };
+        let policy = Policy::from_locked(locked).unwrap();
+        let applied = find_profile(&dir, "Laptop on battery")
+            .unwrap()
+            .apply_to(&Settings::default(), &policy)
+            .unwrap();
+        assert!(!applied.require_ac_power);
+        assert!(applied.adaptive_interval);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,265 @@
+use serde_json::{Map, Value};
+
+use crate::scheduler::Settings;
+
+/// Layout version written to settings.json
+/// Bump it and add a step to `MIGRATIONS` whenever a field is renamed, removed
+/// or changes type. Purely additive fields with a serde default don't need one.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Upgrades settings.json one layout at a time; entry `n` turns layout `n` into `n + 1`
+const MIGRATIONS: [fn(&mut Map<String, Value>); SCHEMA_VERSION as usize] = [migrate_v0_to_v1];
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Settings read from settings.json
+#[derive(Debug)]
+pub struct ParsedSettings {
+    pub settings: Settings,
+    /// Layout the file was written with (0 = unversioned)
+    pub file_version: u32,
+    /// Fields that couldn't be read and fell back to their defaults
+    pub dropped_fields: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaError {
+    /// The file isn't valid JSON
+    InvalidJson(String),
+    /// The file is JSON but not an object
+    NotAnObject,
+}
+
+impl std::fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaError::InvalidJson(msg) => write!(f, "Settings file is not valid JSON: {}", msg),
+            SchemaError::NotAnObject => write!(f, "Settings file is not a JSON object"),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+// ============================================================================
+// Parsing
+// ============================================================================
+
+/// Parse settings.json, migrating older layouts to the current one
+///
+/// Missing fields take their defaults, and a field that can't be read (wrong
+/// type, unknown enum variant) is dropped on its own rather than discarding the
+/// whole file. Files from a newer version are read as far as possible and keep
+/// their `schema_version`, which makes `Settings::save` refuse to overwrite them.
+pub fn parse_settings(content: &str) -> Result<ParsedSettings, SchemaError> {
+    let value: Value = serde_json::from_str(content).map_err(|e| SchemaError::InvalidJson(e.to_string()))?;
+    let Value::Object(mut fields) = value else {
+        return Err(SchemaError::NotAnObject);
+    };
+
+    let file_version = migrate(&mut fields);
+    let (settings, dropped_fields) = deserialize_lenient(fields);
+    Ok(ParsedSettings {
+        settings,
+        file_version,
+        dropped_fields,
+    })
+}
+
+/// Bring settings fields up to the current layout, returning the layout they were in
+pub fn migrate(fields: &mut Map<String, Value>) -> u32 {
+    let file_version = schema_version(fields);
+    for (version, step) in MIGRATIONS.iter().enumerate().skip(file_version as usize) {
+        step(fields);
+        fields.insert("schema_version".to_string(), Value::from(version as u32 + 1));
+    }
+    file_version
+}
+
+/// Layout version recorded in the file (files from before versioning have none)
+fn schema_version(fields: &Map<String, Value>) -> u32 {
+    fields
+        .get("schema_version")
+        .and_then(Value::as_u64)
+        .map_or(0, |v| v.min(u32::MAX as u64) as u32)
+}
+
+/// Deserialize on top of the defaults, dropping any field that can't be read
+fn deserialize_lenient(fields: Map<String, Value>) -> (Settings, Vec<String>) {
+    let Ok(Value::Object(defaults)) = serde_json::to_value(Settings::default()) else {
+        return (Settings::default(), fields.keys().cloned().collect());
+    };
+    let merge = |fields: &Map<String, Value>| {
+        let mut merged = defaults.clone();
+        merged.extend(fields.iter().map(|(k, v)| (k.clone(), v.clone())));
+        serde_json::from_value::<Settings>(Value::Object(merged))
+    };
+
+    if let Ok(settings) = merge(&fields) {
+        return (settings, Vec::new());
+    }
+
+    // Find the offending fields by trying each one on its own
+    let mut kept = Map::new();
+    let mut dropped = Vec::new();
+    for (key, value) in fields {
+        let single = Map::from_iter([(key.clone(), value.clone())]);
+        if merge(&single).is_ok() {
+            kept.insert(key, value);
+        } else {
+            dropped.push(key);
+        }
+    }
+    let settings = merge(&kept).unwrap_or_default();
+    (settings, dropped)
+}
+
+/// Settings as a JSON object keyed by field name
+pub fn settings_fields(settings: &Settings) -> Map<String, Value> {
+    match serde_json::to_value(settings) {
+        Ok(Value::Object(fields)) => fields,
+        _ => Map::new(),
+    }
+}
+
+// ============================================================================
+// Migrations
+// ============================================================================
+
+/// v0 (unversioned: 1.0 through quiet hours, busy deferral and threshold reminders) -> v1
+/// Every field added in that time has a serde default, so the layout carries
+/// over unchanged; this step only starts recording the version.
+fn migrate_v0_to_v1(_fields: &mut Map<String, Value>) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::{CleanSchedule, TimeOfDay, WeekdayMask};
+    use crate::scheduler::MissedCleanPolicy;
+
+    const V0_1_0: &str = include_str!("../tests/fixtures/settings/v0-1.0.json");
+    const V0_SCHEDULES: &str = include_str!("../tests/fixtures/settings/v0-schedules.json");
+    const V1: &str = include_str!("../tests/fixtures/settings/v1.json");
+    const V1_BAD_FIELD: &str = include_str!("../tests/fixtures/settings/v1-bad-field.json");
+    const FUTURE: &str = include_str!("../tests/fixtures/settings/future.json");
+
+    #[test]
+    fn test_migrations_cover_every_version() {
+        assert_eq!(MIGRATIONS.len(), SCHEMA_VERSION as usize);
+        assert_eq!(Settings::default().schema_version, SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_v0_from_1_0_keeps_user_choices() {
+        let parsed = parse_settings(V0_1_0).unwrap();
+        assert_eq!(parsed.file_version, 0);
+        assert!(parsed.dropped_fields.is_empty());
+
+        let s = parsed.settings;
+        assert_eq!(s.schema_version, SCHEMA_VERSION);
+        assert!(s.auto_clean_on_threshold);
+        assert_eq!(s.auto_clean_threshold, 8 * 1024 * 1024 * 1024);
+        assert!(!s.show_notifications);
+        assert!(s.launch_at_login);
+        assert_eq!(s.last_clean_timestamp, 1_699_990_000);
+        assert_eq!(s.monitor_interval_secs, 120);
+        assert!(s.first_clean_confirmed);
+        // Fields that didn't exist yet take their defaults
+        assert!(s.runaway_detection_enabled);
+        assert_eq!(s.auto_clean_schedule, CleanSchedule::Interval);
+    }
+
+    #[test]
+    fn test_v0_with_schedules_and_backoff() {
+        let parsed = parse_settings(V0_SCHEDULES).unwrap();
+        assert_eq!(parsed.file_version, 0);
+        assert!(parsed.dropped_fields.is_empty());
+
+        let s = parsed.settings;
+        assert_eq!(
+            s.auto_clean_schedule,
+            CleanSchedule::Daily {
+                times: vec![TimeOfDay { hour: 12, minute: 30 }],
+                weekdays: WeekdayMask::WEEKDAYS,
+            }
+        );
+        assert_eq!(s.schedule_time_zone.as_deref(), Some("Europe/London"));
+        assert_eq!(s.quiet_hours_start, TimeOfDay { hour: 23, minute: 0 });
+        assert_eq!(s.auto_clean_backoff.consecutive_failures, 1);
+        assert_eq!(s.busy_process_names, vec!["Xcode", "lldb"]);
+        assert!(s.require_ac_power);
+        assert_eq!(s.missed_clean_policy, MissedCleanPolicy::Skip);
+        assert_eq!(s.warning_reminder_secs, Settings::default().warning_reminder_secs);
+    }
+
+    #[test]
+    fn test_v1_reads_as_is() {
+        let parsed = parse_settings(V1).unwrap();
+        assert_eq!(parsed.file_version, 1);
+        assert!(parsed.dropped_fields.is_empty());
+        assert!(parsed.settings.adaptive_interval);
+        assert_eq!(parsed.settings.warning_reminder_secs, 0);
+        assert_eq!(parsed.settings.critical_reminder_secs, 7200);
+        assert_eq!(parsed.settings.threshold_alerts.last_notified_at, 1_700_000_000);
+    }
+
+    #[test]
+    fn test_unreadable_fields_fall_back_individually() {
+        let parsed = parse_settings(V1_BAD_FIELD).unwrap();
+        let mut dropped = parsed.dropped_fields.clone();
+        dropped.sort();
+        assert_eq!(dropped, vec!["missed_clean_policy", "monitor_interval_secs"]);
+
+        let s = parsed.settings;
+        assert_eq!(s.monitor_interval_secs, Settings::default().monitor_interval_secs);
+        assert_eq!(s.missed_clean_policy, MissedCleanPolicy::CatchUp);
+        // The rest of the file survives
+        assert!(s.auto_clean_on_threshold);
+        assert!(s.launch_at_login);
+    }
+
+    #[test]
+    fn test_future_version_is_read_only() {
+        let parsed = parse_settings(FUTURE).unwrap();
+        assert_eq!(parsed.file_version, 7);
+        assert!(parsed.dropped_fields.is_empty());
+
+        let s = parsed.settings;
+        assert_eq!(s.schema_version, 7);
+        assert!(!s.show_notifications);
+        assert_eq!(s.monitor_interval_secs, 30);
+        assert!(s.is_from_newer_version());
+        assert!(s.ensure_writable().is_err());
+    }
+
+    #[test]
+    fn test_round_trip_is_current_version() {
+        let json = serde_json::to_string(&Settings::default()).unwrap();
+        let parsed = parse_settings(&json).unwrap();
+        assert_eq!(parsed.file_version, SCHEMA_VERSION);
+        assert!(parsed.settings.ensure_writable().is_ok());
+    }
+
+    #[test]
+    fn test_durations_and_sizes_can_be_written_as_text() {
+        let parsed = parse_settings(
+            r#"{ "schema_version": 1, "auto_clean_interval_secs": "1d12h", "auto_clean_threshold": "8GB", "monitor_interval_secs": "2m" }"#,
+        )
+        .unwrap();
+        assert!(parsed.dropped_fields.is_empty());
+        assert_eq!(parsed.settings.auto_clean_interval_secs, 36 * 60 * 60);
+        assert_eq!(parsed.settings.auto_clean_threshold, 8 * 1024 * 1024 * 1024);
+        assert_eq!(parsed.settings.monitor_interval_secs, 120);
+
+        let parsed = parse_settings(r#"{ "schema_version": 1, "auto_clean_threshold": "lots" }"#).unwrap();
+        assert_eq!(parsed.dropped_fields, vec!["auto_clean_threshold"]);
+    }
+
+    #[test]
+    fn test_rejects_non_settings_files() {
+        assert!(matches!(parse_settings("{ not json"), Err(SchemaError::InvalidJson(_))));
+        assert_eq!(parse_settings("[1, 2]").unwrap_err(), SchemaError::NotAnObject);
+    }
+}
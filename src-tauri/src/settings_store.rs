@@ -0,0 +1,442 @@
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, SystemTime};
+
+use crate::i18n::{tr_with, Msg};
+use crate::scheduler::Settings;
+use crate::settings_schema::parse_settings;
+use crate::settings_validation::sanitize;
+
+/// Settings file name inside the settings directory
+pub const SETTINGS_FILE: &str = "settings.json";
+/// Directory (inside the settings directory) holding last-known-good copies
+const BACKUP_DIR: &str = "backups";
+/// Backups kept; the oldest is dropped when a new one is taken
+const MAX_BACKUPS: usize = 5;
+/// Minimum age of the newest backup before another is taken
+/// Saves happen on every clean, so backing up each one would rotate out
+/// anything older than the last few minutes
+const BACKUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Held for every write to the settings directory, so concurrent saves can't interleave
+static SAVE_LOCK: Mutex<()> = Mutex::new(());
+/// Makes each temp file name unique within the process
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+/// Hash of the content the app last wrote to each file
+static OWN_WRITES: OnceLock<Mutex<HashMap<PathBuf, u64>>> = OnceLock::new();
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// What happened when settings.json couldn't be read
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SettingsRecovery {
+    /// Why the settings file was rejected
+    pub reason: String,
+    /// Where the unreadable file was moved (None if it couldn't be moved)
+    pub corrupt_copy: Option<PathBuf>,
+    /// Backup the settings were restored from (None = reset to defaults)
+    pub restored_from: Option<PathBuf>,
+}
+
+impl SettingsRecovery {
+    /// One-line summary for notifications and logs
+    pub fn message(&self) -> String {
+        let msg = match &self.restored_from {
+            Some(_) => Msg::RecoveryRestored,
+            None => Msg::RecoveryReset,
+        };
+        tr_with(msg, &[("reason", &self.reason)])
+    }
+}
+
+/// Settings read from disk, with what was done to recover them if needed
+#[derive(Debug)]
+pub struct LoadedSettings {
+    pub settings: Settings,
+    pub recovery: Option<SettingsRecovery>,
+}
+
+// ============================================================================
+// Saving
+// ============================================================================
+
+/// Write settings.json in `dir` atomically, backing up the previous file first
+pub fn save_to(dir: &Path, settings: &Settings) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create settings directory: {}", e))?;
+
+    let content =
+        serde_json::to_string_pretty(settings).map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+    let path = dir.join(SETTINGS_FILE);
+    let _saving = lock_saves();
+    if let Err(e) = backup_if_due(dir, &path) {
+        // A missed backup shouldn't stop the save itself
+        eprintln!("Settings: backup failed: {}", e);
+    }
+    write_atomic(&path, content.as_bytes()).map_err(|e| format!("Failed to write settings: {}", e))
+}
+
+/// Serialize writes to the settings directory (settings, backups and profiles)
+/// Read-modify-write callers hold this across the read as well as the write.
+pub(crate) fn lock_saves() -> MutexGuard<'static, ()> {
+    // A panicked writer leaves nothing half-done that the lock protects
+    SAVE_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Write via a temp file in the same directory, fsync it, then rename over `path`
+/// A crash leaves either the old file or the new one, never a truncated mix
+pub(crate) fn write_atomic(path: &Path, content: &[u8]) -> io::Result<()> {
+    let tmp = path.with_extension(format!(
+        "json.{}-{}.tmp",
+        std::process::id(),
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let result = (|| {
+        let mut file = File::create(&tmp)?;
+        file.write_all(content)?;
+        file.sync_all()?;
+        fs::rename(&tmp, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+        return result;
+    }
+    record_own_write(path, content);
+
+    // Persist the rename itself (best effort - not every platform can open a directory)
+    if let Some(parent) = path.parent() {
+        if let Ok(dir) = File::open(parent) {
+            let _ = dir.sync_all();
+        }
+    }
+    Ok(())
+}
+
+/// Whether `content` is what the app itself last wrote to `path`
+/// Lets the settings watcher tell the app's own saves from outside edits.
+pub(crate) fn is_own_write(path: &Path, content: &str) -> bool {
+    let own_writes = OWN_WRITES.get_or_init(Default::default).lock().unwrap();
+    own_writes.get(path) == Some(&content_hash(content.as_bytes()))
+}
+
+fn record_own_write(path: &Path, content: &[u8]) {
+    let mut own_writes = OWN_WRITES.get_or_init(Default::default).lock().unwrap();
+    own_writes.insert(path.to_path_buf(), content_hash(content));
+}
+
+fn content_hash(content: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Copy the current file into the backups if it's readable and the newest backup is old enough
+fn backup_if_due(dir: &Path, path: &Path) -> io::Result<()> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Ok(());
+    };
+    // Only known-good files are worth keeping
+    if parse_settings(&content).is_err() {
+        return Ok(());
+    }
+
+    let backups = backup_paths(dir);
+    let newest_age = backups
+        .first()
+        .and_then(|p| fs::metadata(p).and_then(|m| m.modified()).ok())
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+    if newest_age.is_some_and(|age| age < BACKUP_INTERVAL) {
+        return Ok(());
+    }
+
+    // Shift settings.1.json -> settings.2.json and so on, dropping the oldest
+    for n in (1..MAX_BACKUPS).rev() {
+        let from = backup_path(dir, n);
+        if from.exists() {
+            fs::rename(&from, backup_path(dir, n + 1))?;
+        }
+    }
+    fs::create_dir_all(dir.join(BACKUP_DIR))?;
+    write_atomic(&backup_path(dir, 1), content.as_bytes())
+}
+
+fn backup_path(dir: &Path, n: usize) -> PathBuf {
+    dir.join(BACKUP_DIR).join(format!("settings.{}.json", n))
+}
+
+/// Existing backups, newest first
+fn backup_paths(dir: &Path) -> Vec<PathBuf> {
+    (1..=MAX_BACKUPS)
+        .map(|n| backup_path(dir, n))
+        .filter(|p| p.exists())
+        .collect()
+}
+
+// ============================================================================
+// Loading
+// ============================================================================
+
+/// Read settings.json from `dir`, restoring the newest valid backup if it's corrupt
+pub fn load_from(dir: &Path) -> LoadedSettings {
+    let path = dir.join(SETTINGS_FILE);
+    let reason = match fs::read_to_string(&path) {
+        Ok(content) => match parse_settings(&content) {
+            Ok(parsed) => {
+                log_parse_notes(&parsed.settings, parsed.file_version, &parsed.dropped_fields);
+                return LoadedSettings {
+                    settings: sanitized(parsed.settings),
+                    recovery: None,
+                };
+            }
+            Err(e) => e.to_string(),
+        },
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            return LoadedSettings {
+                settings: Settings::default(),
+                recovery: None,
+            }
+        }
+        Err(e) => format!("Failed to read settings: {}", e),
+    };
+
+    let recovery = recover(dir, &path, reason);
+    eprintln!("Settings: {}", recovery.0.message());
+    LoadedSettings {
+        settings: recovery.1,
+        recovery: Some(recovery.0),
+    }
+}
+
+/// Move the bad file aside and fall back to the newest backup that parses
+fn recover(dir: &Path, path: &Path, reason: String) -> (SettingsRecovery, Settings) {
+    let stamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let corrupt = dir.join(format!("settings.corrupt-{}.json", stamp));
+    let corrupt_copy = fs::rename(path, &corrupt).ok().map(|_| corrupt);
+
+    for backup in backup_paths(dir) {
+        let Ok(content) = fs::read_to_string(&backup) else { continue };
+        let Ok(parsed) = parse_settings(&content) else { continue };
+        let _saving = lock_saves();
+        if let Err(e) = write_atomic(path, content.as_bytes()) {
+            eprintln!("Settings: couldn't restore {}: {}", backup.display(), e);
+        }
+        let recovery = SettingsRecovery {
+            reason,
+            corrupt_copy,
+            restored_from: Some(backup),
+        };
+        return (recovery, sanitized(parsed.settings));
+    }
+
+    let recovery = SettingsRecovery {
+        reason,
+        corrupt_copy,
+        restored_from: None,
+    };
+    (recovery, Settings::default())
+}
+
+/// Reset out-of-range values to their defaults so a hand-edited file can't break the monitor
+fn sanitized(mut settings: Settings) -> Settings {
+    for error in sanitize(&mut settings) {
+        eprintln!("Settings: {}, using the default", error);
+    }
+    settings
+}
+
+fn log_parse_notes(settings: &Settings, file_version: u32, dropped_fields: &[String]) {
+    if file_version < settings.schema_version {
+        eprintln!(
+            "Settings: migrated from schema v{} to v{}",
+            file_version, settings.schema_version
+        );
+    }
+    if !dropped_fields.is_empty() {
+        eprintln!(
+            "Settings: couldn't read {}, using defaults for them",
+            dropped_fields.join(", ")
+        );
+    }
+    if settings.is_from_newer_version() {
+        eprintln!(
+            "Settings: written by a newer SymbolSweep (schema v{}), changes won't be saved",
+            settings.schema_version
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fresh settings directory under the system temp dir
+    fn temp_dir(test_name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("symbolsweep-settings-{}-{}", test_name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Pretend the newest backup was taken long enough ago for another
+    fn age_backups(dir: &Path) {
+        for backup in backup_paths(dir) {
+            let old = SystemTime::now() - BACKUP_INTERVAL - Duration::from_secs(1);
+            File::options().write(true).open(&backup).unwrap().set_modified(old).unwrap();
+        }
+    }
+
+    fn with_timestamp(timestamp: u64) -> Settings {
+        Settings {
+            last_clean_timestamp: timestamp,
+            ..Settings::default()
+        }
+    }
+
+    #[test]
+    fn test_save_then_load() {
+        let dir = temp_dir("round-trip");
+        save_to(&dir, &with_timestamp(42)).unwrap();
+
+        let loaded = load_from(&dir);
+        assert_eq!(loaded.settings.last_clean_timestamp, 42);
+        assert!(loaded.recovery.is_none());
+        assert!(!has_temp_files(&dir));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    fn has_temp_files(dir: &Path) -> bool {
+        fs::read_dir(dir)
+            .unwrap()
+            .any(|entry| entry.unwrap().file_name().to_string_lossy().ends_with(".tmp"))
+    }
+
+    #[test]
+    fn test_concurrent_saves_each_land_whole() {
+        let dir = temp_dir("concurrent");
+        let writers: Vec<_> = (1..=8)
+            .map(|timestamp| {
+                let dir = dir.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..10 {
+                        save_to(&dir, &with_timestamp(timestamp)).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let loaded = load_from(&dir);
+        assert!(loaded.recovery.is_none());
+        assert!((1..=8).contains(&loaded.settings.last_clean_timestamp));
+        assert!(!has_temp_files(&dir));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_missing_file_is_not_corruption() {
+        let dir = temp_dir("missing");
+        let loaded = load_from(&dir);
+        assert!(loaded.recovery.is_none());
+        assert_eq!(loaded.settings.auto_clean_threshold, Settings::default().auto_clean_threshold);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_backups_rotate_hourly_and_keep_last_few() {
+        let dir = temp_dir("rotate");
+        save_to(&dir, &with_timestamp(1)).unwrap();
+        // Nothing to back up on the first save
+        assert!(backup_paths(&dir).is_empty());
+
+        save_to(&dir, &with_timestamp(2)).unwrap();
+        assert_eq!(backup_paths(&dir).len(), 1);
+        // Within the hour, saves don't take another backup
+        save_to(&dir, &with_timestamp(3)).unwrap();
+        assert_eq!(backup_paths(&dir).len(), 1);
+
+        for timestamp in 4..12 {
+            age_backups(&dir);
+            save_to(&dir, &with_timestamp(timestamp)).unwrap();
+        }
+        let backups = backup_paths(&dir);
+        assert_eq!(backups.len(), MAX_BACKUPS);
+        let newest = parse_settings(&fs::read_to_string(&backups[0]).unwrap()).unwrap();
+        assert_eq!(newest.settings.last_clean_timestamp, 10);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_truncated_file_restores_newest_good_backup() {
+        let dir = temp_dir("truncated");
+        save_to(&dir, &with_timestamp(1)).unwrap();
+        save_to(&dir, &with_timestamp(2)).unwrap();
+
+        // A crash mid-write under the old fs::write
+        let path = dir.join(SETTINGS_FILE);
+        let full = fs::read_to_string(&path).unwrap();
+        fs::write(&path, &full[..full.len() / 2]).unwrap();
+        // A corrupt newer backup is skipped
+        age_backups(&dir);
+        fs::rename(backup_path(&dir, 1), backup_path(&dir, 2)).unwrap();
+        fs::write(backup_path(&dir, 1), "{").unwrap();
+
+        let loaded = load_from(&dir);
+        assert_eq!(loaded.settings.last_clean_timestamp, 1);
+        let recovery = loaded.recovery.unwrap();
+        assert_eq!(recovery.restored_from, Some(backup_path(&dir, 2)));
+        let corrupt = recovery.corrupt_copy.unwrap();
+        assert_eq!(fs::read_to_string(corrupt).unwrap(), full[..full.len() / 2]);
+
+        // The restored copy is back in place for the next launch
+        let again = load_from(&dir);
+        assert!(again.recovery.is_none());
+        assert_eq!(again.settings.last_clean_timestamp, 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_restored_backup_is_sanitized() {
+        let dir = temp_dir("sanitize-backup");
+        let bad = Settings {
+            monitor_interval_secs: 0,
+            ..with_timestamp(1)
+        };
+        save_to(&dir, &bad).unwrap();
+        age_backups(&dir);
+        save_to(&dir, &bad).unwrap();
+        fs::write(dir.join(SETTINGS_FILE), "{").unwrap();
+
+        let loaded = load_from(&dir);
+        assert!(loaded.recovery.unwrap().restored_from.is_some());
+        assert_eq!(loaded.settings.last_clean_timestamp, 1);
+        assert_eq!(loaded.settings.monitor_interval_secs, Settings::default().monitor_interval_secs);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_corrupt_without_backups_resets_to_defaults() {
+        let dir = temp_dir("no-backups");
+        fs::write(dir.join(SETTINGS_FILE), "").unwrap();
+
+        let loaded = load_from(&dir);
+        let recovery = loaded.recovery.unwrap();
+        assert_eq!(recovery.restored_from, None);
+        assert!(recovery.message().contains("reset to defaults"));
+        assert!(!dir.join(SETTINGS_FILE).exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
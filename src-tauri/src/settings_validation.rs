@@ -0,0 +1,370 @@
+use chrono_tz::Tz;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::cache_monitor::CRITICAL_THRESHOLD;
+use crate::schedule::{next_clean_after, ScheduleError};
+use crate::scheduler::Settings;
+
+const MB: u64 = 1024 * 1024;
+const GB: u64 = 1024 * MB;
+const MINUTE: u64 = 60;
+const HOUR: u64 = 60 * MINUTE;
+const DAY: u64 = 24 * HOUR;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// A problem with one settings field
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind")]
+pub enum FieldError {
+    /// Number outside its allowed range
+    OutOfRange { field: String, min: f64, max: f64 },
+    /// Must be no more than another field
+    NotAtMost { field: String, other: String },
+    /// Must be no more than a fixed limit that isn't a setting of its own
+    AboveLimit { field: String, limit: f64 },
+    /// Text or structure that can't be used (time zone, schedule, empty name)
+    Invalid { field: String, reason: String },
+    /// Only makes sense with another setting turned on
+    Requires { field: String, other: String },
+    /// Set by the managed policy and can't be changed
+    Locked { field: String },
+}
+
+impl FieldError {
+    /// Settings field the error is about
+    pub fn field(&self) -> &str {
+        match self {
+            FieldError::OutOfRange { field, .. }
+            | FieldError::NotAtMost { field, .. }
+            | FieldError::AboveLimit { field, .. }
+            | FieldError::Invalid { field, .. }
+            | FieldError::Requires { field, .. }
+            | FieldError::Locked { field } => field,
+        }
+    }
+
+    /// Human-readable description
+    pub fn message(&self) -> String {
+        match self {
+            FieldError::OutOfRange { field, min, max } => format!("{} must be between {} and {}", field, min, max),
+            FieldError::NotAtMost { field, other } => format!("{} must not be more than {}", field, other),
+            FieldError::AboveLimit { field, limit } => format!("{} must not be more than {}", field, limit),
+            FieldError::Invalid { field, reason } => format!("{}: {}", field, reason),
+            FieldError::Requires { field, other } => format!("{} requires {}", field, other),
+            FieldError::Locked { field } => format!("{} is managed by your organization", field),
+        }
+    }
+}
+
+impl std::fmt::Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+/// Why a settings update was rejected, in a shape the frontend can act on
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind")]
+pub enum SettingsError {
+    /// One or more fields failed validation
+    Invalid { errors: Vec<FieldError> },
+    /// settings.json belongs to a newer version and won't be overwritten
+    ReadOnly { message: String },
+    /// Writing the file failed
+    SaveFailed { message: String },
+    /// An edited settings.json or an imported file couldn't be read
+    Unreadable { message: String },
+    /// No saved or built-in profile has that name
+    UnknownProfile { name: String },
+}
+
+impl SettingsError {
+    /// Classify a failed `Settings::save`
+    pub fn saving(settings: &Settings, message: String) -> Self {
+        if settings.is_from_newer_version() {
+            SettingsError::ReadOnly { message }
+        } else {
+            SettingsError::SaveFailed { message }
+        }
+    }
+}
+
+impl std::fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SettingsError::Invalid { errors } => {
+                let messages: Vec<String> = errors.iter().map(FieldError::message).collect();
+                write!(f, "Invalid settings: {}", messages.join("; "))
+            }
+            SettingsError::ReadOnly { message }
+            | SettingsError::SaveFailed { message }
+            | SettingsError::Unreadable { message } => write!(f, "{}", message),
+            SettingsError::UnknownProfile { name } => write!(f, "No profile named \"{}\"", name),
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+// ============================================================================
+// Validation
+// ============================================================================
+
+/// Check every field, returning all problems found
+pub fn validate(settings: &Settings) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+    let mut range = |field: &str, value: u64, min: u64, max: u64| {
+        if !(min..=max).contains(&value) {
+            errors.push(FieldError::OutOfRange {
+                field: field.to_string(),
+                min: min as f64,
+                max: max as f64,
+            });
+        }
+    };
+
+    // 0 would turn the monitor into a busy loop
+    range("monitor_interval_secs", settings.monitor_interval_secs, 5, HOUR);
+    range("min_monitor_interval_secs", settings.min_monitor_interval_secs, 5, HOUR);
+    range("max_monitor_interval_secs", settings.max_monitor_interval_secs, 5, DAY);
+    // A tiny threshold would clean on every tick
+    range("auto_clean_threshold", settings.auto_clean_threshold, 100 * MB, 1024 * GB);
+    // Debug mode offers a 2 minute interval, and it may outlive debug mode
+    range("auto_clean_interval_secs", settings.auto_clean_interval_secs, MINUTE, 30 * DAY);
+    range("runaway_cpu_duration_secs", settings.runaway_cpu_duration_secs, MINUTE, DAY);
+    range("runaway_growth_bytes", settings.runaway_growth_bytes, 10 * MB, 1024 * GB);
+    range("runaway_growth_window_secs", settings.runaway_growth_window_secs, MINUTE, DAY);
+    range("auto_clean_max_failures", settings.auto_clean_max_failures as u64, 1, 100);
+    range("busy_max_defer_secs", settings.busy_max_defer_secs, 0, 7 * DAY);
+    range("require_idle_secs", settings.require_idle_secs, 10, DAY);
+    range("wake_grace_secs", settings.wake_grace_secs, 0, HOUR);
+    range("notification_snooze_secs", settings.notification_snooze_secs, 5 * MINUTE, 7 * DAY);
+    // Reminders are off at 0, otherwise no more often than every 5 minutes
+    for (field, value) in [
+        ("warning_reminder_secs", settings.warning_reminder_secs),
+        ("critical_reminder_secs", settings.critical_reminder_secs),
+    ] {
+        if value != 0 {
+            range(field, value, 5 * MINUTE, 30 * DAY);
+        }
+    }
+
+    // Multi-core processes can report well over 100%
+    if !(1.0..=1000.0).contains(&settings.runaway_cpu_percent) {
+        errors.push(FieldError::OutOfRange {
+            field: "runaway_cpu_percent".to_string(),
+            min: 1.0,
+            max: 1000.0,
+        });
+    }
+
+    // Relationships
+    if settings.min_monitor_interval_secs > settings.max_monitor_interval_secs {
+        errors.push(FieldError::NotAtMost {
+            field: "min_monitor_interval_secs".to_string(),
+            other: "max_monitor_interval_secs".to_string(),
+        });
+    }
+    if settings.auto_clean_threshold > CRITICAL_THRESHOLD {
+        // The cache would go critical before auto-clean ever ran
+        errors.push(FieldError::AboveLimit {
+            field: "auto_clean_threshold".to_string(),
+            limit: CRITICAL_THRESHOLD as f64,
+        });
+    }
+
+    // Combinations that can't do anything
+    if settings.runaway_auto_remediate && !settings.runaway_detection_enabled {
+        errors.push(FieldError::Requires {
+            field: "runaway_auto_remediate".to_string(),
+            other: "runaway_detection_enabled".to_string(),
+        });
+    }
+    if settings.defer_while_busy && settings.busy_process_names.iter().any(|n| n.trim().is_empty()) {
+        errors.push(FieldError::Invalid {
+            field: "busy_process_names".to_string(),
+            reason: "process names can't be empty".to_string(),
+        });
+    }
+    if settings.quiet_hours_enabled && settings.quiet_hours_start == settings.quiet_hours_end {
+        errors.push(FieldError::Invalid {
+            field: "quiet_hours_end".to_string(),
+            reason: "quiet hours must start and end at different times".to_string(),
+        });
+    }
+
+    // Schedule and time zone
+    if let Some(zone) = &settings.schedule_time_zone {
+        if zone.parse::<Tz>().is_err() {
+            errors.push(FieldError::Invalid {
+                field: "schedule_time_zone".to_string(),
+                reason: format!("unknown time zone \"{}\"", zone),
+            });
+        }
+    }
+    match next_clean_after(settings, 0) {
+        Ok(_) | Err(ScheduleError::InvalidTimeZone(_)) => {}
+        Err(e) => errors.push(FieldError::Invalid {
+            field: "auto_clean_schedule".to_string(),
+            reason: e.to_string(),
+        }),
+    }
+
+    errors
+}
+
+/// Reset invalid fields to their defaults, returning what was wrong
+/// Used when loading, where rejecting the whole file would lose everything else
+pub fn sanitize(settings: &mut Settings) -> Vec<FieldError> {
+    let errors = validate(settings);
+    if errors.is_empty() {
+        return errors;
+    }
+
+    let (Ok(Value::Object(mut fields)), Ok(Value::Object(defaults))) =
+        (serde_json::to_value(&*settings), serde_json::to_value(Settings::default()))
+    else {
+        return errors;
+    };
+    for error in &errors {
+        if let Some(default) = defaults.get(error.field()) {
+            fields.insert(error.field().to_string(), default.clone());
+        }
+    }
+
+    match serde_json::from_value::<Settings>(Value::Object(fields)) {
+        // A reset field can still clash with another (e.g. min vs a custom max)
+        Ok(fixed) if validate(&fixed).is_empty() => *settings = fixed,
+        _ => {
+            let schema_version = settings.schema_version;
+            *settings = Settings {
+                schema_version,
+                ..Settings::default()
+            };
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::{CleanSchedule, TimeOfDay};
+
+    fn fields(errors: &[FieldError]) -> Vec<&str> {
+        errors.iter().map(FieldError::field).collect()
+    }
+
+    #[test]
+    fn test_defaults_are_valid() {
+        assert_eq!(validate(&Settings::default()), Vec::new());
+    }
+
+    #[test]
+    fn test_ranges() {
+        let settings = Settings {
+            monitor_interval_secs: 0,
+            auto_clean_threshold: 0,
+            runaway_cpu_percent: 0.0,
+            warning_reminder_secs: 0,
+            critical_reminder_secs: 60,
+            ..Settings::default()
+        };
+        let errors = validate(&settings);
+        assert_eq!(
+            fields(&errors),
+            vec!["monitor_interval_secs", "auto_clean_threshold", "critical_reminder_secs", "runaway_cpu_percent"]
+        );
+        assert_eq!(
+            errors[0],
+            FieldError::OutOfRange {
+                field: "monitor_interval_secs".to_string(),
+                min: 5.0,
+                max: 3600.0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_relationships_and_combinations() {
+        let settings = Settings {
+            min_monitor_interval_secs: 600,
+            max_monitor_interval_secs: 60,
+            auto_clean_threshold: 20 * GB,
+            runaway_detection_enabled: false,
+            runaway_auto_remediate: true,
+            quiet_hours_enabled: true,
+            quiet_hours_start: TimeOfDay { hour: 9, minute: 0 },
+            quiet_hours_end: TimeOfDay { hour: 9, minute: 0 },
+            ..Settings::default()
+        };
+        assert_eq!(
+            fields(&validate(&settings)),
+            vec![
+                "min_monitor_interval_secs",
+                "auto_clean_threshold",
+                "runaway_auto_remediate",
+                "quiet_hours_end"
+            ]
+        );
+        assert!(validate(&settings).contains(&FieldError::AboveLimit {
+            field: "auto_clean_threshold".to_string(),
+            limit: CRITICAL_THRESHOLD as f64,
+        }));
+    }
+
+    #[test]
+    fn test_schedule_and_time_zone() {
+        let settings = Settings {
+            schedule_time_zone: Some("Mars/Olympus_Mons".to_string()),
+            auto_clean_schedule: CleanSchedule::Cron {
+                expression: "every tuesday".to_string(),
+            },
+            ..Settings::default()
+        };
+        assert_eq!(
+            fields(&validate(&settings)),
+            vec!["schedule_time_zone", "auto_clean_schedule"]
+        );
+    }
+
+    #[test]
+    fn test_sanitize_resets_only_bad_fields() {
+        let mut settings = Settings {
+            monitor_interval_secs: 0,
+            auto_clean_on_threshold: true,
+            show_notifications: false,
+            ..Settings::default()
+        };
+        let errors = sanitize(&mut settings);
+        assert_eq!(fields(&errors), vec!["monitor_interval_secs"]);
+        assert_eq!(settings.monitor_interval_secs, Settings::default().monitor_interval_secs);
+        assert!(settings.auto_clean_on_threshold);
+        assert!(!settings.show_notifications);
+    }
+
+    #[test]
+    fn test_errors_serialize_for_the_frontend() {
+        let error = SettingsError::Invalid {
+            errors: vec![FieldError::Requires {
+                field: "runaway_auto_remediate".to_string(),
+                other: "runaway_detection_enabled".to_string(),
+            }],
+        };
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "kind": "Invalid",
+                "errors": [{
+                    "kind": "Requires",
+                    "field": "runaway_auto_remediate",
+                    "other": "runaway_detection_enabled",
+                }],
+            })
+        );
+    }
+}
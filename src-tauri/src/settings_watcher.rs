@@ -0,0 +1,352 @@
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::scheduler::Settings;
+use crate::settings_policy::Policy;
+use crate::settings_schema::{parse_settings, settings_fields};
+use crate::settings_store::{is_own_write, save_to, SETTINGS_FILE};
+use crate::settings_validation::{validate, FieldError, SettingsError};
+
+/// How often settings.json is checked for edits made outside the app
+pub const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Result of checking settings.json for outside edits
+#[derive(Debug, PartialEq)]
+pub enum ReloadOutcome {
+    /// The file hasn't changed, or says nothing the app doesn't already have
+    Unchanged,
+    /// Fields edited in the file were merged into the settings
+    Applied { fields: Vec<String> },
+    /// The edit couldn't be read or failed validation; the settings were left alone
+    Rejected(SettingsError),
+}
+
+/// Picks up edits to settings.json made by hand or by provisioning scripts
+///
+/// Conflicts are resolved per field: a field written in the file that differs
+/// from the running settings takes the file's value, and every field the file
+/// leaves out or agrees on keeps the app's value. Files the app wrote itself are
+/// recognized by their content and skipped. Monitor-owned state (last clean, backoff,
+/// alert state, layout version) always keeps the app's value, and fields locked
+/// by the managed policy keep the policy's.
+pub struct SettingsWatcher {
+    dir: PathBuf,
+    policy: Policy,
+    /// File contents at the last check, so an untouched file isn't re-parsed
+    last_content: Option<String>,
+}
+
+impl SettingsWatcher {
+    /// Watch settings.json in `dir`, treating its current contents as already loaded
+    pub fn new(dir: PathBuf, policy: Policy) -> Self {
+        let last_content = fs::read_to_string(dir.join(SETTINGS_FILE)).ok();
+        Self {
+            dir,
+            policy,
+            last_content,
+        }
+    }
+
+    /// Read settings.json, returning its contents if someone other than the app changed it
+    /// Does file I/O only, so it runs without holding the settings lock
+    pub fn poll(&mut self) -> Option<String> {
+        let path = self.dir.join(SETTINGS_FILE);
+        // Missing means deleted or mid-replace; the next save recreates it
+        let content = fs::read_to_string(&path).ok()?;
+        if self.last_content.as_deref() == Some(content.as_str()) {
+            return None;
+        }
+        self.last_content = Some(content.clone());
+        // The app's own save, even one older than memory, isn't an outside edit
+        if is_own_write(&path, &content) {
+            return None;
+        }
+        Some(content)
+    }
+
+    /// Merge an edit returned by `poll` into `settings`
+    pub fn merge(&self, content: &str, settings: &mut Settings) -> ReloadOutcome {
+        match merge_edit(content, settings, &self.policy) {
+            Ok(Some((merged, fields))) => {
+                *settings = merged;
+                ReloadOutcome::Applied { fields }
+            }
+            Ok(None) => ReloadOutcome::Unchanged,
+            Err(e) => ReloadOutcome::Rejected(e),
+        }
+    }
+
+    /// Save merged `settings` over the edited `content` if they differ, so disk matches
+    /// memory: a partial file is filled in and monitor-owned state is restored
+    pub fn write_back(&mut self, content: &str, settings: &Settings) {
+        let Ok(written) = serde_json::to_string_pretty(settings) else { return };
+        if written == content {
+            return;
+        }
+        match save_to(&self.dir, settings) {
+            Ok(()) => self.last_content = Some(written),
+            Err(e) => eprintln!("Settings: couldn't save reloaded settings: {}", e),
+        }
+    }
+
+    /// Poll, merge and write back in one go
+    #[cfg(test)]
+    pub fn check(&mut self, settings: &mut Settings) -> ReloadOutcome {
+        let Some(content) = self.poll() else {
+            return ReloadOutcome::Unchanged;
+        };
+        let outcome = self.merge(&content, settings);
+        if matches!(outcome, ReloadOutcome::Applied { .. }) {
+            self.write_back(&content, settings);
+        }
+        outcome
+    }
+}
+
+// ============================================================================
+// Merging
+// ============================================================================
+
+/// Apply the fields written in `content` on top of `current`
+/// Returns None when the file doesn't change anything
+fn merge_edit(content: &str, current: &Settings, policy: &Policy) -> Result<Option<(Settings, Vec<String>)>, SettingsError> {
+    let parsed = parse_settings(content).map_err(|e| SettingsError::Unreadable { message: e.to_string() })?;
+    if parsed.settings.is_from_newer_version() {
+        return Err(SettingsError::Unreadable {
+            message: format!(
+                "Settings file was written by a newer SymbolSweep (schema v{})",
+                parsed.settings.schema_version
+            ),
+        });
+    }
+    // A field the file got wrong is an error here, not something to quietly default
+    if !parsed.dropped_fields.is_empty() {
+        let errors = parsed
+            .dropped_fields
+            .iter()
+            .map(|field| FieldError::Invalid {
+                field: field.clone(),
+                reason: "couldn't be read".to_string(),
+            })
+            .collect();
+        return Err(SettingsError::Invalid { errors });
+    }
+
+    // Only the fields the file actually mentions count as edits
+    let written: Map<String, Value> = serde_json::from_str(content).unwrap_or_default();
+    let theirs = settings_fields(&parsed.settings);
+    let ours = settings_fields(current);
+
+    let mut fields = ours.clone();
+    for key in written.keys() {
+        if let Some(value) = theirs.get(key) {
+            fields.insert(key.clone(), value.clone());
+        }
+    }
+    let mut merged: Settings = serde_json::from_value(Value::Object(fields))
+        .map_err(|e| SettingsError::Unreadable { message: e.to_string() })?;
+    merged.keep_runtime_state(current);
+    // Stale copies of locked fields are expected in the file; the policy wins
+    policy.apply(&mut merged);
+
+    let errors = validate(&merged);
+    if !errors.is_empty() {
+        return Err(SettingsError::Invalid { errors });
+    }
+
+    let merged_fields = settings_fields(&merged);
+    let changed: Vec<String> = merged_fields
+        .iter()
+        .filter(|(key, value)| ours.get(*key) != Some(*value))
+        .map(|(key, _)| key.clone())
+        .collect();
+    if changed.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some((merged, changed)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings_store::load_from;
+    use std::path::Path;
+
+    /// Fresh settings directory under the system temp dir, holding `settings`
+    fn temp_dir(test_name: &str, settings: &Settings) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("symbolsweep-watcher-{}-{}", test_name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        save_to(&dir, settings).unwrap();
+        dir
+    }
+
+    fn write(dir: &Path, content: &str) {
+        fs::write(dir.join(SETTINGS_FILE), content).unwrap();
+    }
+
+    #[test]
+    fn test_own_saves_are_not_reloads() {
+        let mut settings = Settings::default();
+        let dir = temp_dir("own-save", &settings);
+        let mut watcher = SettingsWatcher::new(dir.clone(), Policy::default());
+        assert_eq!(watcher.check(&mut settings), ReloadOutcome::Unchanged);
+
+        // An in-app edit saves the whole file, which then matches memory
+        settings.monitor_interval_secs = 300;
+        save_to(&dir, &settings).unwrap();
+        assert_eq!(watcher.check(&mut settings), ReloadOutcome::Unchanged);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_stale_monitor_save_does_not_revert_an_in_app_change() {
+        let mut settings = Settings::default();
+        let dir = temp_dir("stale-snapshot", &settings);
+        let mut watcher = SettingsWatcher::new(dir.clone(), Policy::default());
+
+        // The monitor takes a snapshot, then the user changes a setting and it's saved
+        let snapshot = settings.clone();
+        settings.monitor_interval_secs = 300;
+        save_to(&dir, &settings).unwrap();
+        // The snapshot lands on disk after the in-app save
+        save_to(&dir, &snapshot).unwrap();
+
+        assert_eq!(watcher.check(&mut settings), ReloadOutcome::Unchanged);
+        assert_eq!(settings.monitor_interval_secs, 300);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_outside_edit_is_applied() {
+        let mut settings = Settings::default();
+        let dir = temp_dir("edit", &settings);
+        let mut watcher = SettingsWatcher::new(dir.clone(), Policy::default());
+
+        let mut edited = settings.clone();
+        edited.show_notifications = false;
+        edited.monitor_interval_secs = 120;
+        write(&dir, &serde_json::to_string_pretty(&edited).unwrap());
+
+        assert_eq!(
+            watcher.check(&mut settings),
+            ReloadOutcome::Applied {
+                fields: vec!["monitor_interval_secs".to_string(), "show_notifications".to_string()],
+            }
+        );
+        assert!(!settings.show_notifications);
+        assert_eq!(settings.monitor_interval_secs, 120);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_partial_file_merges_and_is_filled_in() {
+        let mut settings = Settings {
+            auto_clean_on_threshold: true,
+            ..Settings::default()
+        };
+        let dir = temp_dir("partial", &settings);
+        let mut watcher = SettingsWatcher::new(dir.clone(), Policy::default());
+
+        write(&dir, r#"{ "monitor_interval_secs": 30 }"#);
+        assert_eq!(
+            watcher.check(&mut settings),
+            ReloadOutcome::Applied {
+                fields: vec!["monitor_interval_secs".to_string()],
+            }
+        );
+        // Fields the file left out keep the app's value
+        assert!(settings.auto_clean_on_threshold);
+
+        // The file is rewritten in full, and that rewrite isn't seen as another edit
+        let on_disk = load_from(&dir).settings;
+        assert!(on_disk.auto_clean_on_threshold);
+        assert_eq!(on_disk.monitor_interval_secs, 30);
+        assert_eq!(watcher.check(&mut settings), ReloadOutcome::Unchanged);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_monitor_state_keeps_the_apps_value() {
+        let mut settings = Settings::default();
+        let dir = temp_dir("runtime", &settings);
+        let mut watcher = SettingsWatcher::new(dir.clone(), Policy::default());
+        // The monitor records a clean after the script read the file
+        settings.record_clean_at(1_700_000_000);
+
+        let stale = Settings {
+            require_ac_power: true,
+            ..Settings::default()
+        };
+        write(&dir, &serde_json::to_string_pretty(&stale).unwrap());
+
+        assert_eq!(
+            watcher.check(&mut settings),
+            ReloadOutcome::Applied {
+                fields: vec!["require_ac_power".to_string()],
+            }
+        );
+        assert_eq!(settings.last_clean_timestamp, 1_700_000_000);
+        assert_eq!(load_from(&dir).settings.last_clean_timestamp, 1_700_000_000);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_locked_fields_keep_the_policy_value() {
+        let locked = serde_json::json!({ "show_notifications": true });
+        let Value::Object(locked) = locked else { unreachable!() };
+        let policy = Policy::from_locked(locked).unwrap();
+        let mut settings = Settings::default();
+        let dir = temp_dir("locked", &settings);
+        let mut watcher = SettingsWatcher::new(dir.clone(), policy);
+
+        write(&dir, r#"{ "show_notifications": false, "require_idle": true }"#);
+        assert_eq!(
+            watcher.check(&mut settings),
+            ReloadOutcome::Applied {
+                fields: vec!["require_idle".to_string()],
+            }
+        );
+        assert!(settings.show_notifications);
+        assert!(load_from(&dir).settings.show_notifications);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_bad_edits_are_rejected_until_fixed() {
+        let mut settings = Settings::default();
+        let dir = temp_dir("rejected", &settings);
+        let mut watcher = SettingsWatcher::new(dir.clone(), Policy::default());
+
+        // Half-written by an editor that doesn't save atomically
+        write(&dir, r#"{ "monitor_interval_secs": "#);
+        assert!(matches!(
+            watcher.check(&mut settings),
+            ReloadOutcome::Rejected(SettingsError::Unreadable { .. })
+        ));
+        // Reported once, not on every poll
+        assert_eq!(watcher.check(&mut settings), ReloadOutcome::Unchanged);
+
+        write(&dir, r#"{ "monitor_interval_secs": 0 }"#);
+        let ReloadOutcome::Rejected(SettingsError::Invalid { errors }) = watcher.check(&mut settings) else {
+            panic!("expected a validation error");
+        };
+        assert_eq!(errors[0].field(), "monitor_interval_secs");
+        write(&dir, r#"{ "missed_clean_policy": "Sometimes" }"#);
+        assert!(matches!(
+            watcher.check(&mut settings),
+            ReloadOutcome::Rejected(SettingsError::Invalid { .. })
+        ));
+        assert_eq!(settings.monitor_interval_secs, Settings::default().monitor_interval_secs);
+
+        write(&dir, r#"{ "monitor_interval_secs": 45 }"#);
+        assert!(matches!(watcher.check(&mut settings), ReloadOutcome::Applied { .. }));
+        assert_eq!(settings.monitor_interval_secs, 45);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
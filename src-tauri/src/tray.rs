@@ -1,13 +1,18 @@
 use std::sync::atomic::{AtomicU64, Ordering};
 use tauri::{
     image::Image,
-    menu::{Menu, MenuItem},
+    menu::{Menu, MenuItem, Submenu},
     tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent},
     AppHandle, Emitter, Manager, Runtime,
 };
 use tauri_plugin_positioner::{Position, WindowExt};
 
-use crate::cache_monitor::{CacheState, CacheStatus};
+use crate::cache_monitor::{format_size, CacheState, CacheStatus, CRITICAL_THRESHOLD, WARNING_THRESHOLD};
+use crate::i18n::{format_local_time, format_relative, tr, tr_count, tr_with, Msg};
+use crate::scheduler::{format_duration, format_last_clean, Settings};
+use crate::settings_profiles::list_profiles;
+use crate::notification_policy::Notification;
+use crate::notifier::{NotificationAction, Notifier, NotifierBackend, NotifyError};
 
 /// Activate the macOS app so it receives first-click events
 #[cfg(target_os = "macos")]
@@ -26,6 +31,16 @@ fn activate_app() {
     // No-op on other platforms
 }
 
+/// Snooze choices in the tray menu: (menu id, seconds)
+const SNOOZE_OPTIONS: [(&str, u64); 3] = [
+    ("snooze-1h", 60 * 60),
+    ("snooze-4h", 4 * 60 * 60),
+    ("snooze-1d", 24 * 60 * 60),
+];
+
+/// Menu id prefix for the profile items ("profile:<name>")
+const PROFILE_ID_PREFIX: &str = "profile:";
+
 /// Tray icon identifier
 pub const TRAY_ID: &str = "symbolsweep-tray";
 
@@ -51,14 +66,8 @@ pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> Result<TrayIcon<R>, Box<dy
         return Ok(existing);
     }
 
-    // Create menu items
-    let show_item = MenuItem::with_id(app, "show", "Show SymbolSweep", true, None::<&str>)?;
-    let clean_item = MenuItem::with_id(app, "clean", "Clean Cache Now", true, None::<&str>)?;
-    let separator = MenuItem::with_id(app, "sep", "---", false, None::<&str>)?;
-    let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-
     // Create menu
-    let menu = Menu::with_items(app, &[&show_item, &clean_item, &separator, &quit_item])?;
+    let menu = build_menu(app, false)?;
 
     // Create a minimal 1x1 transparent icon (required by Tauri, but we'll hide it with title)
     let icon = create_minimal_icon()?;
@@ -69,8 +78,8 @@ pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> Result<TrayIcon<R>, Box<dy
         .icon(icon)
         .icon_as_template(true)
         .menu(&menu)
-        .title("0 B") // Initial title - will be updated
-        .tooltip("SymbolSweep - Cache Monitor")
+        .title(format_size(0)) // Initial title - will be updated
+        .tooltip(tr(Msg::TrayTooltipIdle))
         .show_menu_on_left_click(false)
         .on_tray_icon_event(|tray, event| {
             if let TrayIconEvent::Click {
@@ -101,30 +110,92 @@ pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> Result<TrayIcon<R>, Box<dy
             }
         })
         .on_menu_event(|app, event| match event.id.as_ref() {
-            "show" => {
-                if let Some(window) = app.get_webview_window("main") {
-                    LAST_SHOW_TIME.store(current_time_ms(), Ordering::SeqCst);
-                    activate_app();
-                    position_window_near_tray(&window);
-                    std::thread::sleep(std::time::Duration::from_millis(10));
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                }
-            }
+            "show" => show_main_window(app),
             "clean" => {
                 // Emit event to trigger clean from frontend
                 let _ = app.emit("clean-requested", ());
             }
+            "approve" => {
+                // Run off the main thread - this may show an admin password prompt
+                let app = app.clone();
+                std::thread::spawn(move || crate::run_approved_clean(&app));
+            }
+            "snooze-resume" => {
+                let _ = crate::snooze_alerts(app, 0);
+            }
             "quit" => {
                 app.exit(0);
             }
-            _ => {}
+            id => {
+                if let Some((_, secs)) = SNOOZE_OPTIONS.iter().find(|(option, _)| *option == id) {
+                    let _ = crate::snooze_alerts(app, *secs);
+                } else if let Some(name) = id.strip_prefix(PROFILE_ID_PREFIX) {
+                    if let Err(e) = crate::switch_to_profile(app, name) {
+                        eprintln!("Tray: couldn't switch to profile \"{}\": {}", name, e);
+                    }
+                }
+            }
         })
         .build(app)?;
 
     Ok(tray)
 }
 
+/// Show the main window near the tray
+fn show_main_window<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window("main") {
+        LAST_SHOW_TIME.store(current_time_ms(), Ordering::SeqCst);
+        activate_app();
+        position_window_near_tray(&window);
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// Build the tray menu
+/// `approval_pending` adds an item for a background clean that needs an admin password
+fn build_menu<R: Runtime>(app: &AppHandle<R>, approval_pending: bool) -> tauri::Result<Menu<R>> {
+    let show_item = MenuItem::with_id(app, "show", tr(Msg::TrayShow), true, None::<&str>)?;
+    let clean_item = MenuItem::with_id(app, "clean", tr(Msg::TrayCleanNow), true, None::<&str>)?;
+    let separator = MenuItem::with_id(app, "sep", "---", false, None::<&str>)?;
+    let quit_item = MenuItem::with_id(app, "quit", tr(Msg::TrayQuit), true, None::<&str>)?;
+
+    let menu = Menu::with_items(app, &[&show_item, &clean_item])?;
+    if approval_pending {
+        let approve_item = MenuItem::with_id(app, "approve", tr(Msg::TrayApprove), true, None::<&str>)?;
+        menu.append(&approve_item)?;
+    }
+    let snooze_menu = Submenu::with_id(app, "snooze", tr(Msg::TraySnoozeMenu), true)?;
+    for (id, secs) in SNOOZE_OPTIONS {
+        let label = tr_with(Msg::TraySnoozeFor, &[("duration", &format_duration(secs))]);
+        snooze_menu.append(&MenuItem::with_id(app, id, label, true, None::<&str>)?)?;
+    }
+    snooze_menu.append(&MenuItem::with_id(app, "snooze-resume", tr(Msg::TrayResumeAlerts), true, None::<&str>)?)?;
+    let profiles_menu = Submenu::with_id(app, "profiles", tr(Msg::TraySwitchProfile), true)?;
+    for profile in list_profiles(&Settings::dir()) {
+        let id = format!("{}{}", PROFILE_ID_PREFIX, profile.name);
+        profiles_menu.append(&MenuItem::with_id(app, id, &profile.name, true, None::<&str>)?)?;
+    }
+    menu.append_items(&[&snooze_menu, &profiles_menu, &separator, &quit_item])?;
+
+    Ok(menu)
+}
+
+/// Show or hide the "needs your approval" item in the tray menu
+pub fn set_approval_pending<R: Runtime>(app: &AppHandle<R>, pending: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        tray.set_menu(Some(build_menu(app, pending)?))?;
+    }
+    Ok(())
+}
+
+/// Rebuild the tray menu, e.g. after a profile was saved or deleted
+pub fn refresh_menu<R: Runtime>(app: &AppHandle<R>) -> Result<(), Box<dyn std::error::Error>> {
+    let pending = app.state::<crate::AppState>().pending_approval.load(Ordering::SeqCst);
+    set_approval_pending(app, pending)
+}
+
 /// Create a minimal transparent icon (macOS requires an icon, but we use title for display)
 fn create_minimal_icon() -> Result<Image<'static>, Box<dyn std::error::Error>> {
     // 16x16 transparent image using raw RGBA data
@@ -136,6 +207,17 @@ fn create_minimal_icon() -> Result<Image<'static>, Box<dyn std::error::Error>> {
     Ok(Image::new_owned(rgba_data, width, height))
 }
 
+/// Describe the next scheduled clean, e.g. "Next clean: in 3 hours (Wed Oct 18, 12:30)"
+fn describe_next_clean(timestamp: u64) -> String {
+    let now = chrono::Utc::now().timestamp() as u64;
+    // An overdue clean is about to run rather than "5 minutes ago"
+    let relative = if timestamp <= now { tr(Msg::Now) } else { format_relative(timestamp) };
+    tr_with(
+        Msg::TrayNextClean,
+        &[("relative", &relative), ("time", &format_local_time(timestamp))],
+    )
+}
+
 /// Format the tray title with status indicator
 /// Uses flat colored circles - renders cleanly on macOS
 fn format_tray_title(status: &CacheStatus) -> String {
@@ -159,16 +241,27 @@ pub fn update_tray_icon<R: Runtime>(
         tray.set_title(Some(&title))?;
 
         // Update tooltip with more details
-        let tooltip = format!(
-            "SymbolSweep\n{} - {} files\nStatus: {}",
-            status.size_display,
-            status.file_count,
-            match status.state {
-                CacheState::Normal => "Normal",
-                CacheState::Warning => "Warning (5GB+)",
-                CacheState::Critical => "Critical (10GB+)",
-            }
+        let state = match status.state {
+            CacheState::Normal => tr(Msg::StateNormal),
+            CacheState::Warning => tr_with(Msg::StateWarning, &[("size", &format_size(WARNING_THRESHOLD))]),
+            CacheState::Critical => tr_with(Msg::StateCritical, &[("size", &format_size(CRITICAL_THRESHOLD))]),
+        };
+        let tooltip = tr_with(
+            Msg::TrayTooltip,
+            &[
+                ("size", &status.size_display),
+                ("files", &tr_count(Msg::Files, status.file_count, &[])),
+                ("state", &state),
+            ],
         );
+        let mut lines = vec![tooltip];
+        if let Some(last) = status.auto_clean.last_clean {
+            lines.push(tr_with(Msg::TrayLastClean, &[("relative", &format_last_clean(last))]));
+        }
+        if let Some(next) = status.auto_clean.next_scheduled_clean {
+            lines.push(describe_next_clean(next));
+        }
+        let tooltip = lines.join("\n");
         tray.set_tooltip(Some(&tooltip))?;
     }
 
@@ -211,56 +304,75 @@ fn position_window_near_tray<R: Runtime>(window: &tauri::WebviewWindow<R>) {
     }
 }
 
-/// Send a macOS notification with multiple fallback methods
-/// 1. Tauri plugin (works in signed production builds)
-/// 2. terminal-notifier (works if installed via Homebrew)
-/// 3. osascript (may require Script Editor permissions)
-pub fn send_notification<R: Runtime>(app: &AppHandle<R>, title: &str, body: &str) {
-    use tauri_plugin_notification::NotificationExt;
-
-    // Try Tauri notification plugin first
-    let result = app.notification()
-        .builder()
-        .title(title)
-        .body(body)
-        .show();
-
-    match &result {
-        Ok(_) => {
-            // In release builds, trust that Tauri notification worked
-            #[cfg(not(debug_assertions))]
-            return;
-            // In debug builds, fall through to terminal-notifier since Tauri silently fails for unsigned apps
+/// Tauri notification plugin (works in signed production builds) - no buttons
+struct PluginBackend<R: Runtime> {
+    app: AppHandle<R>,
+}
+
+impl<R: Runtime> NotifierBackend for PluginBackend<R> {
+    fn name(&self) -> &'static str {
+        "notification plugin"
+    }
+
+    fn supports_actions(&self) -> bool {
+        false
+    }
+
+    fn show(&self, notification: &Notification) -> Result<Option<NotificationAction>, NotifyError> {
+        use tauri_plugin_notification::NotificationExt;
+
+        self.app
+            .notification()
+            .builder()
+            .title(&notification.title)
+            .body(&notification.body)
+            .show()
+            .map_err(|e| NotifyError::Failed(e.to_string()))?;
+
+        // The plugin silently fails for unsigned macOS apps, so debug builds there fall through to the
+        // command-line tools; elsewhere it works unsigned and falling through would show it twice
+        if cfg!(all(debug_assertions, target_os = "macos")) {
+            return Err(NotifyError::Failed("unsigned debug build".to_string()));
         }
-        Err(_) => {}
+        Ok(None)
     }
+}
 
-    #[cfg(target_os = "macos")]
-    {
-        // Fallback to terminal-notifier (works if installed)
-        let result = std::process::Command::new("terminal-notifier")
-            .arg("-title")
-            .arg(title)
-            .arg("-message")
-            .arg(body)
-            .arg("-sound")
-            .arg("default")
-            .output();
-
-        if matches!(&result, Ok(output) if output.status.success()) {
-            return;
+/// Send a notification through the best available backend
+/// 1. Tools that show buttons (alerter on macOS, notify-send on Linux) when there are actions
+/// 2. Tauri plugin (works in signed production builds)
+/// 3. terminal-notifier / osascript on macOS
+///
+/// Runs on its own thread since backends with buttons wait for a click; the
+/// clicked action is routed back into the app
+pub fn send_notification<R: Runtime>(app: &AppHandle<R>, notification: Notification) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let mut backends: Vec<Box<dyn NotifierBackend>> = vec![Box::new(PluginBackend { app: app.clone() })];
+        backends.extend(Notifier::platform_backends());
+
+        match Notifier::new(backends).show(&notification) {
+            Ok(Some(action)) => run_notification_action(&app, action),
+            Ok(None) => {}
+            Err(e) => eprintln!("Notification not shown: {}", e),
         }
+    });
+}
 
-        // Last resort: osascript
-        let script = format!(
-            r#"display notification "{}" with title "{}" sound name "Glass""#,
-            body.replace('"', r#"\""#),
-            title.replace('"', r#"\""#),
-        );
-        let _ = std::process::Command::new("osascript")
-            .arg("-e")
-            .arg(&script)
-            .output();
+/// Handle a notification button
+fn run_notification_action<R: Runtime>(app: &AppHandle<R>, action: NotificationAction) {
+    match action {
+        // Same path as the tray's "Clean Cache Now"
+        NotificationAction::CleanNow => {
+            let _ = app.emit("clean-requested", ());
+        }
+        // Called from the notification thread; AppKit window calls must run on the main thread
+        NotificationAction::OpenWindow => {
+            let handle = app.clone();
+            let _ = app.run_on_main_thread(move || show_main_window(&handle));
+        }
+        NotificationAction::Snooze { secs } => {
+            let _ = crate::snooze_alerts(app, secs);
+        }
     }
 }
-
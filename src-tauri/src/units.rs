@@ -0,0 +1,463 @@
+use serde::{de, Deserialize, Deserializer, Serialize};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::i18n::localize_decimal;
+
+const MINUTE: f64 = 60.0;
+const HOUR: f64 = 60.0 * MINUTE;
+const DAY: f64 = 24.0 * HOUR;
+const WEEK: f64 = 7.0 * DAY;
+
+const KIB: f64 = 1024.0;
+const MIB: f64 = 1024.0 * KIB;
+const GIB: f64 = 1024.0 * MIB;
+const TIB: f64 = 1024.0 * GIB;
+
+/// Units sizes are currently shown in (a `SizeUnits` as u8), set from settings
+static SIZE_UNITS: AtomicU8 = AtomicU8::new(SizeUnits::Iec as u8);
+/// Style durations are currently written in (a `DurationStyle` as u8), set from settings
+static DURATION_STYLE: AtomicU8 = AtomicU8::new(DurationStyle::Compound as u8);
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// How sizes are shown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SizeUnits {
+    /// Decimal: 1 GB = 1,000,000,000 bytes
+    Si,
+    /// Binary: 1 GiB = 1,073,741,824 bytes (the thresholds are whole GiB)
+    #[default]
+    Iec,
+    /// Decimal with Finder's precision: "12 bytes", "456 KB", "7.8 MB", "1.23 GB"
+    Finder,
+}
+
+impl SizeUnits {
+    fn base(self) -> f64 {
+        match self {
+            SizeUnits::Iec => 1024.0,
+            SizeUnits::Si | SizeUnits::Finder => 1000.0,
+        }
+    }
+
+    /// Labels for bytes, kilo, mega, giga and tera
+    fn labels(self) -> [&'static str; 5] {
+        match self {
+            SizeUnits::Si => ["B", "KB", "MB", "GB", "TB"],
+            SizeUnits::Iec => ["B", "KiB", "MiB", "GiB", "TiB"],
+            SizeUnits::Finder => ["bytes", "KB", "MB", "GB", "TB"],
+        }
+    }
+
+    /// Decimal places shown at each power
+    fn decimals(self, power: usize) -> usize {
+        match self {
+            SizeUnits::Si | SizeUnits::Iec => [0, 0, 0, 1, 1][power],
+            SizeUnits::Finder => [0, 0, 1, 2, 2][power],
+        }
+    }
+}
+
+/// How durations are written
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DurationStyle {
+    /// Largest whole unit: "1 day"
+    Single,
+    /// Two largest units: "1 day 23 hours"
+    #[default]
+    Compound,
+    /// Two largest units, abbreviated: "1d 23h"
+    Abbreviated,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// Nothing to parse
+    Empty,
+    /// A number was expected here
+    InvalidNumber(String),
+    /// Unit that isn't recognised
+    UnknownUnit(String),
+    /// A number in a compound duration without a unit ("1h30")
+    MissingUnit,
+    /// Larger than a u64 can hold
+    TooLarge,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "Value is empty"),
+            ParseError::InvalidNumber(s) => write!(f, "\"{}\" is not a number", s),
+            ParseError::UnknownUnit(s) => write!(f, "Unknown unit \"{}\"", s),
+            ParseError::MissingUnit => write!(f, "Every number in a duration needs a unit (e.g. 1h30m)"),
+            ParseError::TooLarge => write!(f, "Value is too large"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// ============================================================================
+// Parsing
+// ============================================================================
+
+/// Parse a duration into seconds: "90" (seconds), "90m", "6h", "1d12h", "1.5 hours", "2 days"
+/// Accepts everything `format_duration` produces in English, in any style.
+pub fn parse_duration(input: &str) -> Result<u64, ParseError> {
+    let input = input.trim().to_lowercase();
+    if input.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    if let Ok(secs) = input.parse::<u64>() {
+        return Ok(secs);
+    }
+
+    let mut rest = input.as_str();
+    let mut total = 0.0;
+    while !rest.is_empty() {
+        let (value, after_number) = take_number(rest)?;
+        let (unit, after_unit) = take_unit(after_number);
+        if unit.is_empty() {
+            return Err(ParseError::MissingUnit);
+        }
+        let scale = match unit {
+            "s" | "sec" | "secs" | "second" | "seconds" => 1.0,
+            "m" | "min" | "mins" | "minute" | "minutes" => MINUTE,
+            "h" | "hr" | "hrs" | "hour" | "hours" => HOUR,
+            "d" | "day" | "days" => DAY,
+            "w" | "wk" | "wks" | "week" | "weeks" => WEEK,
+            _ => return Err(ParseError::UnknownUnit(unit.to_string())),
+        };
+        total += value * scale;
+        rest = after_unit.trim_start();
+    }
+    to_u64(total)
+}
+
+/// Parse a size into bytes: "500" (bytes), "512 KB", "5GB", "4.5 GiB"
+/// KB/MB/GB/TB are 1024-based like the cache thresholds whatever units sizes are
+/// shown in, so "5GB" and "5 GiB" are the same size and a settings file means the
+/// same thing on every machine. Accepts everything `format_size` produces in IEC units.
+pub fn parse_size(input: &str) -> Result<u64, ParseError> {
+    let input = input.trim().to_lowercase();
+    if input.is_empty() {
+        return Err(ParseError::Empty);
+    }
+
+    let (value, after_number) = take_number(&input)?;
+    let (unit, rest) = take_unit(after_number);
+    if !rest.trim().is_empty() {
+        return Err(ParseError::UnknownUnit(after_number.trim().to_string()));
+    }
+    let scale = match unit {
+        "" | "b" | "byte" | "bytes" => 1.0,
+        "k" | "kb" | "kib" => KIB,
+        "m" | "mb" | "mib" => MIB,
+        "g" | "gb" | "gib" => GIB,
+        "t" | "tb" | "tib" => TIB,
+        _ => return Err(ParseError::UnknownUnit(unit.to_string())),
+    };
+    to_u64(value * scale)
+}
+
+/// Split a leading decimal number off `input` (after optional whitespace)
+/// Digits may be grouped with commas ("1,000 days"), as English formatting does.
+fn take_number(input: &str) -> Result<(f64, &str), ParseError> {
+    let input = input.trim_start();
+    let end = input
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+        .unwrap_or(input.len());
+    let (number, rest) = input.split_at(end);
+    if number.is_empty() {
+        let word = input.split_whitespace().next().unwrap_or(input);
+        return Err(ParseError::InvalidNumber(word.to_string()));
+    }
+    let whole = number.split('.').next().unwrap_or(number);
+    let mut groups = whole.split(',');
+    let first_group_ok = groups.next().is_some_and(|group| !group.is_empty() && group.len() <= 3);
+    if whole.contains(',') && !(first_group_ok && groups.all(|group| group.len() == 3)) {
+        return Err(ParseError::InvalidNumber(number.to_string()));
+    }
+    let value = number
+        .replace(',', "")
+        .parse::<f64>()
+        .map_err(|_| ParseError::InvalidNumber(number.to_string()))?;
+    Ok((value, rest))
+}
+
+/// Split a leading unit (letters, after optional whitespace) off `input`
+fn take_unit(input: &str) -> (&str, &str) {
+    let input = input.trim_start();
+    let end = input.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(input.len());
+    input.split_at(end)
+}
+
+fn to_u64(value: f64) -> Result<u64, ParseError> {
+    let rounded = value.round();
+    if !rounded.is_finite() || rounded >= u64::MAX as f64 {
+        return Err(ParseError::TooLarge);
+    }
+    Ok(rounded as u64)
+}
+
+// ============================================================================
+// Formatting
+// ============================================================================
+
+/// Use `units` for every size shown from now on (tray, notifications, clean results, logs)
+pub fn set_size_units(units: SizeUnits) {
+    SIZE_UNITS.store(units as u8, Ordering::Relaxed);
+}
+
+/// Units sizes are currently shown in
+pub fn size_units() -> SizeUnits {
+    match SIZE_UNITS.load(Ordering::Relaxed) {
+        x if x == SizeUnits::Si as u8 => SizeUnits::Si,
+        x if x == SizeUnits::Finder as u8 => SizeUnits::Finder,
+        _ => SizeUnits::Iec,
+    }
+}
+
+/// Write durations in `style` from now on (tray, notifications, last clean)
+pub fn set_duration_style(style: DurationStyle) {
+    DURATION_STYLE.store(style as u8, Ordering::Relaxed);
+}
+
+/// Style durations are currently written in
+pub fn duration_style() -> DurationStyle {
+    match DURATION_STYLE.load(Ordering::Relaxed) {
+        x if x == DurationStyle::Single as u8 => DurationStyle::Single,
+        x if x == DurationStyle::Abbreviated as u8 => DurationStyle::Abbreviated,
+        _ => DurationStyle::Compound,
+    }
+}
+
+/// Format a size in the given unit system, e.g. "1.5 GiB", "1.61 GB", "300 KB"
+pub fn format_size_in(bytes: u64, units: SizeUnits) -> String {
+    let base = units.base();
+    let in_power = |power: usize| {
+        let decimals = units.decimals(power);
+        let scale = 10f64.powi(decimals as i32);
+        ((bytes as f64 / base.powi(power as i32)) * scale).round() / scale
+    };
+
+    let mut power = 0;
+    while power < 4 && bytes as f64 >= base.powi(power as i32 + 1) {
+        power += 1;
+    }
+    let mut value = in_power(power);
+    // 1023.6 KiB rounds to 1024 KiB, which should read 1 MiB
+    if value >= base && power < 4 {
+        power += 1;
+        value = in_power(power);
+    }
+
+    let mut number = format!("{:.*}", units.decimals(power), value);
+    if number.contains('.') {
+        number = number.trim_end_matches('0').trim_end_matches('.').to_string();
+    }
+    let number = localize_decimal(&number);
+    let label = match (units, bytes) {
+        (SizeUnits::Finder, 1) => "byte",
+        _ => units.labels()[power],
+    };
+    format!("{} {}", number, label)
+}
+
+// ============================================================================
+// Serde
+// ============================================================================
+
+/// A settings value written either as a plain number or as text
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NumberOrText {
+    Number(u64),
+    Text(String),
+}
+
+/// Deserialize seconds from a number or a duration string ("6h", "1d12h")
+pub fn deserialize_duration<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    match NumberOrText::deserialize(deserializer)? {
+        NumberOrText::Number(secs) => Ok(secs),
+        NumberOrText::Text(text) => parse_duration(&text).map_err(de::Error::custom),
+    }
+}
+
+/// Deserialize bytes from a number or a size string ("5GB", "4.5 GiB")
+pub fn deserialize_size<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    match NumberOrText::deserialize(deserializer)? {
+        NumberOrText::Number(bytes) => Ok(bytes),
+        NumberOrText::Text(text) => parse_size(&text).map_err(de::Error::custom),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::{format_duration_in, Locale};
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90"), Ok(90));
+        assert_eq!(parse_duration("6h"), Ok(6 * 3600));
+        assert_eq!(parse_duration("90m"), Ok(90 * 60));
+        assert_eq!(parse_duration("1d12h"), Ok(36 * 3600));
+        assert_eq!(parse_duration(" 1d 12h 30m "), Ok(36 * 3600 + 1800));
+        assert_eq!(parse_duration("1.5 hours"), Ok(5400));
+        assert_eq!(parse_duration("2 Days"), Ok(2 * 86400));
+        assert_eq!(parse_duration("1 week"), Ok(7 * 86400));
+        assert_eq!(parse_duration("1 second"), Ok(1));
+        assert_eq!(parse_duration("1,000 days"), Ok(1000 * 86400));
+    }
+
+    #[test]
+    fn test_parse_duration_errors() {
+        assert_eq!(parse_duration(""), Err(ParseError::Empty));
+        assert_eq!(parse_duration("1h30"), Err(ParseError::MissingUnit));
+        assert_eq!(parse_duration("5 fortnights"), Err(ParseError::UnknownUnit("fortnights".to_string())));
+        assert_eq!(parse_duration("soon"), Err(ParseError::InvalidNumber("soon".to_string())));
+        assert_eq!(parse_duration("-5m"), Err(ParseError::InvalidNumber("-5m".to_string())));
+        assert_eq!(parse_duration("1,5h"), Err(ParseError::InvalidNumber("1,5".to_string())));
+        assert_eq!(parse_duration("99999999999999999999w"), Err(ParseError::TooLarge));
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("500"), Ok(500));
+        assert_eq!(parse_size("500 B"), Ok(500));
+        assert_eq!(parse_size("512 KB"), Ok(512 * 1024));
+        assert_eq!(parse_size("5GB"), Ok(5 * 1024 * 1024 * 1024));
+        assert_eq!(parse_size("5 gib"), Ok(5 * 1024 * 1024 * 1024));
+        assert_eq!(parse_size("4.5 GiB"), Ok(4_831_838_208));
+        assert_eq!(parse_size("1 TB"), Ok(1024u64.pow(4)));
+    }
+
+    #[test]
+    fn test_parse_size_reads_every_unit_spelling() {
+        assert_eq!(parse_size("12 bytes"), Ok(12));
+        assert_eq!(parse_size("512k"), Ok(512 * 1024));
+        assert_eq!(parse_size("512 KiB"), Ok(512 * 1024));
+        assert_eq!(parse_size("300m"), Ok(300 * 1024 * 1024));
+        assert_eq!(parse_size("8g"), Ok(8 * 1024 * 1024 * 1024));
+        assert_eq!(parse_size("1 TiB"), Ok(1024u64.pow(4)));
+        assert_eq!(parse_size("2t"), Ok(2 * 1024u64.pow(4)));
+    }
+
+    #[test]
+    fn test_format_size_in_each_system() {
+        let gib = 1024 * 1024 * 1024;
+        assert_eq!(format_size_in(0, SizeUnits::Iec), "0 B");
+        assert_eq!(format_size_in(1024, SizeUnits::Iec), "1 KiB");
+        assert_eq!(format_size_in(999 * 1024 * 1024, SizeUnits::Iec), "999 MiB");
+        assert_eq!(format_size_in(1000 * 1024 * 1024, SizeUnits::Iec), "1000 MiB");
+        assert_eq!(format_size_in(5 * gib, SizeUnits::Iec), "5 GiB");
+        assert_eq!(format_size_in(gib + gib / 2, SizeUnits::Iec), "1.5 GiB");
+
+        assert_eq!(format_size_in(999, SizeUnits::Si), "999 B");
+        assert_eq!(format_size_in(1000, SizeUnits::Si), "1 KB");
+        assert_eq!(format_size_in(5 * gib, SizeUnits::Si), "5.4 GB");
+        assert_eq!(format_size_in(2_000_000_000_000, SizeUnits::Si), "2 TB");
+
+        assert_eq!(format_size_in(1, SizeUnits::Finder), "1 byte");
+        assert_eq!(format_size_in(12, SizeUnits::Finder), "12 bytes");
+        assert_eq!(format_size_in(456_400, SizeUnits::Finder), "456 KB");
+        assert_eq!(format_size_in(7_840_000, SizeUnits::Finder), "7.8 MB");
+        assert_eq!(format_size_in(1_234_000_000, SizeUnits::Finder), "1.23 GB");
+        assert_eq!(format_size_in(5 * gib, SizeUnits::Finder), "5.37 GB");
+    }
+
+    #[test]
+    fn test_rounding_up_moves_to_the_next_unit() {
+        assert_eq!(format_size_in(1024 * 1024 - 1, SizeUnits::Iec), "1 MiB");
+        assert_eq!(format_size_in(999_999, SizeUnits::Si), "1 MB");
+        assert_eq!(format_size_in(999_999_999, SizeUnits::Finder), "1 GB");
+    }
+
+    #[test]
+    fn test_size_units_setting() {
+        assert_eq!(size_units(), SizeUnits::Iec);
+        assert_eq!(serde_json::to_string(&SizeUnits::Finder).unwrap(), "\"Finder\"");
+    }
+
+    #[test]
+    fn test_parse_size_errors() {
+        assert_eq!(parse_size("  "), Err(ParseError::Empty));
+        assert_eq!(parse_size("5 GB free"), Err(ParseError::UnknownUnit("GB free".to_lowercase())));
+        assert_eq!(parse_size("5 PB"), Err(ParseError::UnknownUnit("pb".to_string())));
+        assert_eq!(parse_size("lots"), Err(ParseError::InvalidNumber("lots".to_string())));
+        assert_eq!(parse_size("1.2.3 GB"), Err(ParseError::InvalidNumber("1.2.3".to_string())));
+    }
+
+    #[test]
+    fn test_serde_accepts_numbers_and_text() {
+        #[derive(Deserialize)]
+        struct Example {
+            #[serde(deserialize_with = "deserialize_duration")]
+            interval: u64,
+            #[serde(deserialize_with = "deserialize_size")]
+            threshold: u64,
+        }
+        let parsed: Example = serde_json::from_str(r#"{ "interval": "6h", "threshold": 1024 }"#).unwrap();
+        assert_eq!((parsed.interval, parsed.threshold), (6 * 3600, 1024));
+        let parsed: Example = serde_json::from_str(r#"{ "interval": 60, "threshold": "5GB" }"#).unwrap();
+        assert_eq!((parsed.interval, parsed.threshold), (60, 5 * 1024 * 1024 * 1024));
+        assert!(serde_json::from_str::<Example>(r#"{ "interval": "often", "threshold": 1 }"#).is_err());
+    }
+
+    proptest! {
+        /// Durations show whole units, so parsing one back loses less than the smallest unit shown
+        #[test]
+        fn prop_duration_round_trips_within_display_precision(
+            secs in 0u64..10 * 365 * 86400,
+            style in prop::sample::select(vec![DurationStyle::Single, DurationStyle::Compound, DurationStyle::Abbreviated]),
+        ) {
+            let text = format_duration_in(secs, style, Locale::En);
+            let parsed = parse_duration(&text).unwrap();
+            let largest = match secs {
+                0..=59 => 1,
+                60..=3599 => 60,
+                3600..=86399 => 3600,
+                _ => 86400,
+            };
+            let unit = match (style, largest) {
+                (DurationStyle::Single, _) | (_, 1) => largest,
+                (_, 60) => 1,
+                (_, 3600) => 60,
+                _ => 3600,
+            };
+            prop_assert!(parsed <= secs, "{} -> {} -> {}", secs, text, parsed);
+            prop_assert!(secs - parsed < unit, "{} -> {} -> {}", secs, text, parsed);
+        }
+
+        /// Parsing a size shown in IEC units is off by at most half of the last digit shown
+        #[test]
+        fn prop_size_round_trips_within_display_precision(bytes in 0u64..64 * 1024 * 1024 * 1024 * 1024) {
+            let units = SizeUnits::Iec;
+            let text = format_size_in(bytes, units);
+            let parsed = parse_size(&text).unwrap();
+            let label = text.split_once(' ').unwrap().1;
+            let power = units.labels().iter().position(|l| *l == label).unwrap_or(0);
+            let step = units.base().powi(power as i32) / 10f64.powi(units.decimals(power) as i32);
+            prop_assert!(parsed.abs_diff(bytes) as f64 <= step / 2.0 + 1.0, "{} -> {} -> {}", bytes, text, parsed);
+        }
+
+        /// Compound durations are the sum of their parts
+        #[test]
+        fn prop_compound_durations_add_up(d in 0u64..400, h in 0u64..24, m in 0u64..60, s in 0u64..60) {
+            let text = format!("{}d {}h {}m {}s", d, h, m, s);
+            prop_assert_eq!(parse_duration(&text).unwrap(), d * 86400 + h * 3600 + m * 60 + s);
+        }
+
+        /// Whole numbers of any unit parse exactly
+        #[test]
+        fn prop_whole_sizes_are_exact(n in 0u64..1024, unit in 0usize..5) {
+            let (suffix, scale) = [("B", 1u64), ("KB", 1 << 10), ("MB", 1 << 20), ("GiB", 1 << 30), ("TB", 1 << 40)][unit];
+            prop_assert_eq!(parse_size(&format!("{} {}", n, suffix)).unwrap(), n * scale);
+        }
+    }
+}
@@ -1,17 +1,26 @@
 import { useState, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { isPermissionGranted, requestPermission, sendNotification } from '@tauri-apps/plugin-notification';
-import { useSettings } from '../hooks/useSettings';
+import { isSettingsError, settingsErrorMessage, useSettings } from '../hooks/useSettings';
+import { useProfiles } from '../hooks/useProfiles';
+import { useFormattedSizes } from '../hooks/useFormattedSizes';
 import { WARNING_THRESHOLD, CRITICAL_THRESHOLD, DEBUG_SIZES } from '../types';
+import type { DurationStyle, SizeUnits } from '../types';
 import './SettingsPanel.css';
 
 interface SettingsPanelProps {
   onBack: () => void;
 }
 
-function formatBytes(bytes: number): string {
-  const gb = bytes / (1024 * 1024 * 1024);
-  return `${gb.toFixed(0)}GB`;
+/** Bytes in one GB (GiB for Iec) */
+function gigabyte(units: SizeUnits): number {
+  return units === 'Iec' ? 1024 ** 3 : 1000 ** 3;
+}
+
+function thresholdLabel(bytes: number, size: string): string {
+  if (bytes === WARNING_THRESHOLD) return `${size} (Warning)`;
+  if (bytes === CRITICAL_THRESHOLD) return `${size} (Critical)`;
+  return size;
 }
 
 function formatInterval(secs: number): string {
@@ -22,11 +31,40 @@ function formatInterval(secs: number): string {
   return `${hours} hours`;
 }
 
+/** Whole-unit thresholds offered in the chosen unit system */
+function thresholdOptions(units: SizeUnits): number[] {
+  return [2, 5, 7, 10].map((n) => n * gigabyte(units));
+}
+
 export function SettingsPanel({ onBack }: SettingsPanelProps) {
-  const { settings, loading, saving, updateSetting } = useSettings();
+  const { settings, loading, saving, error, recovery, policy, isLocked, updateSetting } = useSettings();
+  const { profiles, switchProfile, saveProfile, deleteProfile, exportSettings, importSettings } = useProfiles();
+  const [profileName, setProfileName] = useState('');
+  const [importPath, setImportPath] = useState('');
+  const [profileMessage, setProfileMessage] = useState<string | null>(null);
   const [debugUnlocked, setDebugUnlocked] = useState(false);
   const [tapCount, setTapCount] = useState(0);
   const tapTimeoutRef = useRef<ReturnType<typeof setTimeout> | null>(null);
+  const formatSize = useFormattedSizes(
+    [
+      settings.auto_clean_threshold,
+      ...thresholdOptions(settings.size_units),
+      WARNING_THRESHOLD,
+      DEBUG_SIZES.SMALL,
+      DEBUG_SIZES.WARNING,
+      DEBUG_SIZES.CRITICAL,
+    ],
+    settings.size_units
+  );
+
+  // Run a profile action, showing its result or error under the Profiles heading
+  const runProfileAction = async (action: () => Promise<string>) => {
+    try {
+      setProfileMessage(await action());
+    } catch (err) {
+      setProfileMessage(isSettingsError(err) ? settingsErrorMessage(err) : String(err));
+    }
+  };
 
   const handleVersionTap = () => {
     if (debugUnlocked) return;
@@ -71,6 +109,23 @@ export function SettingsPanel({ onBack }: SettingsPanelProps) {
       </header>
 
       <div className="settings-content">
+        {recovery && (
+          <div className="settings-notice">
+            {recovery.restored_from
+              ? 'Your settings file was damaged and has been restored from a backup.'
+              : 'Your settings file was damaged and has been reset to defaults.'}
+          </div>
+        )}
+        {error && <div className="settings-notice">{error}</div>}
+        {policy && policy.locked.length > 0 && (
+          <div className="settings-notice">Some settings are managed by your organization.</div>
+        )}
+        {policy && policy.reset.length > 0 && (
+          <div className="settings-notice">
+            Reset to defaults to fit your organization's settings: {policy.reset.join(', ')}
+          </div>
+        )}
+
         <section className="settings-section">
           <h2>Auto-Clean</h2>
 
@@ -78,7 +133,7 @@ export function SettingsPanel({ onBack }: SettingsPanelProps) {
             <div className="setting-info">
               <label htmlFor="auto-threshold">Clean when cache exceeds threshold</label>
               <span className="setting-description">
-                Automatically clean when cache reaches {formatBytes(settings.auto_clean_threshold)}
+                Automatically clean when cache reaches {formatSize(settings.auto_clean_threshold)}
               </span>
             </div>
             <label className="toggle">
@@ -87,7 +142,7 @@ export function SettingsPanel({ onBack }: SettingsPanelProps) {
                 id="auto-threshold"
                 checked={settings.auto_clean_on_threshold}
                 onChange={(e) => updateSetting('auto_clean_on_threshold', e.target.checked)}
-                disabled={saving}
+                disabled={saving || isLocked('auto_clean_on_threshold')}
               />
               <span className="toggle-slider" />
             </label>
@@ -100,12 +155,17 @@ export function SettingsPanel({ onBack }: SettingsPanelProps) {
                 id="threshold-select"
                 value={settings.auto_clean_threshold}
                 onChange={(e) => updateSetting('auto_clean_threshold', Number(e.target.value))}
-                disabled={saving}
+                disabled={saving || isLocked('auto_clean_threshold')}
               >
-                <option value={2 * 1024 * 1024 * 1024}>2GB</option>
-                <option value={WARNING_THRESHOLD}>5GB (Warning)</option>
-                <option value={7 * 1024 * 1024 * 1024}>7GB</option>
-                <option value={CRITICAL_THRESHOLD}>10GB (Critical)</option>
+                {!thresholdOptions(settings.size_units).includes(settings.auto_clean_threshold) && (
+                  // Set by a policy, settings.json edit or another unit system rather than picked here
+                  <option value={settings.auto_clean_threshold}>
+                    {thresholdLabel(settings.auto_clean_threshold, formatSize(settings.auto_clean_threshold))}
+                  </option>
+                )}
+                {thresholdOptions(settings.size_units).map((bytes) => (
+                  <option key={bytes} value={bytes}>{thresholdLabel(bytes, formatSize(bytes))}</option>
+                ))}
               </select>
             </div>
           )}
@@ -123,7 +183,7 @@ export function SettingsPanel({ onBack }: SettingsPanelProps) {
                 id="auto-scheduled"
                 checked={settings.auto_clean_scheduled}
                 onChange={(e) => updateSetting('auto_clean_scheduled', e.target.checked)}
-                disabled={saving}
+                disabled={saving || isLocked('auto_clean_scheduled')}
               />
               <span className="toggle-slider" />
             </label>
@@ -136,7 +196,7 @@ export function SettingsPanel({ onBack }: SettingsPanelProps) {
                 id="interval-select"
                 value={settings.auto_clean_interval_secs}
                 onChange={(e) => updateSetting('auto_clean_interval_secs', Number(e.target.value))}
-                disabled={saving}
+                disabled={saving || isLocked('auto_clean_interval_secs')}
               >
                 {settings.debug_mode && (
                   <option value={2 * 60}>2 minutes (debug)</option>
@@ -189,7 +249,7 @@ export function SettingsPanel({ onBack }: SettingsPanelProps) {
                     }
                   }
                 }}
-                disabled={saving}
+                disabled={saving || isLocked('show_notifications')}
               />
               <span className="toggle-slider" />
             </label>
@@ -228,7 +288,7 @@ export function SettingsPanel({ onBack }: SettingsPanelProps) {
                 id="launch-login"
                 checked={settings.launch_at_login}
                 onChange={(e) => updateSetting('launch_at_login', e.target.checked)}
-                disabled={saving}
+                disabled={saving || isLocked('launch_at_login')}
               />
               <span className="toggle-slider" />
             </label>
@@ -245,7 +305,7 @@ export function SettingsPanel({ onBack }: SettingsPanelProps) {
               id="monitor-interval"
               value={settings.monitor_interval_secs}
               onChange={(e) => updateSetting('monitor_interval_secs', Number(e.target.value))}
-              disabled={saving}
+              disabled={saving || isLocked('monitor_interval_secs')}
             >
               <option value={30}>30 seconds</option>
               <option value={60}>1 minute</option>
@@ -253,6 +313,142 @@ export function SettingsPanel({ onBack }: SettingsPanelProps) {
               <option value={600}>10 minutes</option>
             </select>
           </div>
+
+          <div className="setting-row">
+            <div className="setting-info">
+              <label htmlFor="size-units">Size units</label>
+              <span className="setting-description">
+                {formatSize(WARNING_THRESHOLD)} is the warning level
+              </span>
+            </div>
+            <select
+              id="size-units"
+              value={settings.size_units}
+              onChange={(e) => updateSetting('size_units', e.target.value as SizeUnits)}
+              disabled={saving || isLocked('size_units')}
+            >
+              <option value="Iec">Binary (GiB)</option>
+              <option value="Si">Decimal (GB)</option>
+              <option value="Finder">Finder style</option>
+            </select>
+          </div>
+
+          <div className="setting-row">
+            <div className="setting-info">
+              <label htmlFor="duration-style">Time format</label>
+              <span className="setting-description">
+                How the menu bar and notifications show how long ago or until
+              </span>
+            </div>
+            <select
+              id="duration-style"
+              value={settings.duration_style}
+              onChange={(e) => updateSetting('duration_style', e.target.value as DurationStyle)}
+              disabled={saving || isLocked('duration_style')}
+            >
+              <option value="Compound">1 day 23 hours</option>
+              <option value="Abbreviated">1d 23h</option>
+              <option value="Single">1 day</option>
+            </select>
+          </div>
+        </section>
+
+        <section className="settings-section">
+          <h2>Profiles</h2>
+
+          {profileMessage && <div className="profile-message">{profileMessage}</div>}
+
+          <div className="setting-row">
+            <div className="setting-info">
+              <label htmlFor="profile-select">Switch profile</label>
+              <span className="setting-description">
+                Replaces your settings, except launch at login
+              </span>
+            </div>
+            <select
+              id="profile-select"
+              value=""
+              onChange={(e) => {
+                const name = e.target.value;
+                runProfileAction(async () => {
+                  await switchProfile(name);
+                  return `Switched to ${name}`;
+                });
+              }}
+              disabled={saving}
+            >
+              <option value="" disabled>
+                Choose…
+              </option>
+              {profiles.map((profile) => (
+                <option key={profile.name} value={profile.name}>
+                  {profile.name}
+                </option>
+              ))}
+            </select>
+          </div>
+
+          <div className="setting-row profile-actions">
+            <input
+              type="text"
+              placeholder="Profile name"
+              value={profileName}
+              onChange={(e) => setProfileName(e.target.value)}
+            />
+            <button
+              className="profile-btn"
+              disabled={!profileName.trim()}
+              onClick={() =>
+                runProfileAction(async () => {
+                  await saveProfile(profileName);
+                  setProfileName('');
+                  return `Saved ${profileName.trim()}`;
+                })
+              }
+            >
+              Save Current
+            </button>
+            <button
+              className="profile-btn"
+              disabled={!profiles.some((p) => !p.builtin && p.name.toLowerCase() === profileName.trim().toLowerCase())}
+              onClick={() =>
+                runProfileAction(async () => {
+                  await deleteProfile(profileName);
+                  setProfileName('');
+                  return `Deleted ${profileName.trim()}`;
+                })
+              }
+            >
+              Delete
+            </button>
+          </div>
+
+          <div className="setting-row profile-actions">
+            <input
+              type="text"
+              placeholder="Path to exported settings"
+              value={importPath}
+              onChange={(e) => setImportPath(e.target.value)}
+            />
+            <button
+              className="profile-btn"
+              disabled={!importPath.trim()}
+              onClick={() =>
+                runProfileAction(async () => {
+                  await importSettings(importPath.trim());
+                  return 'Imported settings';
+                })
+              }
+            >
+              Import
+            </button>
+            <button
+              className="profile-btn"
+              onClick={() => runProfileAction(async () => `Exported to ${await exportSettings()}`)}
+            >
+              Export
+            </button>
+          </div>
         </section>
 
         {debugUnlocked && (
@@ -294,21 +490,21 @@ export function SettingsPanel({ onBack }: SettingsPanelProps) {
                     onClick={() => updateSetting('debug_simulated_size', DEBUG_SIZES.SMALL)}
                     disabled={saving}
                   >
-                    3GB
+                    {formatSize(DEBUG_SIZES.SMALL)}
                   </button>
                   <button
                     className={`debug-btn warning ${settings.debug_simulated_size === DEBUG_SIZES.WARNING ? 'active' : ''}`}
                     onClick={() => updateSetting('debug_simulated_size', DEBUG_SIZES.WARNING)}
                     disabled={saving}
                   >
-                    7GB
+                    {formatSize(DEBUG_SIZES.WARNING)}
                   </button>
                   <button
                     className={`debug-btn critical ${settings.debug_simulated_size === DEBUG_SIZES.CRITICAL ? 'active' : ''}`}
                     onClick={() => updateSetting('debug_simulated_size', DEBUG_SIZES.CRITICAL)}
                     disabled={saving}
                   >
-                    15GB
+                    {formatSize(DEBUG_SIZES.CRITICAL)}
                   </button>
                 </div>
                 <button
@@ -2,7 +2,7 @@ import { useState, useEffect } from 'react';
 import { useCacheStatus, useCleanCache, useLastCleanTime } from '../hooks/useCacheStatus';
 import { useSettings } from '../hooks/useSettings';
 import { CleanConfirmation } from './CleanConfirmation';
-import type { CacheState, CleanResult } from '../types';
+import type { CacheState, CleanResult, DeferralReason } from '../types';
 import './StatusPanel.css';
 
 interface StatusIndicatorProps {
@@ -34,6 +34,40 @@ function StatusIndicator({ state, size, isLoading }: StatusIndicatorProps) {
   );
 }
 
+function formatNextClean(timestamp: number): string {
+  if (timestamp * 1000 <= Date.now()) {
+    return 'Now';
+  }
+  return new Date(timestamp * 1000).toLocaleString([], {
+    weekday: 'short',
+    hour: '2-digit',
+    minute: '2-digit',
+  });
+}
+
+function formatInterval(secs: number): string {
+  return secs < 60 ? `${secs} sec` : `${Math.round(secs / 60)} min`;
+}
+
+function describeDeferral(reason: DeferralReason): string {
+  if (reason === 'OnBattery') {
+    return 'Auto-clean waiting for AC power';
+  }
+  if (reason === 'QuietHours') {
+    return 'Auto-clean waiting for quiet hours to end';
+  }
+  if (reason === 'Presenting') {
+    return 'Auto-clean waiting for the presentation to end';
+  }
+  if ('WakeGrace' in reason) {
+    return `Auto-clean waiting until ${new Date(reason.WakeGrace.until * 1000).toLocaleTimeString()} after wake`;
+  }
+  if ('NotIdle' in reason) {
+    return `Auto-clean waiting for ${Math.round(reason.NotIdle.required_secs / 60)} min of inactivity`;
+  }
+  return `Auto-clean waiting for ${reason.BusyProcesses.names.join(', ')} to exit`;
+}
+
 interface StatusPanelProps {
   onSettingsClick: () => void;
 }
@@ -193,6 +227,23 @@ export function StatusPanel({ onSettingsClick }: StatusPanelProps) {
               )}
             </span>
           </div>
+          {status.auto_clean?.next_scheduled_clean != null && (
+            <div className="detail-row">
+              <span className="detail-label">Next clean</span>
+              <span className="detail-value">
+                {formatNextClean(status.auto_clean.next_scheduled_clean)}
+              </span>
+            </div>
+          )}
+          {status.monitor != null && status.monitor.reason !== 'Fixed' && status.monitor.interval_secs > 0 && (
+            <div className="detail-row">
+              <span className="detail-label">Checking every</span>
+              <span className="detail-value">
+                {formatInterval(status.monitor.interval_secs)}
+                {status.monitor.reason === 'OnBattery' ? ' (battery)' : ''}
+              </span>
+            </div>
+          )}
         </div>
 
         {showBanner && cleanResult && !cleanResult.was_dry_run && cleanResult.files_removed > 0 && (
@@ -223,6 +274,16 @@ export function StatusPanel({ onSettingsClick }: StatusPanelProps) {
         {status.state === 'Critical' && (
           <p className="critical-text">Cache critically large – clean now!</p>
         )}
+
+        {status.auto_clean?.deferral && (
+          <p className="warning-text">{describeDeferral(status.auto_clean.deferral.reason)}</p>
+        )}
+
+        {status.auto_clean?.backoff.paused && (
+          <p className="warning-text">
+            Auto-clean paused after {status.auto_clean.backoff.consecutive_failures} failures
+          </p>
+        )}
       </div>
     </div>
   );
@@ -0,0 +1,24 @@
+import { useState, useEffect, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import type { SizeUnits } from '../types';
+
+/**
+ * Sizes formatted by the backend, so they read exactly like the tray and
+ * notifications (unit labels, precision and the locale's decimal separator)
+ */
+export function useFormattedSizes(sizes: number[], units: SizeUnits) {
+  const [labels, setLabels] = useState<Record<number, string>>({});
+  const key = sizes.join(',');
+
+  useEffect(() => {
+    const requested = key.split(',').map(Number);
+    invoke<string[]>('format_sizes', { sizes: requested, units })
+      .then((formatted) => {
+        setLabels(Object.fromEntries(requested.map((bytes, i) => [bytes, formatted[i]])));
+      })
+      .catch((err) => console.error('Failed to format sizes:', err));
+  }, [key, units]);
+
+  // Plain bytes until the backend answers
+  return useCallback((bytes: number) => labels[bytes] ?? `${bytes} B`, [labels]);
+}
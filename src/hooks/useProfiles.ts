@@ -0,0 +1,59 @@
+import { useState, useEffect, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import type { ProfileInfo } from '../types';
+
+export function useProfiles() {
+  const [profiles, setProfiles] = useState<ProfileInfo[]>([]);
+
+  const fetchProfiles = useCallback(async () => {
+    try {
+      setProfiles(await invoke<ProfileInfo[]>('list_profiles'));
+    } catch (err) {
+      console.error('Failed to list profiles:', err);
+    }
+  }, []);
+
+  // Settings changes arrive through the 'settings-updated' event in useSettings
+  const switchProfile = useCallback(async (name: string) => {
+    await invoke('switch_profile', { name });
+  }, []);
+
+  const saveProfile = useCallback(
+    async (name: string) => {
+      await invoke('save_profile', { name });
+      await fetchProfiles();
+    },
+    [fetchProfiles]
+  );
+
+  const deleteProfile = useCallback(
+    async (name: string) => {
+      await invoke('delete_profile', { name });
+      await fetchProfiles();
+    },
+    [fetchProfiles]
+  );
+
+  // Returns the path the settings were written to
+  const exportSettings = useCallback(async (path?: string) => {
+    return invoke<string>('export_settings', { path: path ?? null });
+  }, []);
+
+  const importSettings = useCallback(async (path: string) => {
+    await invoke('import_settings', { path });
+  }, []);
+
+  useEffect(() => {
+    fetchProfiles();
+  }, [fetchProfiles]);
+
+  return {
+    profiles,
+    switchProfile,
+    saveProfile,
+    deleteProfile,
+    exportSettings,
+    importSettings,
+    refresh: fetchProfiles,
+  };
+}
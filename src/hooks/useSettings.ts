@@ -1,33 +1,105 @@
 import { useState, useEffect, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
-import type { Settings } from '../types';
+import type { FieldError, ManagedPolicy, Settings, SettingsError, SettingsRecovery } from '../types';
 
 const defaultSettings: Settings = {
+  schema_version: 1,
   auto_clean_on_threshold: false,
   auto_clean_threshold: 5 * 1024 * 1024 * 1024, // 5GB
   auto_clean_scheduled: false,
   auto_clean_interval_secs: 6 * 60 * 60, // 6 hours
+  auto_clean_schedule: { kind: 'Interval' },
+  schedule_time_zone: null,
   show_notifications: true,
+  size_units: 'Iec',
+  duration_style: 'Compound',
+  quiet_hours_enabled: false,
+  quiet_hours_start: { hour: 22, minute: 0 },
+  quiet_hours_end: { hour: 8, minute: 0 },
+  presenting_mode: false,
+  quiet_defers_auto_clean: false,
   launch_at_login: false,
   last_clean_timestamp: 0,
   monitor_interval_secs: 60,
+  adaptive_interval: false,
+  min_monitor_interval_secs: 15,
+  max_monitor_interval_secs: 10 * 60, // 10 minutes
   debug_mode: false,
   debug_simulated_size: 0,
   first_run_completed: false,
   first_clean_confirmed: false,
+  runaway_detection_enabled: true,
+  runaway_cpu_percent: 80,
+  runaway_cpu_duration_secs: 30 * 60, // 30 minutes
+  runaway_growth_bytes: 1024 * 1024 * 1024, // 1GB
+  runaway_growth_window_secs: 10 * 60, // 10 minutes
+  runaway_auto_remediate: false,
+  auto_clean_max_failures: 3,
+  auto_clean_backoff: {
+    consecutive_failures: 0,
+    next_attempt_timestamp: 0,
+    paused: false,
+    last_error: null,
+  },
+  defer_while_busy: true,
+  busy_process_names: ['Xcode', 'lldb', 'debugserver', 'Instruments'],
+  busy_max_defer_secs: 4 * 60 * 60, // 4 hours
+  require_idle: false,
+  require_idle_secs: 10 * 60, // 10 minutes
+  require_ac_power: false,
+  wake_grace_secs: 5 * 60, // 5 minutes
+  missed_clean_policy: 'CatchUp',
+  warning_reminder_secs: 24 * 60 * 60, // 1 day
+  critical_reminder_secs: 4 * 60 * 60, // 4 hours
+  notification_snooze_secs: 4 * 60 * 60, // 4 hours
+  threshold_alerts: {
+    notified: null,
+    last_notified_at: 0,
+    snoozed_until: 0,
+  },
 };
 
+function fieldErrorMessage(error: FieldError): string {
+  switch (error.kind) {
+    case 'OutOfRange':
+      return `${error.field} must be between ${error.min} and ${error.max}`;
+    case 'NotAtMost':
+      return `${error.field} must not be more than ${error.other}`;
+    case 'AboveLimit':
+      return `${error.field} must not be more than ${error.limit}`;
+    case 'Invalid':
+      return `${error.field}: ${error.reason}`;
+    case 'Requires':
+      return `${error.field} requires ${error.other}`;
+    case 'Locked':
+      return `${error.field} is managed by your organization`;
+  }
+}
+
+export function settingsErrorMessage(error: SettingsError): string {
+  return error.kind === 'Invalid' ? error.errors.map(fieldErrorMessage).join('; ') : error.message;
+}
+
+export function isSettingsError(err: unknown): err is SettingsError {
+  return typeof err === 'object' && err !== null && 'kind' in err;
+}
+
 export function useSettings() {
   const [settings, setSettings] = useState<Settings>(defaultSettings);
   const [loading, setLoading] = useState(true);
   const [saving, setSaving] = useState(false);
   const [error, setError] = useState<string | null>(null);
+  const [fieldErrors, setFieldErrors] = useState<FieldError[]>([]);
+  const [recovery, setRecovery] = useState<SettingsRecovery | null>(null);
+  const [policy, setPolicy] = useState<ManagedPolicy | null>(null);
 
   const fetchSettings = useCallback(async () => {
     try {
       const result = await invoke<Settings>('get_settings');
       setSettings(result);
+      setRecovery(await invoke<SettingsRecovery | null>('get_settings_recovery'));
+      setPolicy(await invoke<ManagedPolicy>('get_managed_policy'));
       setError(null);
     } catch (err) {
       setError(err instanceof Error ? err.message : String(err));
@@ -39,13 +111,18 @@ export function useSettings() {
   const updateSettings = useCallback(async (newSettings: Settings) => {
     setSaving(true);
     setError(null);
+    setFieldErrors([]);
 
     try {
       await invoke('update_settings', { settings: newSettings });
       setSettings(newSettings);
     } catch (err) {
-      const errorMsg = err instanceof Error ? err.message : String(err);
-      setError(errorMsg);
+      if (isSettingsError(err)) {
+        setFieldErrors(err.kind === 'Invalid' ? err.errors : []);
+        setError(`Couldn't save settings: ${settingsErrorMessage(err)}`);
+      } else {
+        setError(err instanceof Error ? err.message : String(err));
+      }
       throw err;
     } finally {
       setSaving(false);
@@ -60,6 +137,11 @@ export function useSettings() {
     [settings, updateSettings]
   );
 
+  const isLocked = useCallback(
+    (key: keyof Settings) => policy?.locked.includes(key) ?? false,
+    [policy]
+  );
+
   useEffect(() => {
     fetchSettings();
 
@@ -68,8 +150,20 @@ export function useSettings() {
       setSettings(event.payload);
     });
 
+    // settings.json was edited outside the app and the edit couldn't be applied
+    const unlistenReloadFailed = listen<SettingsError>('settings-reload-failed', (event) => {
+      setError(`Ignored an edit to settings.json: ${settingsErrorMessage(event.payload)}`);
+    });
+
+    // Settings file was corrupt at launch and restored from a backup (or reset)
+    const unlistenRecovered = listen<SettingsRecovery>('settings-recovered', (event) => {
+      setRecovery(event.payload);
+    });
+
     return () => {
       unlisten.then((fn) => fn());
+      unlistenReloadFailed.then((fn) => fn());
+      unlistenRecovered.then((fn) => fn());
     };
   }, [fetchSettings]);
 
@@ -78,6 +172,10 @@ export function useSettings() {
     loading,
     saving,
     error,
+    fieldErrors,
+    recovery,
+    policy,
+    isLocked,
     updateSettings,
     updateSetting,
     refresh: fetchSettings,
@@ -2,6 +2,41 @@
 
 export type CacheState = 'Normal' | 'Warning' | 'Critical';
 
+export interface AutoCleanBackoff {
+  consecutive_failures: number;
+  next_attempt_timestamp: number;
+  paused: boolean;
+  last_error: string | null;
+}
+
+export type DeferralReason =
+  | { BusyProcesses: { names: string[] } }
+  | 'OnBattery'
+  | { NotIdle: { required_secs: number } }
+  | 'QuietHours'
+  | 'Presenting'
+  | { WakeGrace: { until: number } };
+
+export interface AutoCleanDeferral {
+  reason: DeferralReason;
+  since: number;
+  until: number | null;
+}
+
+export interface AutoCleanStatus {
+  backoff: AutoCleanBackoff;
+  next_scheduled_clean: number | null;
+  deferral: AutoCleanDeferral | null;
+  last_clean: number | null;
+}
+
+export type IntervalReason = 'Fixed' | 'Steady' | 'Growing' | 'OnBattery';
+
+export interface MonitorStatus {
+  interval_secs: number;
+  reason: IntervalReason;
+}
+
 export interface CacheStatus {
   size_bytes: number;
   size_display: string;
@@ -10,6 +45,46 @@ export interface CacheStatus {
   exists: boolean;
   file_count: number;
   last_checked: number;
+  auto_clean: AutoCleanStatus;
+  monitor: MonitorStatus;
+}
+
+export interface ThresholdAlerts {
+  notified: CacheState | null;
+  last_notified_at: number;
+  snoozed_until: number;
+}
+
+export interface ProcessInfo {
+  pid: number;
+  start_time: number;
+  uptime_secs: number;
+  resident_bytes: number;
+  cpu_time_secs: number;
+  cpu_percent: number;
+}
+
+export interface DaemonRestart {
+  timestamp: number;
+  old_pid: number | null;
+  new_pid: number;
+}
+
+export interface DaemonInfo {
+  running: boolean;
+  process: ProcessInfo | null;
+  restarts: DaemonRestart[];
+}
+
+export type RunawayReason =
+  | { HighCpu: { cpu_percent: number; duration_secs: number } }
+  | { RapidGrowth: { bytes: number; window_secs: number } };
+
+export interface RunawayEvent {
+  timestamp: number;
+  reason: RunawayReason;
+  pid: number | null;
+  cache_size_bytes: number;
 }
 
 export interface DeletionItem {
@@ -31,21 +106,112 @@ export interface CleanResult {
   items_found: DeletionItem[];
 }
 
+export type MissedCleanPolicy = 'CatchUp' | 'Skip';
+
+export interface TimeOfDay {
+  hour: number;
+  minute: number;
+}
+
+/** Bit 0 = Monday through bit 6 = Sunday */
+export type WeekdayMask = number;
+
+export type CleanSchedule =
+  | { kind: 'Interval' }
+  | { kind: 'Daily'; times: TimeOfDay[]; weekdays: WeekdayMask }
+  | { kind: 'Cron'; expression: string };
+
+/** Si = 1000-based GB, Iec = 1024-based GiB, Finder = decimal with Finder's precision */
+export type SizeUnits = 'Si' | 'Iec' | 'Finder';
+
+/** Single = "1 day", Compound = "1 day 23 hours", Abbreviated = "1d 23h" */
+export type DurationStyle = 'Single' | 'Compound' | 'Abbreviated';
+
 export interface Settings {
+  schema_version: number;
   auto_clean_on_threshold: boolean;
   auto_clean_threshold: number;
   auto_clean_scheduled: boolean;
   auto_clean_interval_secs: number;
+  auto_clean_schedule: CleanSchedule;
+  schedule_time_zone: string | null;
   show_notifications: boolean;
+  size_units: SizeUnits;
+  duration_style: DurationStyle;
+  quiet_hours_enabled: boolean;
+  quiet_hours_start: TimeOfDay;
+  quiet_hours_end: TimeOfDay;
+  presenting_mode: boolean;
+  quiet_defers_auto_clean: boolean;
   launch_at_login: boolean;
   last_clean_timestamp: number;
   monitor_interval_secs: number;
+  adaptive_interval: boolean;
+  min_monitor_interval_secs: number;
+  max_monitor_interval_secs: number;
   debug_mode: boolean;
   debug_simulated_size: number;
   first_run_completed: boolean;
   first_clean_confirmed: boolean;
+  runaway_detection_enabled: boolean;
+  runaway_cpu_percent: number;
+  runaway_cpu_duration_secs: number;
+  runaway_growth_bytes: number;
+  runaway_growth_window_secs: number;
+  runaway_auto_remediate: boolean;
+  auto_clean_max_failures: number;
+  auto_clean_backoff: AutoCleanBackoff;
+  defer_while_busy: boolean;
+  busy_process_names: string[];
+  busy_max_defer_secs: number;
+  require_idle: boolean;
+  require_idle_secs: number;
+  require_ac_power: boolean;
+  wake_grace_secs: number;
+  missed_clean_policy: MissedCleanPolicy;
+  warning_reminder_secs: number;
+  critical_reminder_secs: number;
+  notification_snooze_secs: number;
+  threshold_alerts: ThresholdAlerts;
 }
 
+export interface SettingsRecovery {
+  reason: string;
+  corrupt_copy: string | null;
+  restored_from: string | null;
+}
+
+// Settings locked by the system-wide policy file
+export interface ManagedPolicy {
+  path: string;
+  locked: string[];
+  error: string | null;
+  // Saved settings reset to their defaults because they clashed with a locked value
+  reset: string[];
+}
+
+// A built-in or saved settings profile
+export interface ProfileInfo {
+  name: string;
+  builtin: boolean;
+}
+
+// A problem with one settings field
+export type FieldError =
+  | { kind: 'OutOfRange'; field: string; min: number; max: number }
+  | { kind: 'NotAtMost'; field: string; other: string }
+  | { kind: 'AboveLimit'; field: string; limit: number }
+  | { kind: 'Invalid'; field: string; reason: string }
+  | { kind: 'Requires'; field: string; other: string }
+  | { kind: 'Locked'; field: string };
+
+// Why update_settings rejected a change
+export type SettingsError =
+  | { kind: 'Invalid'; errors: FieldError[] }
+  | { kind: 'ReadOnly'; message: string }
+  | { kind: 'SaveFailed'; message: string }
+  | { kind: 'Unreadable'; message: string };
+
 // Debug preset sizes
 export const DEBUG_SIZES = {
   EMPTY: 0,